@@ -0,0 +1,472 @@
+//! `frustum` — render Frustum scene files from the command line.
+//!
+//! Exit status is 0 on success, 1 when a check fails (invalid scene,
+//! invariant errors in the audit, regression mismatch), and 2 for usage,
+//! I/O and rendering errors.
+
+mod png;
+mod settings;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use frustum_core::scene::{Scene, SceneElement};
+use frustum_core::{SceneError, ValidationErrors, BINARY_MAGIC};
+use frustum_render::audit::{AuditBundle, OverallStatus};
+use frustum_render::invariants::check_all_invariants;
+use frustum_render::metrics::compute_image_metrics;
+use frustum_render::{compare_for_regression, render_to_png, render_with_audit, RegressionTolerance, RenderError};
+use settings::{parse_color, RenderSettings};
+use thiserror::Error;
+
+const USAGE: &str = "\
+Usage: frustum <command> [options]
+
+Commands:
+  validate <scene.json>                     Check a scene file for errors
+  render <scene.json> -o <out.png>          Render a scene to PNG
+         [--audit <audit.json>]             Also write the audit bundle
+  audit <scene.json> [-o <audit.json>]      Render and run invariant checks
+        [--png <out.png>]                   Also write the rendered image
+  compare <baseline.json> <current.json>    Compare two audit bundles
+  convert <in> <out>                        Convert a scene between JSON and
+                                            binary (.json output is JSON)
+
+Scene files may be JSON or the binary container format.
+
+Render options (render, audit):
+  --config <settings.json>   Load width, height, background, dpi, antialias
+  --width <px>               Output width (default 800)
+  --height <px>              Output height (default 600)
+  --background <r,g,b[,a]>   Background color, components in [0, 1]
+  --dpi <dpi>                Physical resolution written to the PNG
+  --antialias <n>            Supersampling factor, 1 to 8 (default 1)
+
+Compare options:
+  --depth-tolerance <f>      (default 0.01)
+  --histogram-tolerance <f>  (default 0.05)
+  --edge-tolerance <f>       (default 0.1)
+  --background-tolerance <f> (default 5.0)
+
+  -h, --help                 Print this help
+  -V, --version              Print the version";
+
+/// Errors that abort a command before it can produce a result.
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("{}: {source}", path.display())]
+    Json { path: PathBuf, source: serde_json::Error },
+    #[error("{}: {source}", path.display())]
+    Scene { path: PathBuf, source: SceneError },
+    #[error("{}: {errors}", path.display())]
+    InvalidScene { path: PathBuf, errors: ValidationErrors },
+    #[error("render failed: {0}")]
+    Render(#[from] RenderError),
+    #[error("image processing failed: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+/// Result of a command that ran to completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Success,
+    CheckFailed,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(args) {
+        Ok(Outcome::Success) => ExitCode::SUCCESS,
+        Ok(Outcome::CheckFailed) => ExitCode::from(1),
+        Err(e @ CliError::InvalidScene { .. }) => {
+            eprintln!("{e}");
+            ExitCode::from(1)
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<Outcome, CliError> {
+    let mut args = Args::new(args);
+    let Some(command) = args.next() else {
+        return Err(CliError::Usage("missing command".to_string()));
+    };
+    match command.as_str() {
+        "-h" | "--help" | "help" => {
+            println!("{USAGE}");
+            Ok(Outcome::Success)
+        }
+        "-V" | "--version" => {
+            println!("frustum {}", env!("CARGO_PKG_VERSION"));
+            Ok(Outcome::Success)
+        }
+        "validate" => validate(args),
+        "render" => render(args),
+        "audit" => audit(args),
+        "compare" => compare(args),
+        "convert" => convert(args),
+        other => Err(CliError::Usage(format!("unknown command '{other}'"))),
+    }
+}
+
+fn validate(mut args: Args) -> Result<Outcome, CliError> {
+    let path = args.positional("scene file")?;
+    args.finish()?;
+
+    let scene = match parse_scene(&read_bytes(&path)?) {
+        Ok(scene) => scene,
+        Err(e) => {
+            println!("{}: invalid scene: {e}", path.display());
+            return Ok(Outcome::CheckFailed);
+        }
+    };
+    if let Err(errors) = scene.validate() {
+        println!("{}: invalid scene", path.display());
+        for error in &errors.errors {
+            println!("  {error}");
+        }
+        return Ok(Outcome::CheckFailed);
+    }
+    println!("{}: ok ({})", path.display(), describe(&scene));
+    Ok(Outcome::Success)
+}
+
+fn convert(mut args: Args) -> Result<Outcome, CliError> {
+    let input = args.positional("input scene file")?;
+    let output = args.positional("output scene file")?;
+    args.finish()?;
+
+    let scene = load_scene(&input)?;
+    let bytes = if output.extension().is_some_and(|ext| ext == "json") {
+        scene
+            .to_json()
+            .map_err(|source| CliError::Json { path: output.clone(), source })?
+            .into_bytes()
+    } else {
+        scene.to_binary()
+    };
+    write_bytes(&output, &bytes)?;
+    Ok(Outcome::Success)
+}
+
+fn render(mut args: Args) -> Result<Outcome, CliError> {
+    let mut scene_path = None;
+    let mut output = None;
+    let mut audit_path = None;
+    let mut settings = SettingsArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(args.path(&arg)?),
+            "--audit" => audit_path = Some(args.path(&arg)?),
+            _ if settings.accept(&arg, &mut args)? => {}
+            _ => scene_path = Some(args.only_positional(arg, scene_path)?),
+        }
+    }
+    let scene_path = scene_path.ok_or_else(|| CliError::Usage("missing scene file".to_string()))?;
+    let output = output.ok_or_else(|| CliError::Usage("missing output file (-o)".to_string()))?;
+    let settings = settings.resolve()?;
+    let scene = load_scene(&scene_path)?;
+
+    let config = settings.render_config().map_err(CliError::Usage)?;
+    if let Some(audit_path) = audit_path {
+        let (image, mut bundle) = render_with_audit(&scene, &config)?;
+        let image = finish_png(&image, &settings)?;
+        audit_output(&scene, &image, &settings, &mut bundle)?;
+        write_bytes(&output, &image)?;
+        write_audit(&audit_path, &bundle)?;
+        report_invariants(&bundle);
+        return Ok(status_outcome(&bundle));
+    }
+
+    let image = render_to_png(&scene, &config)?;
+    write_bytes(&output, &finish_png(&image, &settings)?)?;
+    Ok(Outcome::Success)
+}
+
+fn audit(mut args: Args) -> Result<Outcome, CliError> {
+    let mut scene_path = None;
+    let mut output = None;
+    let mut png_path = None;
+    let mut settings = SettingsArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(args.path(&arg)?),
+            "--png" => png_path = Some(args.path(&arg)?),
+            _ if settings.accept(&arg, &mut args)? => {}
+            _ => scene_path = Some(args.only_positional(arg, scene_path)?),
+        }
+    }
+    let scene_path = scene_path.ok_or_else(|| CliError::Usage("missing scene file".to_string()))?;
+    let settings = settings.resolve()?;
+    let scene = load_scene(&scene_path)?;
+
+    let config = settings.render_config().map_err(CliError::Usage)?;
+    let (image, mut bundle) = render_with_audit(&scene, &config)?;
+    let image = finish_png(&image, &settings)?;
+    audit_output(&scene, &image, &settings, &mut bundle)?;
+    if let Some(png_path) = png_path {
+        write_bytes(&png_path, &image)?;
+    }
+    match output {
+        Some(path) => write_audit(&path, &bundle)?,
+        None => println!("{}", to_json(&bundle)?),
+    }
+    report_invariants(&bundle);
+    Ok(status_outcome(&bundle))
+}
+
+fn compare(mut args: Args) -> Result<Outcome, CliError> {
+    let mut paths = Vec::new();
+    let mut tolerance = RegressionTolerance::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth-tolerance" => tolerance.depth_tolerance = args.number(&arg)?,
+            "--histogram-tolerance" => tolerance.histogram_tolerance = args.number(&arg)?,
+            "--edge-tolerance" => tolerance.edge_density_tolerance = args.number(&arg)?,
+            "--background-tolerance" => tolerance.background_tolerance = args.number(&arg)?,
+            _ if arg.starts_with('-') => return Err(CliError::Usage(format!("unknown option '{arg}'"))),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let [baseline, current] = &paths[..] else {
+        return Err(CliError::Usage("compare takes a baseline and a current audit file".to_string()));
+    };
+
+    let baseline = load_audit(baseline)?;
+    let current = load_audit(current)?;
+    let result = compare_for_regression(&baseline, &current, tolerance);
+    for difference in &result.differences {
+        println!("difference: {difference}");
+    }
+    for note in &result.notes {
+        println!("note: {note}");
+    }
+    if result.matches {
+        println!("match");
+        Ok(Outcome::Success)
+    } else {
+        println!("mismatch ({} differences)", result.differences.len());
+        Ok(Outcome::CheckFailed)
+    }
+}
+
+/// Render options shared by `render` and `audit`; flags override the config file.
+#[derive(Default)]
+struct SettingsArgs {
+    config: Option<PathBuf>,
+    width: Option<u32>,
+    height: Option<u32>,
+    background: Option<[f32; 4]>,
+    dpi: Option<f32>,
+    antialias: Option<u32>,
+}
+
+impl SettingsArgs {
+    /// Consume `flag` (and its value) if it is a render option.
+    fn accept(&mut self, flag: &str, args: &mut Args) -> Result<bool, CliError> {
+        match flag {
+            "--config" => self.config = Some(args.path(flag)?),
+            "--width" => self.width = Some(args.number(flag)?),
+            "--height" => self.height = Some(args.number(flag)?),
+            "--dpi" => self.dpi = Some(args.number(flag)?),
+            "--antialias" => self.antialias = Some(args.number(flag)?),
+            "--background" => {
+                let value = args.value(flag)?;
+                self.background = Some(parse_color(&value).map_err(CliError::Usage)?);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn resolve(self) -> Result<RenderSettings, CliError> {
+        let mut settings = match &self.config {
+            Some(path) => serde_json::from_str(&read_text(path)?)
+                .map_err(|source| CliError::Json { path: path.clone(), source })?,
+            None => RenderSettings::default(),
+        };
+        if let Some(width) = self.width {
+            settings.width = width;
+        }
+        if let Some(height) = self.height {
+            settings.height = height;
+        }
+        if let Some(background) = self.background {
+            settings.background = background;
+        }
+        if let Some(dpi) = self.dpi {
+            settings.dpi = Some(dpi);
+        }
+        if let Some(antialias) = self.antialias {
+            settings.antialias = antialias;
+        }
+        settings.validate().map_err(CliError::Usage)?;
+        Ok(settings)
+    }
+}
+
+/// Cursor over the command-line arguments.
+struct Args {
+    inner: std::vec::IntoIter<String>,
+}
+
+impl Args {
+    fn new(args: Vec<String>) -> Self {
+        Self { inner: args.into_iter() }
+    }
+
+    fn next(&mut self) -> Option<String> {
+        self.inner.next()
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, CliError> {
+        self.next().ok_or_else(|| CliError::Usage(format!("{flag} needs a value")))
+    }
+
+    fn path(&mut self, flag: &str) -> Result<PathBuf, CliError> {
+        self.value(flag).map(PathBuf::from)
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, flag: &str) -> Result<T, CliError> {
+        let value = self.value(flag)?;
+        value.parse().map_err(|_| CliError::Usage(format!("invalid value '{value}' for {flag}")))
+    }
+
+    fn positional(&mut self, what: &str) -> Result<PathBuf, CliError> {
+        match self.next() {
+            Some(arg) if !arg.starts_with('-') => Ok(PathBuf::from(arg)),
+            Some(arg) => Err(CliError::Usage(format!("unknown option '{arg}'"))),
+            None => Err(CliError::Usage(format!("missing {what}"))),
+        }
+    }
+
+    /// Accept `arg` as the single positional argument of a command.
+    fn only_positional(&self, arg: String, current: Option<PathBuf>) -> Result<PathBuf, CliError> {
+        if arg.starts_with('-') {
+            return Err(CliError::Usage(format!("unknown option '{arg}'")));
+        }
+        if current.is_some() {
+            return Err(CliError::Usage(format!("unexpected argument '{arg}'")));
+        }
+        Ok(PathBuf::from(arg))
+    }
+
+    fn finish(mut self) -> Result<(), CliError> {
+        match self.next() {
+            Some(arg) => Err(CliError::Usage(format!("unexpected argument '{arg}'"))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn read_text(path: &Path) -> Result<String, CliError> {
+    std::fs::read_to_string(path).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+fn read_bytes(path: &Path) -> Result<Vec<u8>, CliError> {
+    std::fs::read(path).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+fn write_bytes(path: &Path, bytes: &[u8]) -> Result<(), CliError> {
+    std::fs::write(path, bytes).map_err(|source| CliError::Io { path: path.to_path_buf(), source })
+}
+
+/// Parse a scene in either the JSON or the binary container format.
+fn parse_scene(bytes: &[u8]) -> Result<Scene, SceneError> {
+    if bytes.starts_with(BINARY_MAGIC) {
+        Scene::from_binary(bytes)
+    } else {
+        Scene::from_json(std::str::from_utf8(bytes).map_err(|e| SceneError::InvalidBinary(e.to_string()))?)
+    }
+}
+
+/// Read and validate a scene, so structural errors exit as check failures.
+fn load_scene(path: &Path) -> Result<Scene, CliError> {
+    let scene = parse_scene(&read_bytes(path)?)
+        .map_err(|source| CliError::Scene { path: path.to_path_buf(), source })?;
+    scene
+        .validate()
+        .map_err(|errors| CliError::InvalidScene { path: path.to_path_buf(), errors })?;
+    Ok(scene)
+}
+
+fn load_audit(path: &Path) -> Result<AuditBundle, CliError> {
+    AuditBundle::from_json(&read_text(path)?).map_err(|source| CliError::Json { path: path.to_path_buf(), source })
+}
+
+fn to_json(bundle: &AuditBundle) -> Result<String, CliError> {
+    bundle.to_json().map_err(|source| CliError::Json { path: PathBuf::from("<audit>"), source })
+}
+
+fn write_audit(path: &Path, bundle: &AuditBundle) -> Result<(), CliError> {
+    write_bytes(path, to_json(bundle)?.as_bytes())
+}
+
+/// Apply anti-aliasing reduction and DPI metadata to a rendered PNG.
+fn finish_png(image: &[u8], settings: &RenderSettings) -> Result<Vec<u8>, CliError> {
+    let image = png::downsample(image, settings.antialias)?;
+    Ok(match settings.dpi {
+        Some(dpi) => png::set_dpi(&image, dpi),
+        None => image,
+    })
+}
+
+/// Re-measure a supersampled render's audit on the downsampled output, so
+/// its resolution, image metrics and invariants describe the written PNG.
+fn audit_output(scene: &Scene, png: &[u8], settings: &RenderSettings, bundle: &mut AuditBundle) -> Result<(), CliError> {
+    if settings.antialias <= 1 {
+        return Ok(());
+    }
+    let image = image::load_from_memory(png)?.to_rgba8();
+    let (width, height) = image.dimensions();
+    bundle.metadata.resolution = [width, height];
+    bundle.image_metrics = compute_image_metrics(image.as_raw(), width, height, settings.background);
+    bundle.invariants = check_all_invariants(scene, &bundle.metadata, &bundle.geometry, &bundle.image_metrics);
+    Ok(())
+}
+
+fn report_invariants(bundle: &AuditBundle) {
+    let invariants = &bundle.invariants;
+    for error in &invariants.errors {
+        eprintln!("error [{}]: {}", error.category, error.message);
+    }
+    for warning in &invariants.warnings {
+        eprintln!("warning [{}]: {}", warning.category, warning.message);
+    }
+    eprintln!("invariants: {:?}", invariants.overall);
+}
+
+fn status_outcome(bundle: &AuditBundle) -> Outcome {
+    match bundle.invariants.overall {
+        OverallStatus::Fail => Outcome::CheckFailed,
+        OverallStatus::Pass | OverallStatus::PassWithWarnings => Outcome::Success,
+    }
+}
+
+/// One-line summary of what a scene contains.
+fn describe(scene: &Scene) -> String {
+    let (mut meshes, mut points, mut lines, mut axes) = (0, 0, 0, 0);
+    for element in &scene.elements {
+        match element {
+            SceneElement::Mesh(_) => meshes += 1,
+            SceneElement::PointCloud(_) => points += 1,
+            SceneElement::Polyline(_) => lines += 1,
+            SceneElement::Axes(_) => axes += 1,
+        }
+    }
+    format!(
+        "{meshes} meshes, {points} point clouds, {lines} polylines, {axes} axis bundles, {} materials",
+        scene.materials.len()
+    )
+}
@@ -0,0 +1,66 @@
+//! PNG post-processing: supersample reduction and DPI metadata.
+
+use image::{ImageFormat, RgbaImage};
+
+/// Box-filter a PNG rendered at `factor` times the target size.
+pub fn downsample(png: &[u8], factor: u32) -> Result<Vec<u8>, image::ImageError> {
+    if factor <= 1 {
+        return Ok(png.to_vec());
+    }
+    let source = image::load_from_memory_with_format(png, ImageFormat::Png)?.to_rgba8();
+    let (width, height) = (source.width() / factor, source.height() / factor);
+    let samples = factor * factor;
+    let reduced = RgbaImage::from_fn(width, height, |x, y| {
+        let mut sum = [0u32; 4];
+        for dy in 0..factor {
+            for dx in 0..factor {
+                let pixel = source.get_pixel(x * factor + dx, y * factor + dy);
+                for (total, &channel) in sum.iter_mut().zip(pixel.0.iter()) {
+                    *total += channel as u32;
+                }
+            }
+        }
+        image::Rgba(sum.map(|total| ((total + samples / 2) / samples) as u8))
+    });
+
+    let mut out = Vec::new();
+    reduced.write_to(&mut std::io::Cursor::new(&mut out), ImageFormat::Png)?;
+    Ok(out)
+}
+
+/// Insert a `pHYs` chunk recording `dpi` right after the IHDR chunk.
+///
+/// The PNG signature and IHDR are fixed-size, so the chunk always goes at
+/// byte 33. Any existing `pHYs` chunk is left alone.
+pub fn set_dpi(png: &[u8], dpi: f32) -> Vec<u8> {
+    const IHDR_END: usize = 8 + 25;
+    let pixels_per_meter = (dpi as f64 / 0.0254).round() as u32;
+
+    let mut chunk = Vec::with_capacity(21);
+    chunk.extend_from_slice(&9u32.to_be_bytes());
+    chunk.extend_from_slice(b"pHYs");
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    chunk.push(1); // unit: meter
+    let crc = crc32(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+
+    let mut out = Vec::with_capacity(png.len() + chunk.len());
+    out.extend_from_slice(&png[..IHDR_END]);
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&png[IHDR_END..]);
+    out
+}
+
+/// CRC-32 (ISO 3309) as used by PNG chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
@@ -0,0 +1,92 @@
+//! Render settings shared by the `render` and `audit` subcommands.
+
+use frustum_render::RenderConfig;
+use serde::{Deserialize, Serialize};
+
+/// Largest supersampled image side: wgpu's default 2D texture limit.
+pub const MAX_RENDER_SIZE: u32 = 8192;
+
+/// Output settings loaded from a `--config` file and overridden by flags.
+///
+/// ```json
+/// { "width": 1200, "height": 900, "background": [1, 1, 1, 1], "dpi": 300, "antialias": 2 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderSettings {
+    /// Output width in pixels.
+    pub width: u32,
+    /// Output height in pixels.
+    pub height: u32,
+    /// Background color as RGBA (0.0 to 1.0).
+    pub background: [f32; 4],
+    /// Physical resolution recorded in the PNG, in dots per inch.
+    /// Only metadata: it does not change the pixel size.
+    pub dpi: Option<f32>,
+    /// Supersampling factor. The scene is rendered at `antialias` times the
+    /// output size and box-filtered down; 1 disables anti-aliasing.
+    pub antialias: u32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        let config = RenderConfig::default();
+        Self {
+            width: config.width,
+            height: config.height,
+            background: config.background,
+            dpi: None,
+            antialias: 1,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Check the settings before any GPU work is done.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("image size must be non-zero, got {}x{}", self.width, self.height));
+        }
+        if !(1..=8).contains(&self.antialias) {
+            return Err(format!("antialias must be between 1 and 8, got {}", self.antialias));
+        }
+        self.render_config()?;
+        if let Some(dpi) = self.dpi {
+            if !(dpi.is_finite() && dpi > 0.0) {
+                return Err(format!("dpi must be positive, got {dpi}"));
+            }
+        }
+        if self.background.iter().any(|c| !(0.0..=1.0).contains(c)) {
+            return Err("background components must be in [0, 1]".to_string());
+        }
+        Ok(())
+    }
+
+    /// The renderer configuration, at the supersampled size.
+    ///
+    /// Fails when that size exceeds [`MAX_RENDER_SIZE`].
+    pub fn render_config(&self) -> Result<RenderConfig, String> {
+        let supersampled = |side: u32| side.checked_mul(self.antialias).filter(|&s| s <= MAX_RENDER_SIZE);
+        match (supersampled(self.width), supersampled(self.height)) {
+            (Some(width), Some(height)) => Ok(RenderConfig { width, height, background: self.background }),
+            _ => Err(format!(
+                "image size {}x{} at antialias {} exceeds the {MAX_RENDER_SIZE} pixel render limit",
+                self.width, self.height, self.antialias
+            )),
+        }
+    }
+}
+
+/// Parse a background color given as `r,g,b` or `r,g,b,a`.
+pub fn parse_color(value: &str) -> Result<[f32; 4], String> {
+    let parts = value
+        .split(',')
+        .map(|p| p.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("invalid color '{value}'"))?;
+    match parts[..] {
+        [r, g, b] => Ok([r, g, b, 1.0]),
+        [r, g, b, a] => Ok([r, g, b, a]),
+        _ => Err(format!("color '{value}' must have 3 or 4 components")),
+    }
+}
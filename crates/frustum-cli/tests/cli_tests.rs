@@ -0,0 +1,169 @@
+//! Command-line contract tests for the `frustum` binary.
+//!
+//! These run the built executable and check exit codes and outputs.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use frustum_core::scene::{Bounds, Scene};
+use frustum_core::{Camera, Mesh};
+
+fn frustum(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_frustum"))
+        .args(args)
+        .output()
+        .expect("failed to run frustum")
+}
+
+/// A fresh scratch directory for one test.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("frustum-cli-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn triangle_scene() -> Scene {
+    Scene::new(
+        Camera::perspective([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], 45.0),
+        Bounds { min: [-1.0, -1.0, -1.0], max: [1.0, 1.0, 1.0] },
+    )
+    .add_mesh(Mesh::new(
+        vec![-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0],
+        vec![0, 1, 2],
+    ))
+}
+
+fn write_scene(dir: &std::path::Path) -> PathBuf {
+    let path = dir.join("scene.json");
+    std::fs::write(&path, triangle_scene().to_json().unwrap()).unwrap();
+    path
+}
+
+fn path_str(path: &std::path::Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn test_help_and_version() {
+    let output = frustum(&["--help"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Usage: frustum"));
+
+    let output = frustum(&["--version"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("frustum "));
+}
+
+#[test]
+fn test_usage_errors_exit_2() {
+    assert_eq!(frustum(&[]).status.code(), Some(2));
+    assert_eq!(frustum(&["explode"]).status.code(), Some(2));
+    assert_eq!(frustum(&["render", "scene.json"]).status.code(), Some(2), "missing -o");
+    assert_eq!(frustum(&["compare", "only-one.json"]).status.code(), Some(2));
+    assert_eq!(
+        frustum(&["render", "scene.json", "-o", "out.png", "--antialias", "0"]).status.code(),
+        Some(2)
+    );
+    let oversized = ["render", "scene.json", "-o", "out.png", "--width", "4294967295", "--antialias", "8"];
+    assert_eq!(frustum(&oversized).status.code(), Some(2), "supersampled size overflows u32");
+    let oversized = ["render", "scene.json", "-o", "out.png", "--height", "2000", "--antialias", "8"];
+    assert_eq!(frustum(&oversized).status.code(), Some(2), "supersampled size exceeds the render limit");
+}
+
+#[test]
+fn test_validate() {
+    let dir = scratch_dir("validate");
+    let scene = write_scene(&dir);
+    let output = frustum(&["validate", path_str(&scene)]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 meshes"));
+
+    let broken = dir.join("broken.json");
+    std::fs::write(&broken, r#"{"camera": {}}"#).unwrap();
+    assert_eq!(frustum(&["validate", path_str(&broken)]).status.code(), Some(1));
+
+    let mut scene = triangle_scene();
+    if let frustum_core::scene::SceneElement::Mesh(mesh) = &mut scene.elements[0] {
+        mesh.indices[2] = 9;
+    }
+    let out_of_range = dir.join("out_of_range.json");
+    std::fs::write(&out_of_range, scene.to_json().unwrap()).unwrap();
+    let output = frustum(&["validate", path_str(&out_of_range)]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("/elements/0/indices/2"));
+    let png = dir.join("out.png");
+    let output = frustum(&["render", path_str(&out_of_range), "-o", path_str(&png)]);
+    assert_eq!(output.status.code(), Some(1), "invalid scenes are check failures, not crashes");
+    assert!(!png.exists());
+
+    let missing = dir.join("missing.json");
+    assert_eq!(frustum(&["validate", path_str(&missing)]).status.code(), Some(2));
+}
+
+#[test]
+fn test_convert_binary_round_trip() {
+    let dir = scratch_dir("convert");
+    let scene = write_scene(&dir);
+    let binary = dir.join("scene.fsb");
+    let back = dir.join("back.json");
+
+    assert!(frustum(&["convert", path_str(&scene), path_str(&binary)]).status.success());
+    assert!(std::fs::read(&binary).unwrap().starts_with(frustum_core::BINARY_MAGIC));
+    assert!(frustum(&["validate", path_str(&binary)]).status.success());
+
+    assert!(frustum(&["convert", path_str(&binary), path_str(&back)]).status.success());
+    assert_eq!(std::fs::read_to_string(&back).unwrap(), std::fs::read_to_string(&scene).unwrap());
+}
+
+#[test]
+fn test_compare_audits() {
+    let dir = scratch_dir("compare");
+    let baseline = concat!(env!("CARGO_MANIFEST_DIR"), "/../../audit_cube.json");
+    let output = frustum(&["compare", baseline, baseline]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim_end().lines().last(), Some("match"));
+
+    // Shift the depth statistics past the default tolerance.
+    let mut changed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(baseline).unwrap()).unwrap();
+    changed["geometry"]["depth_stats"]["mean"] = serde_json::json!(0.9);
+    let current = dir.join("current.json");
+    std::fs::write(&current, changed.to_string()).unwrap();
+
+    let output = frustum(&["compare", baseline, path_str(&current)]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("mismatch"));
+
+    let output = frustum(&["compare", baseline, path_str(&current), "--depth-tolerance", "1.0"]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_render_with_audit_settings() {
+    let dir = scratch_dir("render");
+    let scene = write_scene(&dir);
+    let config = dir.join("settings.json");
+    std::fs::write(&config, r#"{"width": 64, "height": 48, "dpi": 300}"#).unwrap();
+    let png = dir.join("out.png");
+    let audit = dir.join("audit.json");
+
+    let output = frustum(&[
+        "render", path_str(&scene), "-o", path_str(&png), "--audit", path_str(&audit),
+        "--config", path_str(&config), "--antialias", "2", "--background", "0,0,0",
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let bytes = std::fs::read(&png).unwrap();
+    let image = image::load_from_memory(&bytes).unwrap();
+    assert_eq!((image.width(), image.height()), (64, 48), "supersampled render is reduced to the output size");
+    let phys = bytes.windows(4).position(|w| w == b"pHYs").expect("pHYs chunk");
+    let ppm = u32::from_be_bytes(bytes[phys + 4..phys + 8].try_into().unwrap());
+    assert_eq!(ppm, 11811, "300 dpi in pixels per meter");
+
+    let bundle: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&audit).unwrap()).unwrap();
+    assert_eq!(bundle["metadata"]["resolution"], serde_json::json!([64, 48]), "audit describes the written PNG");
+    let red: u64 = bundle["image_metrics"]["histogram"]["red"].as_array().unwrap().iter().map(|n| n.as_u64().unwrap()).sum();
+    assert_eq!(red, 64 * 48, "image metrics are measured on the written PNG");
+    assert!(bundle["invariants"]["overall"].is_string());
+}
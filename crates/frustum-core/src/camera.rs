@@ -1,10 +1,11 @@
 //! Camera definition with explicit parameters and matrix generation.
 
 use glam::{Mat4, Vec3};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Projection type for the camera.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Projection {
     Perspective,
@@ -12,7 +13,7 @@ pub enum Projection {
 }
 
 /// Camera with explicit position, target, and projection parameters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Camera {
     /// Camera position in world coordinates.
     pub position: [f32; 3],
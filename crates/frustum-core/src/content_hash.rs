@@ -0,0 +1,433 @@
+//! Content-based scene digest.
+//!
+//! `Scene::content_hash` is the lowercase hex SHA-256 of a canonical binary
+//! encoding of the scene, prefixed by the domain tag
+//! `frustum/scene-hash/v1` and a zero byte. The encoding walks the scene's
+//! serde data model (the same fields, order and omissions as `to_json`), so it
+//! does not depend on float formatting, whitespace or the Rust version:
+//!
+//! | value               | encoding                                         |
+//! |---------------------|--------------------------------------------------|
+//! | bool                | `b`, then 0 or 1                                 |
+//! | integer             | `i` + i64 LE (signed) or `u` + u64 LE (unsigned) |
+//! | f32                 | `f` + IEEE bits LE; every NaN as `0x7fc00000`    |
+//! | f64                 | `d` + IEEE bits LE; every NaN as the quiet NaN   |
+//! | string              | `s` + u64 LE byte length + UTF-8 bytes           |
+//! | None / Some(v)      | `n` / `o` then v                                 |
+//! | unit                | `n`                                              |
+//! | unit variant        | the variant name as a string                     |
+//! | other enum variant  | `{`, the variant name as a string, content, `}`  |
+//! | sequence or tuple   | `[`, elements, `]`                               |
+//! | struct or map       | `{`, then key string and value per field, `}`    |
+//!
+//! Field order is declaration order, with internally tagged enums writing
+//! their tag field first. Two scenes that serialize to the same JSON hash the
+//! same, including after a round trip through JSON or the binary container.
+//! Changing the encoding requires a new domain tag.
+
+use serde::ser::{self, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::scene::Scene;
+
+/// Domain tag that versions the canonical encoding.
+pub const CONTENT_HASH_DOMAIN: &str = "frustum/scene-hash/v1";
+
+impl Scene {
+    /// Stable SHA-256 digest of the scene's content, as 64 hex characters.
+    pub fn content_hash(&self) -> String {
+        let mut encoder = Encoder { hasher: Sha256::new() };
+        encoder.hasher.update(CONTENT_HASH_DOMAIN.as_bytes());
+        encoder.hasher.update([0]);
+        self.serialize(&mut encoder).expect("canonical encoding cannot fail");
+        encoder.hasher.finalize().iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+/// Serde serializer that streams the canonical encoding into a hasher.
+struct Encoder {
+    hasher: Sha256,
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl Encoder {
+    fn tag(&mut self, tag: u8) {
+        self.hasher.update([tag]);
+    }
+
+    fn str(&mut self, s: &str) {
+        self.tag(b's');
+        self.hasher.update((s.len() as u64).to_le_bytes());
+        self.hasher.update(s.as_bytes());
+    }
+
+    fn signed(&mut self, v: i64) {
+        self.tag(b'i');
+        self.hasher.update(v.to_le_bytes());
+    }
+
+    fn unsigned(&mut self, v: u64) {
+        self.tag(b'u');
+        self.hasher.update(v.to_le_bytes());
+    }
+}
+
+impl ser::Serializer for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.tag(b'b');
+        self.hasher.update([v as u8]);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.signed(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.unsigned(v.into());
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.unsigned(v.into());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.unsigned(v.into());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.unsigned(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        let bits = if v.is_nan() { 0x7fc0_0000 } else { v.to_bits() };
+        self.tag(b'f');
+        self.hasher.update(bits.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        let bits = if v.is_nan() { 0x7ff8_0000_0000_0000 } else { v.to_bits() };
+        self.tag(b'd');
+        self.hasher.update(bits.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.str(v.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.tag(b'[');
+        for &b in v {
+            self.unsigned(b.into());
+        }
+        self.tag(b']');
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.tag(b'n');
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        self.tag(b'o');
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.tag(b'n');
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+        self.str(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.tag(b'{');
+        self.str(variant);
+        value.serialize(&mut *self)?;
+        self.tag(b'}');
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, Error> {
+        self.tag(b'[');
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.tag(b'{');
+        self.str(variant);
+        self.tag(b'[');
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, Error> {
+        self.tag(b'{');
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        self.tag(b'{');
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.tag(b'{');
+        self.str(variant);
+        self.tag(b'{');
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b']');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b']');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b']');
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b']');
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.str(key);
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Encoder {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.str(key);
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.tag(b'}');
+        self.tag(b'}');
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Mesh;
+    use crate::scene::test_support::empty_scene;
+
+    fn scene() -> Scene {
+        empty_scene().add_mesh(Mesh::new(vec![0.1, 0.2, 0.3, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], vec![0, 1, 2]))
+    }
+
+    #[test]
+    fn test_known_digest() {
+        // Pinned so that any change to the encoding is noticed: a new
+        // encoding needs a new domain tag, not a silently different hash.
+        assert_eq!(scene().content_hash(), "965920541bbd16d39b0d08edfc92bda8d5a8d25b38139e28dfe39fdcd739b3ed");
+    }
+
+    #[test]
+    fn test_hash_is_stable_across_formats() {
+        let scene = scene();
+        let hash = scene.content_hash();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(Scene::from_json(&scene.to_json().unwrap()).unwrap().content_hash(), hash);
+        assert_eq!(Scene::from_binary(&scene.to_binary()).unwrap().content_hash(), hash);
+
+        let compact = serde_json::to_string(&scene).unwrap();
+        assert_eq!(Scene::from_json(&compact).unwrap().content_hash(), hash, "formatting does not matter");
+    }
+
+    #[test]
+    fn test_hash_tracks_content() {
+        let base = scene().content_hash();
+
+        let mut moved = scene();
+        moved.camera.position[2] = 3.0001;
+        assert_ne!(moved.content_hash(), base);
+
+        let mut reindexed = scene();
+        if let crate::scene::SceneElement::Mesh(m) = &mut reindexed.elements[0] {
+            m.indices = vec![0, 2, 1];
+        }
+        assert_ne!(reindexed.content_hash(), base);
+
+        let mut with_nan = scene();
+        let mut other_nan = scene();
+        if let crate::scene::SceneElement::Mesh(m) = &mut with_nan.elements[0] {
+            m.scalars = Some(vec![f32::NAN, 0.0, 1.0]);
+        }
+        if let crate::scene::SceneElement::Mesh(m) = &mut other_nan.elements[0] {
+            m.scalars = Some(vec![f32::from_bits(0xffc0_0001), 0.0, 1.0]);
+        }
+        assert_eq!(with_nan.content_hash(), other_nan.content_hash(), "NaN payloads are canonicalized");
+        assert_ne!(with_nan.content_hash(), base);
+    }
+}
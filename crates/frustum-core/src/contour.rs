@@ -0,0 +1,525 @@
+//! Contour lines: marching squares on 2D scalar grids, and iso-lines of
+//! per-vertex scalars on triangle meshes.
+//!
+//! Both extract per-cell (or per-triangle) segments and join them into
+//! connected polylines, one per contour, each tagged with its level.
+//! Like Marching Cubes, this is a pure, deterministic geometry generation
+//! step: lines come out in a fixed order.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{Mesh, Polyline};
+use crate::normals::{vertex_normals, NormalWeighting};
+
+/// Placement of a 2D grid's contour lines along z.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContourElevation {
+    /// All lines in the plane at height `z`, e.g. floating over a surface plot.
+    Constant { z: f32 },
+    /// Each line at z = its level, lying on the height field z = f(x, y).
+    Level,
+}
+
+impl Default for ContourElevation {
+    fn default() -> Self {
+        ContourElevation::Constant { z: 0.0 }
+    }
+}
+
+/// A scalar field sampled on a regular grid in the xy plane.
+///
+/// Sample (i, j) lies at `origin + (i * spacing[0], j * spacing[1])`;
+/// `values` is stored i-fastest. Non-finite values mark missing data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarGrid {
+    /// Sample values, `resolution[0] * resolution[1]` of them.
+    pub values: Vec<f32>,
+    /// Number of samples along x and y.
+    pub resolution: [usize; 2],
+    /// Distance between samples along x and y.
+    pub spacing: [f32; 2],
+    /// World-space (x, y) of sample (0, 0).
+    pub origin: [f32; 2],
+    /// Height at which contour lines are placed.
+    pub elevation: ContourElevation,
+}
+
+impl ScalarGrid {
+    /// Create a grid from values stored x-fastest.
+    ///
+    /// # Panics
+    /// Panics if the values length does not match the resolution.
+    pub fn new(values: Vec<f32>, resolution: [usize; 2], spacing: [f32; 2], origin: [f32; 2]) -> Self {
+        assert_eq!(
+            values.len(),
+            resolution[0] * resolution[1],
+            "Grid values length must match resolution"
+        );
+        Self {
+            values,
+            resolution,
+            spacing,
+            origin,
+            elevation: ContourElevation::default(),
+        }
+    }
+
+    /// Set where contour lines are placed along z.
+    pub fn with_elevation(mut self, elevation: ContourElevation) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
+    /// Contour lines at each of `levels`.
+    pub fn contours(&self, levels: &[f32], line_width: f32) -> Vec<ContourLine> {
+        contour_lines(&self.values, self.resolution, levels, line_width, |i, j, level| {
+            let z = match self.elevation {
+                ContourElevation::Constant { z } => z,
+                ContourElevation::Level => level,
+            };
+            [
+                self.origin[0] + i * self.spacing[0],
+                self.origin[1] + j * self.spacing[1],
+                z,
+            ]
+        })
+    }
+}
+
+/// One connected contour line.
+#[derive(Debug, Clone)]
+pub struct ContourLine {
+    /// Iso-value the line was extracted at.
+    pub level: f32,
+    /// Whether the line is a closed loop; its last point then repeats the first.
+    pub closed: bool,
+    /// The line itself. Every vertex scalar equals `level`, so a
+    /// `ScalarMappedMaterial` colours lines by level.
+    pub polyline: Polyline,
+}
+
+/// Contour lines of a grid at each level, placed through `position(i, j, level)`.
+///
+/// Lines are returned level by level in the order given, and in a fixed
+/// order within a level.
+pub(crate) fn contour_lines(
+    values: &[f32],
+    resolution: [usize; 2],
+    levels: &[f32],
+    line_width: f32,
+    position: impl Fn(f32, f32, f32) -> [f32; 3],
+) -> Vec<ContourLine> {
+    let mut lines = Vec::new();
+    for &level in levels {
+        for contour in marching_squares(values, resolution, level) {
+            let positions: Vec<f32> = contour.points.iter().flat_map(|p| position(p[0], p[1], level)).collect();
+            let scalars = vec![level; contour.points.len()];
+            lines.push(ContourLine {
+                level,
+                closed: contour.closed,
+                polyline: Polyline::new(positions, line_width).with_scalars(scalars),
+            });
+        }
+    }
+    lines
+}
+
+/// A connected iso-line in continuous grid coordinates (i, j).
+#[derive(Debug, Clone, PartialEq)]
+struct GridContour {
+    /// Points along the line; closed loops repeat the first point at the end.
+    points: Vec<[f32; 2]>,
+    closed: bool,
+}
+
+/// Grid edge holding a crossing: (direction, i, j), where direction 0 runs
+/// from (i, j) to (i+1, j) and direction 1 from (i, j) to (i, j+1).
+type EdgeKey = (u8, usize, usize);
+
+/// Extract the iso-lines of `values` at `level`.
+///
+/// `values` is a `resolution[0] x resolution[1]` grid stored i-fastest. A
+/// sample is inside if it is below `level`, matching the Marching Cubes
+/// convention. Cells with a non-finite corner are skipped, so lines end at
+/// invalid regions. Saddle cells are resolved by the cell-centre average.
+fn marching_squares(values: &[f32], resolution: [usize; 2], level: f32) -> Vec<GridContour> {
+    let [ni, nj] = resolution;
+    if ni < 2 || nj < 2 {
+        return Vec::new();
+    }
+    let at = |i: usize, j: usize| values[i + j * ni];
+
+    // Per-cell segments between edge crossings
+    let mut segments: Vec<(EdgeKey, EdgeKey)> = Vec::new();
+    for j in 0..(nj - 1) {
+        for i in 0..(ni - 1) {
+            // Corners counter-clockwise: (i,j), (i+1,j), (i+1,j+1), (i,j+1)
+            let v = [at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)];
+            if v.iter().any(|value| !value.is_finite()) {
+                continue;
+            }
+
+            let mut case = 0u8;
+            for (bit, &value) in v.iter().enumerate() {
+                if value < level {
+                    case |= 1 << bit;
+                }
+            }
+
+            // Cell edges: bottom, right, top, left
+            let bottom = (0, i, j);
+            let right = (1, i + 1, j);
+            let top = (0, i, j + 1);
+            let left = (1, i, j);
+
+            let centre_inside = (v[0] + v[1] + v[2] + v[3]) * 0.25 < level;
+            match case {
+                0 | 15 => {}
+                1 | 14 => segments.push((left, bottom)),
+                2 | 13 => segments.push((bottom, right)),
+                3 | 12 => segments.push((left, right)),
+                4 | 11 => segments.push((right, top)),
+                6 | 9 => segments.push((bottom, top)),
+                7 | 8 => segments.push((left, top)),
+                // Saddles: corners 0 and 2 share a side with the centre or not
+                5 if centre_inside => {
+                    segments.push((left, top));
+                    segments.push((bottom, right));
+                }
+                5 => {
+                    segments.push((left, bottom));
+                    segments.push((right, top));
+                }
+                10 if centre_inside => {
+                    segments.push((left, bottom));
+                    segments.push((right, top));
+                }
+                10 => {
+                    segments.push((left, top));
+                    segments.push((bottom, right));
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    // Crossing point on a grid edge
+    let point = |(direction, i, j): EdgeKey| {
+        let (i1, j1) = if direction == 0 { (i + 1, j) } else { (i, j + 1) };
+        let (a, b) = (at(i, j), at(i1, j1));
+        let t = if (b - a).abs() > 1e-10 { (level - a) / (b - a) } else { 0.5 };
+        [i as f32 + t * (i1 - i) as f32, j as f32 + t * (j1 - j) as f32]
+    };
+
+    join_segments(&segments)
+        .into_iter()
+        .map(|(keys, closed)| GridContour {
+            points: keys.into_iter().map(point).collect(),
+            closed,
+        })
+        .collect()
+}
+
+/// Join segments that share an endpoint key into strips.
+///
+/// Returns each strip's keys and whether it is closed (its last key then
+/// repeats the first). Open strips come first, starting from the smallest
+/// free endpoint key; closed loops follow in segment order. The result only
+/// depends on the order of `segments`.
+fn join_segments<K: Ord + Copy>(segments: &[(K, K)]) -> Vec<(Vec<K>, bool)> {
+    let mut incident: BTreeMap<K, Vec<usize>> = BTreeMap::new();
+    for (s, &(a, b)) in segments.iter().enumerate() {
+        incident.entry(a).or_default().push(s);
+        incident.entry(b).or_default().push(s);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut strips = Vec::new();
+    let walk = |start: K, first: usize, used: &mut [bool]| {
+        let mut keys = vec![start];
+        let (mut key, mut segment) = (start, first);
+        loop {
+            used[segment] = true;
+            let (a, b) = segments[segment];
+            key = if a == key { b } else { a };
+            keys.push(key);
+            match incident[&key].iter().find(|&&s| !used[s]) {
+                Some(&next) => segment = next,
+                None => break,
+            }
+        }
+        let closed = keys.len() > 2 && keys[0] == keys[keys.len() - 1];
+        (keys, closed)
+    };
+
+    // Open lines start at keys with a single segment (boundaries and invalid regions)
+    for (&key, list) in incident.iter() {
+        if list.len() == 1 && !used[list[0]] {
+            strips.push(walk(key, list[0], &mut used));
+        }
+    }
+    // Whatever remains forms closed loops
+    for (s, &(start, _)) in segments.iter().enumerate() {
+        if !used[s] {
+            strips.push(walk(start, s, &mut used));
+        }
+    }
+
+    strips
+}
+
+impl Mesh {
+    /// Iso-lines of the per-vertex scalars at each of `levels`, lying on the surface.
+    ///
+    /// Each triangle contributes a segment where a level crosses it; segments
+    /// sharing a mesh edge are joined into one line per connected contour, so
+    /// vertices must be shared between triangles for lines to connect.
+    /// Points are pushed `normal_offset` off the surface along the
+    /// interpolated vertex normal (area-weighted face normals if the mesh has
+    /// none) to keep the lines from z-fighting with it. The offset goes to the
+    /// front of the triangle winding, or outward for a closed mesh whatever
+    /// its winding, so a negative offset buries the lines instead. Triangles
+    /// with a non-finite scalar are skipped. Returns no lines if the mesh has
+    /// no scalars.
+    pub fn contour_lines(&self, levels: &[f32], line_width: f32, normal_offset: f32) -> Vec<ContourLine> {
+        let Some(scalars) = self.scalars.as_ref() else {
+            return Vec::new();
+        };
+        let normals = offset_normals(self);
+        let vertex = |data: &[f32], v: u32| Vec3::from_slice(&data[v as usize * 3..v as usize * 3 + 3]);
+
+        let mut lines = Vec::new();
+        for &level in levels {
+            // Segments between crossings, keyed by the crossed edge (lower, higher vertex)
+            let mut segments: Vec<((u32, u32), (u32, u32))> = Vec::new();
+            for t in self.indices.chunks_exact(3) {
+                let s = [0, 1, 2].map(|k| scalars[t[k] as usize]);
+                if s.iter().any(|v| !v.is_finite()) || t[0] == t[1] || t[1] == t[2] || t[0] == t[2] {
+                    continue;
+                }
+                let mut crossed = [(0, 0); 2];
+                let mut count = 0;
+                for k in 0..3 {
+                    let (a, b) = (k, (k + 1) % 3);
+                    if (s[a] < level) != (s[b] < level) {
+                        crossed[count] = (t[a].min(t[b]), t[a].max(t[b]));
+                        count += 1;
+                    }
+                }
+                if count == 2 {
+                    segments.push((crossed[0], crossed[1]));
+                }
+            }
+
+            let point = |(a, b): (u32, u32)| {
+                let (sa, sb) = (scalars[a as usize], scalars[b as usize]);
+                let t = if (sb - sa).abs() > 1e-10 { (level - sa) / (sb - sa) } else { 0.5 };
+                let position = vertex(&self.positions, a).lerp(vertex(&self.positions, b), t);
+                let normal = vertex(&normals, a).lerp(vertex(&normals, b), t).normalize_or_zero();
+                position + normal * normal_offset
+            };
+
+            for (keys, closed) in join_segments(&segments) {
+                let positions: Vec<f32> = keys.iter().flat_map(|&key| point(key).to_array()).collect();
+                let scalars = vec![level; keys.len()];
+                lines.push(ContourLine {
+                    level,
+                    closed,
+                    polyline: Polyline::new(positions, line_width).with_scalars(scalars),
+                });
+            }
+        }
+        lines
+    }
+}
+
+/// Vertex normals pointing off the surface: oriented to the winding, then
+/// flipped if the mesh is closed and wound inside out (e.g. marching cubes on
+/// a signed distance field, whose normals point down the gradient).
+fn offset_normals(mesh: &Mesh) -> Vec<f32> {
+    let winding = vertex_normals(mesh, NormalWeighting::Area);
+    let mut normals = match mesh.normals.as_ref() {
+        Some(normals) if normals.len() == mesh.positions.len() => normals.clone(),
+        _ => winding.clone(),
+    };
+    let inward = encloses_negative_volume(mesh);
+    for (n, w) in normals.chunks_exact_mut(3).zip(winding.chunks_exact(3)) {
+        let agrees = Vec3::from_slice(n).dot(Vec3::from_slice(w)) >= 0.0;
+        if agrees == inward {
+            n.iter_mut().for_each(|c| *c = -*c);
+        }
+    }
+    normals
+}
+
+/// Whether every edge is shared by exactly two triangles and the winding
+/// encloses a negative signed volume.
+fn encloses_negative_volume(mesh: &Mesh) -> bool {
+    let vertex = |v: u32| Vec3::from_slice(&mesh.positions[v as usize * 3..v as usize * 3 + 3]);
+    let mut edges: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+    let mut volume = 0.0;
+    for t in mesh.indices.chunks_exact(3) {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+        volume += vertex(t[0]).dot(vertex(t[1]).cross(vertex(t[2])));
+    }
+    !edges.is_empty() && edges.values().all(|&n| n == 2) && volume < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle_grid(n: usize, radius: f32) -> Vec<f32> {
+        let mut values = Vec::with_capacity(n * n);
+        for j in 0..n {
+            for i in 0..n {
+                let x = i as f32 / (n - 1) as f32 * 2.0 - 1.0;
+                let y = j as f32 / (n - 1) as f32 * 2.0 - 1.0;
+                values.push((x * x + y * y).sqrt() - radius);
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn test_circle_is_one_closed_loop() {
+        let n = 21;
+        let contours = marching_squares(&circle_grid(n, 0.6), [n, n], 0.0);
+        assert_eq!(contours.len(), 1);
+        let contour = &contours[0];
+        assert!(contour.closed);
+        assert_eq!(contour.points.first(), contour.points.last());
+
+        let scale = (n - 1) as f32 / 2.0;
+        for p in &contour.points {
+            let (x, y) = (p[0] / scale - 1.0, p[1] / scale - 1.0);
+            let r = (x * x + y * y).sqrt();
+            assert!((r - 0.6).abs() < 0.02, "Point off circle: r = {}", r);
+        }
+    }
+
+    #[test]
+    fn test_open_line_and_invalid_cells() {
+        // Linear ramp in i: a single straight line across the grid at i = 2.5
+        let (ni, nj) = (6, 5);
+        let mut values: Vec<f32> = (0..ni * nj).map(|k| (k % ni) as f32).collect();
+        let contours = marching_squares(&values, [ni, nj], 2.5);
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].closed);
+        assert_eq!(contours[0].points.len(), nj);
+        assert!(contours[0].points.iter().all(|p| (p[0] - 2.5).abs() < 1e-6));
+
+        // An invalid sample in the middle row splits the line in two
+        values[2 + 2 * ni] = f32::NAN;
+        let contours = marching_squares(&values, [ni, nj], 2.5);
+        assert_eq!(contours.len(), 2);
+    }
+
+    #[test]
+    fn test_scalar_grid_contours() {
+        let n = 21;
+        let grid = ScalarGrid::new(circle_grid(n, 0.0), [n, n], [0.1, 0.1], [-1.0, -1.0]);
+        let lines = grid.contours(&[0.25, 0.5, 2.0], 1.5);
+
+        // Two concentric circles; level 2.0 lies outside the grid
+        assert_eq!(lines.len(), 2);
+        for (line, radius) in lines.iter().zip([0.25, 0.5]) {
+            assert_eq!(line.level, radius);
+            assert!(line.closed);
+            assert_eq!(line.polyline.line_width, 1.5);
+            let scalars = line.polyline.scalars.as_ref().unwrap();
+            assert_eq!(scalars.len() * 3, line.polyline.positions.len());
+            assert!(scalars.iter().all(|&s| s == radius));
+            for p in line.polyline.positions.chunks(3) {
+                let r = (p[0] * p[0] + p[1] * p[1]).sqrt();
+                assert!((r - radius).abs() < 0.02);
+                assert_eq!(p[2], 0.0);
+            }
+        }
+
+        // Height-field placement lifts each line to its level
+        let lifted = grid.with_elevation(ContourElevation::Level).contours(&[0.5], 1.0);
+        assert!(lifted[0].polyline.positions.chunks(3).all(|p| p[2] == 0.5));
+    }
+
+    fn sphere_mesh(radius: f32) -> Mesh {
+        use crate::marching_cubes::marching_cubes;
+        use crate::marching_cubes::test_support::sphere_volume;
+        let mesh = marching_cubes(&sphere_volume([24; 3], radius), 0.0);
+        let heights = mesh.positions.chunks(3).map(|p| p[2]).collect();
+        mesh.with_scalars(heights)
+    }
+
+    #[test]
+    fn test_mesh_contours_on_sphere() {
+        let mesh = sphere_mesh(0.7);
+        let lines = mesh.contour_lines(&[-0.35, 0.0, 0.35], 1.0, 0.0);
+
+        // One closed ring per level
+        assert_eq!(lines.len(), 3);
+        for (line, level) in lines.iter().zip([-0.35, 0.0, 0.35]) {
+            assert_eq!(line.level, level);
+            assert!(line.closed);
+            for p in line.polyline.positions.chunks(3) {
+                let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+                assert!((r - 0.7).abs() < 0.03, "Point off sphere: r = {}", r);
+                assert!((p[2] - level).abs() < 1e-5);
+            }
+        }
+
+        // The offset lifts every point off the surface, outward even though
+        // marching cubes orients an SDF sphere's normals and winding inward
+        let lifted = mesh.contour_lines(&[-0.35, 0.0, 0.35], 1.0, 0.05);
+        for (a, b) in lines.iter().zip(&lifted) {
+            for (p, q) in a.polyline.positions.chunks(3).zip(b.polyline.positions.chunks(3)) {
+                let d = Vec3::from_slice(q) - Vec3::from_slice(p);
+                assert!((d.length() - 0.05).abs() < 1e-4);
+                assert!(Vec3::from_slice(q).length() > 0.74, "Point inside sphere: {:?}", q);
+            }
+        }
+
+        // Winding the sphere outward instead changes nothing
+        let mut outward = mesh.clone();
+        outward.indices.chunks_exact_mut(3).for_each(|t| t.swap(1, 2));
+        outward.normals = None;
+        for line in outward.contour_lines(&[0.0], 1.0, 0.05) {
+            for q in line.polyline.positions.chunks(3) {
+                assert!(Vec3::from_slice(q).length() > 0.74);
+            }
+        }
+
+        // Deterministic output
+        let again = mesh.contour_lines(&[-0.35, 0.0, 0.35], 1.0, 0.0);
+        for (a, b) in lines.iter().zip(&again) {
+            assert_eq!(a.polyline.positions, b.polyline.positions);
+        }
+    }
+
+    #[test]
+    fn test_mesh_contours_open_and_without_scalars() {
+        // Unit square split into two triangles, scalar = x
+        let mesh = Mesh::new(
+            vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0],
+            vec![0, 1, 2, 0, 2, 3],
+        );
+        assert!(mesh.contour_lines(&[0.5], 1.0, 0.0).is_empty());
+
+        let mesh = mesh.with_scalars(vec![0.0, 1.0, 1.0, 0.0]);
+        let lines = mesh.contour_lines(&[0.25], 1.0, 0.1);
+        assert_eq!(lines.len(), 1);
+        assert!(!lines[0].closed);
+        // Crosses both triangles: three points on x = 0.25, offset along +z
+        assert_eq!(lines[0].polyline.positions.len(), 9);
+        for p in lines[0].polyline.positions.chunks(3) {
+            assert!((p[0] - 0.25).abs() < 1e-6);
+            assert!((p[2] - 0.1).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,639 @@
+//! Quadric error mesh decimation.
+//!
+//! Deterministic edge-collapse simplification using Garland–Heckbert error
+//! quadrics. This is the optional, explicit decimation step described in
+//! 003-marching-cubes.md: it never runs unless requested, and the same mesh
+//! and target always produce the same result.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Mesh;
+
+/// Weight of the constraint planes that keep open boundaries in place.
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+/// When to stop collapsing edges.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DecimationTarget {
+    /// Collapse edges until at most `count` triangles remain.
+    TriangleCount { count: usize },
+    /// Collapse edges while the quadric error stays within `max_error`.
+    ///
+    /// The error of a collapse is the sum of squared distances from the new
+    /// vertex to the planes of the original triangles around it, so
+    /// `max_error` is a distance in world units.
+    MaxError { max_error: f32 },
+}
+
+/// Symmetric 4x4 error quadric, stored as its upper triangle.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Quadric of the plane ax + by + cz + d = 0 (unit normal), scaled by `weight`.
+    fn plane(n: [f64; 3], d: f64, weight: f64) -> Self {
+        let [a, b, c] = n;
+        #[rustfmt::skip]
+        let q = [
+            a * a, a * b, a * c, a * d,
+            b * b, b * c, b * d,
+            c * c, c * d,
+            d * d,
+        ];
+        Self(q.map(|v| v * weight))
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (q, o) in self.0.iter_mut().zip(other.0.iter()) {
+            *q += o;
+        }
+    }
+
+    /// Error v^T Q v for the homogeneous point (p, 1).
+    fn error(&self, p: [f64; 3]) -> f64 {
+        let q = &self.0;
+        let [x, y, z] = p;
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// Position minimizing the error, if the quadric is well conditioned.
+    fn minimizer(&self) -> Option<[f64; 3]> {
+        let q = &self.0;
+        let a = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let b = [-q[3], -q[6], -q[8]];
+
+        let det = |m: [[f64; 3]; 3]| {
+            m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+        };
+
+        let scale = a.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+        let d = det(a);
+        if scale == 0.0 || d.abs() <= 1e-9 * scale * scale * scale {
+            return None;
+        }
+
+        // Cramer's rule
+        let mut x = [0.0; 3];
+        for (col, value) in x.iter_mut().enumerate() {
+            let mut m = a;
+            for row in 0..3 {
+                m[row][col] = b[row];
+            }
+            *value = det(m) / d;
+        }
+        Some(x)
+    }
+}
+
+/// A candidate edge collapse in the priority queue.
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    cost: f64,
+    u: u32,
+    v: u32,
+    stamp_u: u32,
+    stamp_v: u32,
+    position: [f64; 3],
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    /// Reversed so the max-heap pops the cheapest collapse; ties break on vertex ids.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.u.cmp(&self.u))
+            .then_with(|| other.v.cmp(&self.v))
+    }
+}
+
+/// Working state for one decimation run.
+struct Decimator {
+    positions: Vec<[f64; 3]>,
+    faces: Vec<[u32; 3]>,
+    face_alive: Vec<bool>,
+    vertex_faces: Vec<Vec<u32>>,
+    vertex_removed: Vec<bool>,
+    boundary: Vec<bool>,
+    quadrics: Vec<Quadric>,
+    stamps: Vec<u32>,
+    /// Per-vertex attributes, interpolated as vertices merge.
+    normals: Option<Vec<[f64; 3]>>,
+    scalars: Option<Vec<f64>>,
+}
+
+impl Decimator {
+    fn new(mesh: &Mesh) -> Self {
+        let n = mesh.vertex_count();
+        let positions: Vec<[f64; 3]> = mesh
+            .positions
+            .chunks_exact(3)
+            .map(|p| [p[0] as f64, p[1] as f64, p[2] as f64])
+            .collect();
+
+        let faces: Vec<[u32; 3]> = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+
+        // Degenerate input triangles carry no surface and are dropped
+        let face_alive: Vec<bool> = faces
+            .iter()
+            .map(|f| f[0] != f[1] && f[1] != f[2] && f[0] != f[2])
+            .collect();
+
+        let mut vertex_faces = vec![Vec::new(); n];
+        for (fi, f) in faces.iter().enumerate() {
+            if face_alive[fi] {
+                for &v in f {
+                    vertex_faces[v as usize].push(fi as u32);
+                }
+            }
+        }
+
+        let mut state = Self {
+            positions,
+            faces,
+            face_alive,
+            vertex_faces,
+            vertex_removed: vec![false; n],
+            boundary: vec![false; n],
+            quadrics: vec![Quadric::default(); n],
+            stamps: vec![0; n],
+            normals: mesh
+                .normals
+                .as_ref()
+                .map(|ns| ns.chunks_exact(3).map(|v| [v[0] as f64, v[1] as f64, v[2] as f64]).collect()),
+            scalars: mesh.scalars.as_ref().map(|s| s.iter().map(|&v| v as f64).collect()),
+        };
+        state.init_quadrics();
+        state
+    }
+
+    fn face_normal(&self, f: [u32; 3]) -> [f64; 3] {
+        let p0 = self.positions[f[0] as usize];
+        let e1 = sub(self.positions[f[1] as usize], p0);
+        let e2 = sub(self.positions[f[2] as usize], p0);
+        cross(e1, e2)
+    }
+
+    fn init_quadrics(&mut self) {
+        let mut edge_faces: std::collections::BTreeMap<(u32, u32), Vec<u32>> = Default::default();
+
+        for fi in 0..self.faces.len() {
+            if !self.face_alive[fi] {
+                continue;
+            }
+            let f = self.faces[fi];
+            for k in 0..3 {
+                let (a, b) = (f[k], f[(k + 1) % 3]);
+                edge_faces.entry((a.min(b), a.max(b))).or_default().push(fi as u32);
+            }
+
+            let n = normalize(self.face_normal(f));
+            if n == [0.0; 3] {
+                continue;
+            }
+            let d = -dot(n, self.positions[f[0] as usize]);
+            let q = Quadric::plane(n, d, 1.0);
+            for &v in &f {
+                self.quadrics[v as usize].add(&q);
+            }
+        }
+
+        // Constraint planes perpendicular to boundary edges keep borders in place
+        for (&(a, b), faces) in &edge_faces {
+            if faces.len() != 1 {
+                continue;
+            }
+            self.boundary[a as usize] = true;
+            self.boundary[b as usize] = true;
+
+            let face_n = normalize(self.face_normal(self.faces[faces[0] as usize]));
+            let pa = self.positions[a as usize];
+            let edge = sub(self.positions[b as usize], pa);
+            let n = normalize(cross(edge, face_n));
+            if n == [0.0; 3] {
+                continue;
+            }
+            let d = -dot(n, pa);
+            let q = Quadric::plane(n, d, BOUNDARY_WEIGHT);
+            self.quadrics[a as usize].add(&q);
+            self.quadrics[b as usize].add(&q);
+        }
+    }
+
+    /// Best position and cost for collapsing the edge (u, v).
+    fn plan(&self, u: u32, v: u32) -> Collapse {
+        let mut q = self.quadrics[u as usize];
+        q.add(&self.quadrics[v as usize]);
+
+        let pu = self.positions[u as usize];
+        let pv = self.positions[v as usize];
+
+        let position = match q.minimizer() {
+            Some(p) => p,
+            None => {
+                // Fall back to the best of the endpoints and the midpoint, in fixed order
+                let mid = [(pu[0] + pv[0]) / 2.0, (pu[1] + pv[1]) / 2.0, (pu[2] + pv[2]) / 2.0];
+                let mut best = pu;
+                for candidate in [pv, mid] {
+                    if q.error(candidate) < q.error(best) {
+                        best = candidate;
+                    }
+                }
+                best
+            }
+        };
+
+        Collapse {
+            cost: q.error(position).max(0.0),
+            u,
+            v,
+            stamp_u: self.stamps[u as usize],
+            stamp_v: self.stamps[v as usize],
+            position,
+        }
+    }
+
+    /// Sorted, deduplicated neighbours of a vertex.
+    fn neighbours(&self, v: u32) -> Vec<u32> {
+        let mut result: Vec<u32> = self.vertex_faces[v as usize]
+            .iter()
+            .flat_map(|&fi| self.faces[fi as usize])
+            .filter(|&w| w != v)
+            .collect();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    /// Check that collapsing (u, v) keeps the mesh manifold and does not fold faces over.
+    fn is_valid(&self, c: &Collapse) -> bool {
+        let (u, v) = (c.u, c.v);
+
+        let shared_faces = self.vertex_faces[u as usize]
+            .iter()
+            .filter(|&&fi| self.faces[fi as usize].contains(&v))
+            .count();
+        if shared_faces == 0 {
+            return false;
+        }
+
+        // Link condition: the edge's opposite vertices must be the only common neighbours
+        let nu = self.neighbours(u);
+        let nv = self.neighbours(v);
+        let common = nu.iter().filter(|w| nv.binary_search(w).is_ok()).count();
+        if common != shared_faces {
+            return false;
+        }
+
+        // Joining two boundaries through an interior edge would pinch the surface
+        if shared_faces > 1 && self.boundary[u as usize] && self.boundary[v as usize] {
+            return false;
+        }
+
+        // Reject collapses that flip or flatten any surviving triangle
+        for &w in &[u, v] {
+            for &fi in &self.vertex_faces[w as usize] {
+                let f = self.faces[fi as usize];
+                if f.contains(&u) && f.contains(&v) {
+                    continue;
+                }
+                let before = self.face_normal(f);
+                let mut moved = f.map(|x| self.positions[x as usize]);
+                for (k, &x) in f.iter().enumerate() {
+                    if x == w {
+                        moved[k] = c.position;
+                    }
+                }
+                let after = cross(sub(moved[1], moved[0]), sub(moved[2], moved[0]));
+                if dot(before, after) <= 0.0 {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Collapse v into u at the planned position. Returns the number of faces removed.
+    fn collapse(&mut self, c: &Collapse) -> usize {
+        let (u, v) = (c.u as usize, c.v as usize);
+
+        // Attribute blend parameter: projection of the new position onto the edge
+        let edge = sub(self.positions[v], self.positions[u]);
+        let len_sq = dot(edge, edge);
+        let t = if len_sq > 0.0 {
+            (dot(sub(c.position, self.positions[u]), edge) / len_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        if let Some(normals) = self.normals.as_mut() {
+            let blended = lerp(normals[u], normals[v], t);
+            normals[u] = normalize(blended);
+        }
+        if let Some(scalars) = self.scalars.as_mut() {
+            scalars[u] = scalars[u] + t * (scalars[v] - scalars[u]);
+        }
+
+        self.positions[u] = c.position;
+        let qv = self.quadrics[v];
+        self.quadrics[u].add(&qv);
+        self.boundary[u] |= self.boundary[v];
+        self.vertex_removed[v] = true;
+        self.stamps[u] += 1;
+
+        let mut removed = 0;
+        let v_faces = std::mem::take(&mut self.vertex_faces[v]);
+        for fi in v_faces {
+            let f = &mut self.faces[fi as usize];
+            if f.contains(&(u as u32)) {
+                self.face_alive[fi as usize] = false;
+                removed += 1;
+                // Detach the dead face from its third vertex
+                for &w in f.iter() {
+                    if w as usize != u && w as usize != v {
+                        self.vertex_faces[w as usize].retain(|&x| x != fi);
+                    }
+                }
+            } else {
+                for w in f.iter_mut() {
+                    if *w as usize == v {
+                        *w = u as u32;
+                    }
+                }
+                self.vertex_faces[u].push(fi);
+            }
+        }
+
+        let alive = &self.face_alive;
+        self.vertex_faces[u].retain(|&fi| alive[fi as usize]);
+        self.vertex_faces[u].sort_unstable();
+
+        removed
+    }
+}
+
+impl Mesh {
+    /// Simplify the mesh by deterministic quadric-error edge collapses.
+    ///
+    /// Edges are collapsed cheapest first (ties broken by vertex index) until the
+    /// target is met. Collapses that would make the mesh non-manifold or fold a
+    /// triangle over are skipped, and open boundaries are held in place by
+    /// constraint planes. Normals and scalars of merged vertices are
+    /// interpolated along the collapsed edge.
+    ///
+    /// Surviving vertices keep their relative order and triangles keep their
+    /// original order, so the output is fully determined by the input.
+    pub fn decimate(&self, target: DecimationTarget) -> Mesh {
+        let mut state = Decimator::new(self);
+        let mut live = state.face_alive.iter().filter(|&&a| a).count();
+
+        let (target_count, max_cost) = match target {
+            DecimationTarget::TriangleCount { count } => (count, f64::INFINITY),
+            DecimationTarget::MaxError { max_error } => (0, (max_error as f64).powi(2)),
+        };
+
+        // Seed the queue with every edge, in sorted order
+        let mut edges: Vec<(u32, u32)> = Vec::new();
+        for (fi, f) in state.faces.iter().enumerate() {
+            if state.face_alive[fi] {
+                for k in 0..3 {
+                    let (a, b) = (f[k], f[(k + 1) % 3]);
+                    edges.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut heap: BinaryHeap<Collapse> = edges.iter().map(|&(a, b)| state.plan(a, b)).collect();
+
+        while live > target_count {
+            let Some(c) = heap.pop() else { break };
+
+            // Skip entries made stale by earlier collapses
+            if state.vertex_removed[c.u as usize]
+                || state.vertex_removed[c.v as usize]
+                || state.stamps[c.u as usize] != c.stamp_u
+                || state.stamps[c.v as usize] != c.stamp_v
+            {
+                continue;
+            }
+
+            if c.cost > max_cost {
+                break;
+            }
+
+            if !state.is_valid(&c) {
+                continue;
+            }
+
+            live -= state.collapse(&c);
+
+            // Re-plan every edge around the merged vertex
+            for w in state.neighbours(c.u) {
+                let (a, b) = (c.u.min(w), c.u.max(w));
+                heap.push(state.plan(a, b));
+            }
+        }
+
+        state.into_mesh(self)
+    }
+}
+
+impl Decimator {
+    /// Compact surviving vertices and faces into a new mesh.
+    fn into_mesh(self, source: &Mesh) -> Mesh {
+        let n = self.positions.len();
+        let mut used = vec![false; n];
+        for (fi, f) in self.faces.iter().enumerate() {
+            if self.face_alive[fi] {
+                for &v in f {
+                    used[v as usize] = true;
+                }
+            }
+        }
+
+        let mut remap = vec![u32::MAX; n];
+        let mut positions = Vec::new();
+        let mut normals = self.normals.as_ref().map(|_| Vec::new());
+        let mut scalars = self.scalars.as_ref().map(|_| Vec::new());
+        for v in 0..n {
+            if !used[v] {
+                continue;
+            }
+            remap[v] = (positions.len() / 3) as u32;
+            positions.extend(self.positions[v].iter().map(|&c| c as f32));
+            if let (Some(out), Some(src)) = (normals.as_mut(), self.normals.as_ref()) {
+                out.extend(src[v].iter().map(|&c| c as f32));
+            }
+            if let (Some(out), Some(src)) = (scalars.as_mut(), self.scalars.as_ref()) {
+                out.push(src[v] as f32);
+            }
+        }
+
+        let indices = self
+            .faces
+            .iter()
+            .enumerate()
+            .filter(|(fi, _)| self.face_alive[*fi])
+            .flat_map(|(_, f)| f.map(|v| remap[v as usize]))
+            .collect();
+
+        Mesh {
+            positions,
+            indices,
+            normals,
+            scalars,
+            material_id: source.material_id.clone(),
+            shading: source.shading,
+        }
+    }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let len = dot(a, a).sqrt();
+    if len > 1e-20 {
+        [a[0] / len, a[1] / len, a[2] / len]
+    } else {
+        [0.0; 3]
+    }
+}
+
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [
+        a[0] + t * (b[0] - a[0]),
+        a[1] + t * (b[1] - a[1]),
+        a[2] + t * (b[2] - a[2]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marching_cubes::marching_cubes;
+    use crate::marching_cubes::test_support::sphere_volume;
+
+    fn sphere_mesh() -> Mesh {
+        marching_cubes(&sphere_volume([24; 3], 0.7), 0.0)
+    }
+
+    fn is_watertight(mesh: &Mesh) -> bool {
+        let mut edges = std::collections::HashMap::new();
+        for t in mesh.indices.chunks(3) {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        edges.values().all(|&count| count == 2)
+    }
+
+    #[test]
+    fn test_decimate_to_triangle_count() {
+        let mesh = sphere_mesh();
+        let target = mesh.triangle_count() / 4;
+        let decimated = mesh.decimate(DecimationTarget::TriangleCount { count: target });
+
+        assert!(decimated.triangle_count() <= target);
+        assert!(decimated.triangle_count() > target / 2);
+        assert!(is_watertight(&decimated), "Decimation broke the closed surface");
+        assert_eq!(decimated.normals.as_ref().unwrap().len(), decimated.positions.len());
+
+        // Simplified vertices stay close to the sphere
+        for p in decimated.positions.chunks(3) {
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            assert!((r - 0.7).abs() < 0.05, "Vertex drifted off surface: r = {}", r);
+        }
+    }
+
+    #[test]
+    fn test_decimate_error_bound() {
+        let mesh = sphere_mesh();
+        let loose = mesh.decimate(DecimationTarget::MaxError { max_error: 0.02 });
+        let tight = mesh.decimate(DecimationTarget::MaxError { max_error: 0.001 });
+
+        assert!(loose.triangle_count() < tight.triangle_count());
+        assert!(tight.triangle_count() <= mesh.triangle_count());
+    }
+
+    #[test]
+    fn test_decimate_flat_patch_keeps_boundary() {
+        // Planar 6x6 grid: all interior vertices can go, the border must stay put
+        let n = 6;
+        let mut positions = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                positions.extend_from_slice(&[x as f32, y as f32, 0.0]);
+            }
+        }
+        let mut indices = Vec::new();
+        for y in 0..(n - 1) {
+            for x in 0..(n - 1) {
+                let i = (x + y * n) as u32;
+                let n = n as u32;
+                indices.extend_from_slice(&[i, i + 1, i + n + 1, i, i + n + 1, i + n]);
+            }
+        }
+        let mesh = Mesh::new(positions, indices);
+        let decimated = mesh.decimate(DecimationTarget::MaxError { max_error: 1e-4 });
+
+        assert!(decimated.triangle_count() < mesh.triangle_count());
+        for p in decimated.positions.chunks(3) {
+            assert!(p[2].abs() < 1e-5);
+            assert!((-1e-4..=5.0001).contains(&p[0]) && (-1e-4..=5.0001).contains(&p[1]));
+        }
+    }
+
+    #[test]
+    fn test_decimate_is_deterministic() {
+        let mesh = sphere_mesh();
+        let target = DecimationTarget::TriangleCount { count: 300 };
+        let a = mesh.decimate(target);
+        let b = mesh.decimate(target);
+        assert_eq!(a.positions, b.positions);
+        assert_eq!(a.indices, b.indices);
+    }
+}
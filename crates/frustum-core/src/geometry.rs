@@ -1,9 +1,10 @@
 //! Geometry primitives: point clouds, polylines, and triangle meshes.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// A point cloud with per-point positions and optional scalar values.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PointCloud {
     /// Flattened array of vertex positions [x0, y0, z0, x1, y1, z1, ...].
     pub positions: Vec<f32>,
@@ -51,10 +52,13 @@ impl PointCloud {
 }
 
 /// A polyline defined by a sequence of vertices.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Polyline {
     /// Flattened array of vertex positions [x0, y0, z0, x1, y1, z1, ...].
     pub positions: Vec<f32>,
+    /// Optional per-vertex scalar values for colormap mapping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scalars: Option<Vec<f32>>,
     /// Uniform line width in pixels.
     pub line_width: f32,
     /// Material ID reference.
@@ -67,11 +71,18 @@ impl Polyline {
     pub fn new(positions: Vec<f32>, line_width: f32) -> Self {
         Self {
             positions,
+            scalars: None,
             line_width,
             material_id: None,
         }
     }
 
+    /// Set per-vertex scalar values for colormap mapping.
+    pub fn with_scalars(mut self, scalars: Vec<f32>) -> Self {
+        self.scalars = Some(scalars);
+        self
+    }
+
     /// Set material ID.
     pub fn with_material(mut self, material_id: impl Into<String>) -> Self {
         self.material_id = Some(material_id.into());
@@ -80,7 +91,7 @@ impl Polyline {
 }
 
 /// An indexed triangle mesh with optional normals and scalar values.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Mesh {
     /// Flattened array of vertex positions [x0, y0, z0, x1, y1, z1, ...].
     pub positions: Vec<f32>,
@@ -93,10 +104,25 @@ pub struct Mesh {
     /// Material ID reference.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub material_id: Option<String>,
+    /// Shading mode. When absent, vertex normals are used if present and
+    /// face normals otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shading: Option<Shading>,
+}
+
+/// How a mesh's surface normals are interpolated when lit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Shading {
+    /// One normal per triangle, from its winding; stored normals are ignored.
+    Flat,
+    /// Per-vertex normals interpolated across triangles; generated with
+    /// angle weighting if the mesh has none.
+    Smooth,
 }
 
 /// Tick generation mode for axes.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "mode", rename_all = "snake_case")]
 pub enum TickSpec {
     /// Fixed tick positions in world coordinates.
@@ -114,7 +140,7 @@ impl Default for TickSpec {
 }
 
 /// Label specification for axis ticks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LabelSpec {
     /// Whether to generate label placeholders.
     pub show: bool,
@@ -135,7 +161,7 @@ impl Default for LabelSpec {
 }
 
 /// A label placeholder with position and text.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Label {
     /// World-space position.
     pub position: [f32; 3],
@@ -147,7 +173,7 @@ pub struct Label {
 ///
 /// Axes expand into Lines primitives for rendering.
 /// No special-casing in the renderer.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AxisBundle {
     /// Unique identifier.
     pub id: String,
@@ -166,14 +192,14 @@ pub struct AxisBundle {
 }
 
 /// Bounds for axis bundle.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct AxisBounds {
     pub min: [f32; 3],
     pub max: [f32; 3],
 }
 
 /// Which axis (X, Y, or Z).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Axis {
     X,
@@ -398,6 +424,7 @@ impl Mesh {
             normals: None,
             scalars: None,
             material_id: None,
+            shading: None,
         }
     }
 
@@ -419,6 +446,12 @@ impl Mesh {
         self
     }
 
+    /// Set the shading mode.
+    pub fn with_shading(mut self, shading: Shading) -> Self {
+        self.shading = Some(shading);
+        self
+    }
+
     /// Number of vertices in the mesh.
     pub fn vertex_count(&self) -> usize {
         self.positions.len() / 3
@@ -0,0 +1,514 @@
+//! glTF 2.0 export of whole scenes.
+//!
+//! Produces binary glTF (GLB) or JSON glTF with an embedded buffer. Colors
+//! are resolved the way the renderer resolves them and baked into `COLOR_0`:
+//! - Meshes become indexed triangle primitives, with normals unless flat shaded
+//! - Point clouds become `POINTS` and polylines `LINE_STRIP` primitives
+//! - Axes are expanded into line strips; their text labels are not exported
+//! - The scene camera becomes a glTF camera node
+//!
+//! Points, lines and axes use `KHR_materials_unlit`, as do meshes when the
+//! scene has no enabled light. Output depends only on the scene, so the same
+//! scene always produces the same bytes.
+
+use std::fs;
+use std::path::Path;
+
+use glam::Vec3;
+use serde_json::{json, Map, Value};
+
+use crate::camera::Projection;
+use crate::geometry::{Mesh, PointCloud, Polyline, Shading};
+use crate::materials::Material;
+use crate::normals::NormalOptions;
+use crate::scene::{Scene, SceneElement};
+
+// Default colors for primitives without materials, as in the renderer
+const DEFAULT_MESH_COLOR: [f32; 3] = [0.7, 0.7, 0.7];
+const DEFAULT_POINT_COLOR: [f32; 3] = [1.0, 0.5, 0.0];
+const DEFAULT_LINE_COLOR: [f32; 3] = [0.2, 0.8, 0.2];
+const DEFAULT_AXIS_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+
+// glTF enums
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const MODE_POINTS: u32 = 0;
+const MODE_LINE_STRIP: u32 = 3;
+const MODE_TRIANGLES: u32 = 4;
+
+/// Export a scene as a binary glTF (GLB) file in memory.
+pub fn scene_to_glb(scene: &Scene) -> Vec<u8> {
+    let (document, buffer) = GltfBuilder::build(scene);
+    let mut json = serde_json::to_vec(&document).expect("glTF document serializes");
+    pad(&mut json, b' ');
+    let mut bin = buffer;
+    pad(&mut bin, 0);
+
+    let mut total = 12 + 8 + json.len();
+    if !bin.is_empty() {
+        total += 8 + bin.len();
+    }
+    let mut out = Vec::with_capacity(total);
+    out.extend_from_slice(b"glTF");
+    out.extend_from_slice(&2u32.to_le_bytes());
+    out.extend_from_slice(&(total as u32).to_le_bytes());
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"JSON");
+    out.extend_from_slice(&json);
+    if !bin.is_empty() {
+        out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"BIN\0");
+        out.extend_from_slice(&bin);
+    }
+    out
+}
+
+/// Export a scene as a JSON glTF document with the buffer embedded as a data URI.
+pub fn scene_to_gltf(scene: &Scene) -> String {
+    let (mut document, buffer) = GltfBuilder::build(scene);
+    if let Some(buffers) = document.get_mut("buffers").and_then(Value::as_array_mut) {
+        buffers[0]["uri"] = Value::String(format!("data:application/octet-stream;base64,{}", base64(&buffer)));
+    }
+    serde_json::to_string_pretty(&document).expect("glTF document serializes")
+}
+
+/// Write a scene as GLB, or as JSON glTF if the path ends in `.gltf`.
+pub fn write_gltf(scene: &Scene, path: impl AsRef<Path>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let is_json = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("gltf"));
+    if is_json {
+        fs::write(path, scene_to_gltf(scene))
+    } else {
+        fs::write(path, scene_to_glb(scene))
+    }
+}
+
+/// Accumulates glTF objects and the binary buffer they reference.
+#[derive(Default)]
+struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    materials: Vec<Value>,
+    /// (unlit, blend) of each material, for reuse.
+    material_keys: Vec<(bool, bool)>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+}
+
+impl GltfBuilder {
+    fn build(scene: &Scene) -> (Value, Vec<u8>) {
+        let mut builder = GltfBuilder::default();
+        let lit = scene.light.is_some_and(|light| light.enabled);
+
+        for (index, element) in scene.elements.iter().enumerate() {
+            match element {
+                SceneElement::Mesh(mesh) => builder.add_mesh(scene, mesh, format!("mesh_{}", index), lit),
+                SceneElement::PointCloud(pc) => builder.add_points(scene, pc, format!("points_{}", index)),
+                SceneElement::Polyline(line) => {
+                    let material = line.material_id.as_ref().and_then(|id| scene.get_material(id));
+                    builder.add_line(line, material, DEFAULT_LINE_COLOR, format!("polyline_{}", index));
+                }
+                SceneElement::Axes(axes) => {
+                    let (polylines, _labels) = axes.expand();
+                    for (k, line) in polylines.iter().enumerate() {
+                        builder.add_line(line, None, DEFAULT_AXIS_COLOR, format!("{}_{}", axes.id, k));
+                    }
+                }
+            }
+        }
+        builder.add_camera(scene);
+
+        let scene_nodes: Vec<usize> = (0..builder.nodes.len()).collect();
+        let mut document = json!({
+            "asset": { "version": "2.0", "generator": "Frustum" },
+            "scene": 0,
+            "scenes": [{ "nodes": scene_nodes }],
+            "nodes": builder.nodes,
+            "cameras": [camera_object(scene)],
+        });
+        let root = document.as_object_mut().unwrap();
+        if !builder.meshes.is_empty() {
+            root.insert("meshes".into(), Value::Array(builder.meshes));
+            root.insert("materials".into(), Value::Array(builder.materials));
+            root.insert("accessors".into(), Value::Array(builder.accessors));
+            root.insert("bufferViews".into(), Value::Array(builder.buffer_views));
+            root.insert("buffers".into(), json!([{ "byteLength": builder.buffer.len() }]));
+        }
+        if builder.material_keys.iter().any(|&(unlit, _)| unlit) {
+            root.insert("extensionsUsed".into(), json!(["KHR_materials_unlit"]));
+        }
+        (document, builder.buffer)
+    }
+
+    fn add_mesh(&mut self, scene: &Scene, mesh: &Mesh, name: String, lit: bool) {
+        if mesh.positions.len() < 3 || mesh.indices.len() < 3 {
+            return;
+        }
+        // Smooth shading without stored normals generates them; flat ignores them
+        let generated;
+        let mesh = match mesh.shading {
+            Some(Shading::Smooth) if mesh.normals.is_none() => {
+                generated = mesh.compute_normals(&NormalOptions::new());
+                &generated
+            }
+            _ => mesh,
+        };
+        let material = mesh.material_id.as_ref().and_then(|id| scene.get_material(id));
+        let colors = vertex_colors(mesh.vertex_count(), material, mesh.scalars.as_deref(), DEFAULT_MESH_COLOR);
+
+        let mut attributes = Map::new();
+        attributes.insert("POSITION".into(), self.vec3_accessor(&mesh.positions, true).into());
+        let normals = mesh.normals.as_ref().filter(|n| n.len() == mesh.positions.len());
+        if let (Some(normals), false) = (normals, mesh.shading == Some(Shading::Flat)) {
+            attributes.insert("NORMAL".into(), self.vec3_accessor(normals, false).into());
+        }
+        attributes.insert("COLOR_0".into(), self.color_accessor(&colors).into());
+        let indices = self.index_accessor(&mesh.indices);
+        let material = self.material(!lit, alpha(material) < 1.0);
+        let primitive = json!({ "attributes": attributes, "indices": indices, "mode": MODE_TRIANGLES, "material": material });
+        self.add_node(name, primitive);
+    }
+
+    fn add_points(&mut self, scene: &Scene, pc: &PointCloud, name: String) {
+        if pc.positions.len() < 3 {
+            return;
+        }
+        let material = pc.material_id.as_ref().and_then(|id| scene.get_material(id));
+        let colors = vertex_colors(pc.len(), material, pc.scalars.as_deref(), DEFAULT_POINT_COLOR);
+        let position = self.vec3_accessor(&pc.positions, true);
+        let color = self.color_accessor(&colors);
+        let material = self.material(true, alpha(material) < 1.0);
+        let primitive = json!({
+            "attributes": { "POSITION": position, "COLOR_0": color },
+            "mode": MODE_POINTS,
+            "material": material,
+        });
+        self.add_node(name, primitive);
+    }
+
+    fn add_line(&mut self, line: &Polyline, material: Option<&Material>, default: [f32; 3], name: String) {
+        if line.positions.len() < 6 {
+            return;
+        }
+        let colors = vertex_colors(line.positions.len() / 3, material, line.scalars.as_deref(), default);
+        let position = self.vec3_accessor(&line.positions, true);
+        let color = self.color_accessor(&colors);
+        let material = self.material(true, alpha(material) < 1.0);
+        let primitive = json!({
+            "attributes": { "POSITION": position, "COLOR_0": color },
+            "mode": MODE_LINE_STRIP,
+            "material": material,
+        });
+        self.add_node(name, primitive);
+    }
+
+    fn add_node(&mut self, name: String, primitive: Value) {
+        self.meshes.push(json!({ "name": name, "primitives": [primitive] }));
+        self.nodes.push(json!({ "name": name, "mesh": self.meshes.len() - 1 }));
+    }
+
+    fn add_camera(&mut self, scene: &Scene) {
+        // glTF cameras look down their local -Z with +Y up: the inverse view matrix
+        let camera = &scene.camera;
+        let (_, rotation, translation) = camera.view_matrix().inverse().to_scale_rotation_translation();
+        let rotation = if rotation.is_finite() { rotation.normalize() } else { glam::Quat::IDENTITY };
+        self.nodes.push(json!({
+            "name": "camera",
+            "camera": 0,
+            "translation": translation.to_array(),
+            "rotation": rotation.to_array(),
+        }));
+    }
+
+    /// Append 4-byte aligned data as a buffer view and return its index.
+    fn buffer_view(&mut self, bytes: &[u8], target: u32) -> usize {
+        pad(&mut self.buffer, 0);
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.len() - 1
+    }
+
+    fn vec3_accessor(&mut self, data: &[f32], bounds: bool) -> usize {
+        let data = &data[..data.len() / 3 * 3];
+        let view = self.buffer_view(&f32_bytes(data), ARRAY_BUFFER);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": data.len() / 3,
+            "type": "VEC3",
+        });
+        // POSITION accessors require bounds
+        if bounds {
+            let mut min = Vec3::splat(f32::INFINITY);
+            let mut max = Vec3::splat(f32::NEG_INFINITY);
+            for p in data.chunks_exact(3).map(Vec3::from_slice).filter(|p| p.is_finite()) {
+                min = min.min(p);
+                max = max.max(p);
+            }
+            if min.x > max.x {
+                (min, max) = (Vec3::ZERO, Vec3::ZERO);
+            }
+            accessor["min"] = json!(min.to_array());
+            accessor["max"] = json!(max.to_array());
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn color_accessor(&mut self, colors: &[[f32; 4]]) -> usize {
+        let flat: Vec<f32> = colors.iter().flatten().copied().collect();
+        let view = self.buffer_view(&f32_bytes(&flat), ARRAY_BUFFER);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": colors.len(),
+            "type": "VEC4",
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn index_accessor(&mut self, indices: &[u32]) -> usize {
+        let indices = &indices[..indices.len() / 3 * 3];
+        let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let view = self.buffer_view(&bytes, ELEMENT_ARRAY_BUFFER);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// White base material; vertex colors carry the actual color.
+    fn material(&mut self, unlit: bool, blend: bool) -> usize {
+        if let Some(index) = self.material_keys.iter().position(|&key| key == (unlit, blend)) {
+            return index;
+        }
+        let mut material = json!({
+            "pbrMetallicRoughness": { "baseColorFactor": [1.0, 1.0, 1.0, 1.0], "metallicFactor": 0.0, "roughnessFactor": 1.0 },
+            "doubleSided": true,
+        });
+        if unlit {
+            material["extensions"] = json!({ "KHR_materials_unlit": {} });
+        }
+        if blend {
+            material["alphaMode"] = json!("BLEND");
+        }
+        self.materials.push(material);
+        self.material_keys.push((unlit, blend));
+        self.materials.len() - 1
+    }
+}
+
+fn camera_object(scene: &Scene) -> Value {
+    let camera = &scene.camera;
+    match camera.projection {
+        Projection::Perspective => json!({
+            "type": "perspective",
+            "perspective": {
+                "yfov": camera.fov_or_height.to_radians(),
+                "znear": camera.near,
+                "zfar": camera.far,
+            },
+        }),
+        // The aspect ratio is a render setting, so the horizontal extent matches the vertical
+        Projection::Orthographic => json!({
+            "type": "orthographic",
+            "orthographic": {
+                "xmag": camera.fov_or_height / 2.0,
+                "ymag": camera.fov_or_height / 2.0,
+                "znear": camera.near,
+                "zfar": camera.far,
+            },
+        }),
+    }
+}
+
+/// Per-vertex RGBA colors, resolved as in the renderer.
+fn vertex_colors(count: usize, material: Option<&Material>, scalars: Option<&[f32]>, default: [f32; 3]) -> Vec<[f32; 4]> {
+    let a = alpha(material);
+    let rgba = |c: [f32; 3]| [c[0], c[1], c[2], a];
+    match (material, scalars) {
+        (Some(Material::ScalarMapped(sm)), Some(scalars)) => (0..count)
+            .map(|i| rgba(sm.color(scalars.get(i).copied().unwrap_or(0.0))))
+            .collect(),
+        (Some(Material::ScalarMapped(sm)), None) => vec![rgba(sm.color((sm.range[0] + sm.range[1]) / 2.0)); count],
+        (Some(Material::Solid(m)), _) => vec![m.color; count],
+        (None, _) => vec![rgba(default); count],
+    }
+}
+
+fn alpha(material: Option<&Material>) -> f32 {
+    match material {
+        Some(Material::Solid(m)) => m.color[3],
+        _ => 1.0,
+    }
+}
+
+fn f32_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn pad(bytes: &mut Vec<u8>, fill: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(fill);
+    }
+}
+
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for k in 0..4 {
+            if k <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * k) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera;
+    use crate::geometry::{AxisBounds, AxisBundle};
+    use crate::lighting::Light;
+    use crate::materials::{ScalarMappedMaterial, SolidMaterial};
+    use crate::scene::Bounds;
+
+    fn scene() -> Scene {
+        let bounds = Bounds { min: [-1.0; 3], max: [1.0; 3] };
+        Scene::new(Camera::perspective([0.0, 0.0, 5.0], [0.0, 0.0, 0.0], 45.0), bounds)
+            .add_material(Material::Solid(SolidMaterial::with_alpha("glass", [0.2, 0.4, 0.6, 0.5])))
+            .add_material(Material::ScalarMapped(ScalarMappedMaterial::new("heat", "viridis", [0.0, 1.0])))
+            .add_mesh(
+                Mesh::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], vec![0, 1, 2])
+                    .with_scalars(vec![0.0, 0.5, 1.0])
+                    .with_material("heat")
+                    .with_shading(Shading::Smooth),
+            )
+            .add_point_cloud(PointCloud::new(vec![0.0, 0.0, 1.0, 0.5, 0.5, 1.0], 3.0).with_material("glass"))
+            .add_polyline(Polyline::new(vec![-1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0], 1.0))
+            .add_axes(AxisBundle::new("axes", AxisBounds { min: [-1.0; 3], max: [1.0; 3] }))
+            .with_light(Light::new([0.0, 0.0, 1.0], 1.0))
+    }
+
+    /// Split a GLB into its JSON document and binary chunk.
+    fn parse_glb(glb: &[u8]) -> (Value, &[u8]) {
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32::from_le_bytes(glb[4..8].try_into().unwrap()), 2);
+        assert_eq!(u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize, glb.len());
+        let json_len = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        assert_eq!(&glb[16..20], b"JSON");
+        let document = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        let bin = &glb[20 + json_len + 8..];
+        assert_eq!(&glb[20 + json_len + 4..20 + json_len + 8], b"BIN\0");
+        (document, bin)
+    }
+
+    /// Read a float accessor's values.
+    fn read_floats(document: &Value, bin: &[u8], accessor: usize) -> Vec<f32> {
+        let accessor = &document["accessors"][accessor];
+        let view = &document["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let length = view["byteLength"].as_u64().unwrap() as usize;
+        bin[offset..offset + length]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_glb_structure_and_colors() {
+        let scene = scene();
+        let glb = scene_to_glb(&scene);
+        assert_eq!(glb.len() % 4, 0);
+        let (document, bin) = parse_glb(&glb);
+
+        let modes: Vec<u64> = document["meshes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["primitives"][0]["mode"].as_u64().unwrap())
+            .collect();
+        // Mesh, points, polyline, then the axis and tick lines
+        assert_eq!(modes[..3], [4, 0, 3]);
+        assert!(modes.len() > 6 && modes[3..].iter().all(|&m| m == 3));
+
+        // Smooth mesh gets generated normals and colormapped vertex colors
+        let mesh = &document["meshes"][0]["primitives"][0];
+        assert!(mesh["attributes"]["NORMAL"].is_u64());
+        let colors = read_floats(&document, bin, mesh["attributes"]["COLOR_0"].as_u64().unwrap() as usize);
+        let heat = ScalarMappedMaterial::new("heat", "viridis", [0.0, 1.0]);
+        assert_eq!(colors[8..11], heat.color(1.0));
+        let lit = &document["materials"][mesh["material"].as_u64().unwrap() as usize];
+        assert!(lit.get("extensions").is_none());
+
+        // Translucent points blend and stay unlit
+        let points = &document["meshes"][1]["primitives"][0];
+        let material = &document["materials"][points["material"].as_u64().unwrap() as usize];
+        assert_eq!(material["alphaMode"], "BLEND");
+        assert!(material["extensions"]["KHR_materials_unlit"].is_object());
+        let colors = read_floats(&document, bin, points["attributes"]["COLOR_0"].as_u64().unwrap() as usize);
+        assert_eq!(colors, vec![0.2, 0.4, 0.6, 0.5, 0.2, 0.4, 0.6, 0.5]);
+
+        // Camera at +Z looking at the origin needs no rotation
+        let camera_node = document["nodes"].as_array().unwrap().last().unwrap().clone();
+        assert_eq!(camera_node["translation"], json!([0.0, 0.0, 5.0]));
+        let rotation: Vec<f64> = serde_json::from_value(camera_node["rotation"].clone()).unwrap();
+        assert!((rotation[3].abs() - 1.0).abs() < 1e-6);
+        let yfov = document["cameras"][0]["perspective"]["yfov"].as_f64().unwrap();
+        assert!((yfov - 45f64.to_radians()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_export_is_deterministic() {
+        let json = scene().to_json().unwrap();
+        let first = scene_to_glb(&Scene::from_json(&json).unwrap());
+        let second = scene_to_glb(&Scene::from_json(&json).unwrap());
+        assert_eq!(first, second);
+
+        // The JSON variant embeds the same buffer
+        let gltf: Value = serde_json::from_str(&scene_to_gltf(&scene())).unwrap();
+        let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+        assert!(uri.starts_with("data:application/octet-stream;base64,"));
+        assert_eq!(base64(b"glTF!"), "Z2xURiE=");
+    }
+
+    #[test]
+    fn test_empty_scene_and_flat_shading() {
+        let bounds = Bounds { min: [0.0; 3], max: [1.0; 3] };
+        let camera = Camera::orthographic([0.0, 0.0, 5.0], [0.0, 0.0, 0.0], 4.0);
+        let empty = scene_to_glb(&Scene::new(camera.clone(), bounds));
+        let json_len = u32::from_le_bytes(empty[12..16].try_into().unwrap()) as usize;
+        assert_eq!(empty.len(), 20 + json_len);
+        let document: Value = serde_json::from_slice(&empty[20..]).unwrap();
+        assert_eq!(document["cameras"][0]["orthographic"]["ymag"], 2.0);
+
+        // Flat shading drops stored normals; unlit without a light
+        let mesh = Mesh::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], vec![0, 1, 2])
+            .with_normals(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0])
+            .with_shading(Shading::Flat);
+        let (document, _) = parse_glb(&scene_to_glb(&Scene::new(camera, bounds).add_mesh(mesh)));
+        let primitive = &document["meshes"][0]["primitives"][0];
+        assert!(primitive["attributes"].get("NORMAL").is_none());
+        assert_eq!(document["extensionsUsed"], json!(["KHR_materials_unlit"]));
+    }
+}
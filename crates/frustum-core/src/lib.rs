@@ -3,15 +3,61 @@
 //! Core scene model and geometry primitives for the Frustum rendering framework.
 
 pub mod camera;
+pub mod content_hash;
+pub mod contour;
+pub mod decimation;
 pub mod geometry;
+pub mod gltf;
 pub mod lighting;
 pub mod marching_cubes;
+pub mod marching_cubes_slabs;
 pub mod materials;
+pub mod mesh_attributes;
+pub mod mesh_io;
+pub mod migration;
+pub mod normals;
+pub mod resampling;
 pub mod scene;
+pub mod scene_binary;
+pub mod schema;
+pub mod slicing;
+pub mod smoothing;
+pub mod surface_nets;
+pub mod validation;
+pub mod volume_io;
+pub mod vtk_io;
 
 pub use camera::{Camera, Projection};
-pub use geometry::{Axis, AxisBounds, AxisBundle, Label, LabelSpec, Mesh, PointCloud, Polyline, TickSpec};
+pub use content_hash::CONTENT_HASH_DOMAIN;
+pub use contour::{ContourElevation, ContourLine, ScalarGrid};
+pub use decimation::DecimationTarget;
+pub use geometry::{Axis, AxisBounds, AxisBundle, Label, LabelSpec, Mesh, PointCloud, Polyline, Shading, TickSpec};
+pub use gltf::{scene_to_glb, scene_to_gltf, write_gltf};
 pub use lighting::Light;
-pub use marching_cubes::{marching_cubes, marching_cubes_multi, IsoSurface, Volume};
+pub use marching_cubes::{
+    marching_cubes, marching_cubes_multi, marching_cubes_with_options, marching_cubes_with_report,
+    ExtractionReport, GaussianKernel, IsoSurface, MarchingCubesOptions, VectorVolume, Volume,
+};
+pub use marching_cubes_slabs::{
+    marching_cubes_parallel, marching_cubes_slabs, IsosurfaceChunk, StreamingMarchingCubes,
+};
 pub use materials::{Colormap, Material, ScalarMappedMaterial, SolidMaterial};
-pub use scene::Scene;
+pub use mesh_attributes::MeshAttribute;
+pub use mesh_io::{
+    parse_obj, parse_off, parse_ply, parse_stl, read_mesh, write_mesh, write_obj, write_off, write_ply, write_stl,
+    FacePolicy, MeshEncoding, MeshFormat, MeshIoError, MeshReadOptions,
+};
+pub use normals::{NormalOptions, NormalWeighting};
+pub use migration::SCHEMA_VERSION;
+pub use scene::{Scene, SceneError};
+pub use scene_binary::BINARY_MAGIC;
+pub use schema::scene_json_schema;
+pub use slicing::{slice_contours, slice_volume, SliceAxis, SliceGrid, SlicePlane};
+pub use smoothing::MeshSmoothing;
+pub use surface_nets::surface_nets;
+pub use validation::{ValidationError, ValidationErrorKind, ValidationErrors};
+pub use volume_io::{
+    parse_nifti, parse_npy, parse_nrrd, parse_raw, read_nifti, read_npy, read_nrrd, read_raw, Endian,
+    RawVolumeHeader, VolumeIoError, VoxelType,
+};
+pub use vtk_io::{parse_vtk_legacy, parse_vtk_xml, read_vtk, VtkDataset, VtkIoError, VtkPolyData, VtkReadOptions};
@@ -6,13 +6,14 @@
 //! - Points, lines, and axes render unlit (flat color)
 //! - No light specified = flat colors (no implicit headlight)
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// A directional light for Lambertian shading.
 ///
 /// Light direction points toward the light source (not the direction light travels).
 /// Only affects meshes with normals; other primitives render unlit.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct Light {
     /// Direction toward the light source (normalized, world space).
     pub direction: [f32; 3],
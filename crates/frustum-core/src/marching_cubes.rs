@@ -3,20 +3,37 @@
 //! Converts a regular 3D scalar volume into a triangle mesh.
 //! This is a pure geometry generation step, deterministic and reproducible.
 
+use crate::decimation::DecimationTarget;
 use crate::geometry::Mesh;
+use crate::smoothing::MeshSmoothing;
 use serde::{Deserialize, Serialize};
 
-/// A regular 3D scalar volume.
+/// A 3D scalar volume on a regular or rectilinear grid.
+///
+/// Grid points are placed either uniformly, by `origin` and `spacing`, or by
+/// explicit per-axis `coordinates` (a rectilinear grid, e.g. stretched near
+/// walls). Voxels can be marked invalid, either by a non-finite value (NaN or ±Inf)
+/// or by the optional validity `mask`. Extraction skips every cell that touches
+/// an invalid voxel, leaving the surface open around invalid regions, and
+/// gradients never read invalid neighbours.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Volume {
     /// Scalar values stored in row-major order: values[x + y*nx + z*nx*ny]
     pub values: Vec<f32>,
     /// Dimensions (nx, ny, nz).
     pub dimensions: [usize; 3],
-    /// Grid spacing (dx, dy, dz).
+    /// Grid spacing (dx, dy, dz). Mean spacing for rectilinear grids.
     pub spacing: [f32; 3],
     /// World-space origin of voxel [0,0,0].
     pub origin: [f32; 3],
+    /// Optional validity mask in the same order as `values` (true = valid).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<Vec<bool>>,
+    /// Optional strictly increasing x, y and z grid coordinates (rectilinear grid).
+    ///
+    /// When present, these define world positions and override `spacing` and `origin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<[Vec<f32>; 3]>,
 }
 
 impl Volume {
@@ -32,9 +49,52 @@ impl Volume {
             dimensions,
             spacing,
             origin,
+            mask: None,
+            coordinates: None,
         }
     }
 
+    /// Create a volume on a rectilinear grid from per-axis coordinate vectors.
+    ///
+    /// Dimensions come from the coordinate lengths; `origin` is set to the first
+    /// coordinate and `spacing` to the mean spacing along each axis.
+    ///
+    /// # Panics
+    /// Panics if a coordinate vector is empty, not strictly increasing, or the
+    /// values length does not match the grid size.
+    pub fn rectilinear(values: Vec<f32>, x: Vec<f32>, y: Vec<f32>, z: Vec<f32>) -> Self {
+        let coordinates = [x, y, z];
+        for axis in coordinates.iter() {
+            assert!(!axis.is_empty(), "Rectilinear coordinates must not be empty");
+            assert!(
+                axis.iter().all(|c| c.is_finite()) && axis.windows(2).all(|w| w[0] < w[1]),
+                "Rectilinear coordinates must be finite and strictly increasing"
+            );
+        }
+
+        let dimensions = [coordinates[0].len(), coordinates[1].len(), coordinates[2].len()];
+        let origin = [coordinates[0][0], coordinates[1][0], coordinates[2][0]];
+        let spacing = [0, 1, 2].map(|axis| {
+            let c = &coordinates[axis];
+            if c.len() > 1 {
+                (c[c.len() - 1] - c[0]) / (c.len() - 1) as f32
+            } else {
+                1.0
+            }
+        });
+
+        let mut volume = Volume::new(values, dimensions, spacing, origin);
+        volume.coordinates = Some(coordinates);
+        volume
+    }
+
+    /// Set the validity mask (true = valid voxel).
+    pub fn with_mask(mut self, mask: Vec<bool>) -> Self {
+        assert_eq!(mask.len(), self.values.len(), "Volume mask length must match values");
+        self.mask = Some(mask);
+        self
+    }
+
     /// Get the scalar value at grid coordinates (x, y, z).
     #[inline]
     pub fn get(&self, x: usize, y: usize, z: usize) -> f32 {
@@ -42,217 +102,504 @@ impl Volume {
         self.values[x + y * nx + z * nx * ny]
     }
 
+    /// Whether the voxel at (x, y, z) is valid: finite and not masked out.
+    #[inline]
+    pub fn is_valid(&self, x: usize, y: usize, z: usize) -> bool {
+        let [nx, ny, _nz] = self.dimensions;
+        self.is_valid_index(x + y * nx + z * nx * ny)
+    }
+
+    /// Whether the voxel at flat index `i` of `values` is valid.
+    #[inline]
+    fn is_valid_index(&self, i: usize) -> bool {
+        self.values[i].is_finite() && self.mask.as_ref().is_none_or(|mask| mask[i])
+    }
+
+    /// Scalar value at (x, y, z), or NaN if the voxel is invalid.
+    #[inline]
+    pub(crate) fn valid_value(&self, x: usize, y: usize, z: usize) -> f32 {
+        if self.is_valid(x, y, z) {
+            self.get(x, y, z)
+        } else {
+            f32::NAN
+        }
+    }
+
+    /// Whether any voxel is invalid (non-finite or masked out).
+    pub fn has_invalid_voxels(&self) -> bool {
+        self.values.iter().any(|v| !v.is_finite())
+            || self.mask.as_ref().is_some_and(|mask| mask.iter().any(|&valid| !valid))
+    }
+
+    /// Whether grid points are placed by explicit per-axis coordinates.
+    pub fn is_rectilinear(&self) -> bool {
+        self.coordinates.is_some()
+    }
+
+    /// World-space coordinate of grid index `i` along `axis` (0 = x, 1 = y, 2 = z).
+    #[inline]
+    pub fn axis_coordinate(&self, axis: usize, i: usize) -> f32 {
+        self.axes().coordinate(axis, i)
+    }
+
     /// Get the world-space position for grid coordinates (x, y, z).
     #[inline]
     pub fn grid_to_world(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
-        [
-            self.origin[0] + x as f32 * self.spacing[0],
-            self.origin[1] + y as f32 * self.spacing[1],
-            self.origin[2] + z as f32 * self.spacing[2],
-        ]
+        self.axes().world(x, y, z)
     }
 
     /// Compute gradient at grid position using central differences.
-    fn gradient(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
-        let [nx, ny, nz] = self.dimensions;
+    pub(crate) fn gradient(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        central_gradient(|x, y, z| self.valid_value(x, y, z), self.dimensions, &self.axes(), x, y, z)
+    }
 
-        let dx = if x > 0 && x < nx - 1 {
-            (self.get(x + 1, y, z) - self.get(x - 1, y, z)) / (2.0 * self.spacing[0])
-        } else if x == 0 {
-            (self.get(x + 1, y, z) - self.get(x, y, z)) / self.spacing[0]
-        } else {
-            (self.get(x, y, z) - self.get(x - 1, y, z)) / self.spacing[0]
-        };
+    /// Placement of grid points along each axis.
+    pub(crate) fn axes(&self) -> GridAxes<'_> {
+        GridAxes {
+            spacing: self.spacing,
+            origin: self.origin,
+            coordinates: self.coordinates.as_ref(),
+        }
+    }
+}
 
-        let dy = if y > 0 && y < ny - 1 {
-            (self.get(x, y + 1, z) - self.get(x, y - 1, z)) / (2.0 * self.spacing[1])
-        } else if y == 0 {
-            (self.get(x, y + 1, z) - self.get(x, y, z)) / self.spacing[1]
-        } else {
-            (self.get(x, y, z) - self.get(x, y - 1, z)) / self.spacing[1]
-        };
+/// Placement of grid points: uniform `origin + i * spacing`, or explicit coordinates.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GridAxes<'a> {
+    pub spacing: [f32; 3],
+    pub origin: [f32; 3],
+    pub coordinates: Option<&'a [Vec<f32>; 3]>,
+}
 
-        let dz = if z > 0 && z < nz - 1 {
-            (self.get(x, y, z + 1) - self.get(x, y, z - 1)) / (2.0 * self.spacing[2])
-        } else if z == 0 {
-            (self.get(x, y, z + 1) - self.get(x, y, z)) / self.spacing[2]
-        } else {
-            (self.get(x, y, z) - self.get(x, y, z - 1)) / self.spacing[2]
+impl GridAxes<'_> {
+    /// World-space coordinate of grid index `i` along `axis`.
+    #[inline]
+    pub fn coordinate(&self, axis: usize, i: usize) -> f32 {
+        match self.coordinates {
+            Some(coordinates) => coordinates[axis][i],
+            None => self.origin[axis] + i as f32 * self.spacing[axis],
+        }
+    }
+
+    /// Distance from grid index `i` to `i + 1` along `axis`.
+    #[inline]
+    pub fn step(&self, axis: usize, i: usize) -> f32 {
+        match self.coordinates {
+            Some(coordinates) => coordinates[axis][i + 1] - coordinates[axis][i],
+            None => self.spacing[axis],
+        }
+    }
+
+    #[inline]
+    pub fn world(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        [self.coordinate(0, x), self.coordinate(1, y), self.coordinate(2, z)]
+    }
+
+    /// Continuous grid index of world coordinate `p` along `axis`, clamped to the grid.
+    pub fn grid_position(&self, axis: usize, p: f32, n: usize) -> f32 {
+        match self.coordinates {
+            Some(coordinates) => {
+                let c = &coordinates[axis];
+                if n < 2 || p <= c[0] {
+                    return 0.0;
+                }
+                if p >= c[n - 1] {
+                    return (n - 1) as f32;
+                }
+                let i = c.partition_point(|&v| v <= p) - 1;
+                i as f32 + (p - c[i]) / (c[i + 1] - c[i])
+            }
+            None => ((p - self.origin[axis]) / self.spacing[axis]).clamp(0.0, (n - 1) as f32),
+        }
+    }
+}
+
+/// The sample `offset` steps from `p` along `axis`, if it lies inside the grid
+/// and is valid (`get` returns a non-finite value for invalid voxels).
+///
+/// Every derived-field stencil reads its neighbours through this, so they all
+/// share the boundary policy documented on [`Volume::laplacian`].
+#[inline]
+pub(crate) fn stencil_sample(
+    get: &impl Fn(usize, usize, usize) -> f32,
+    dimensions: [usize; 3],
+    p: [usize; 3],
+    axis: usize,
+    offset: isize,
+) -> Option<f32> {
+    let i = p[axis].checked_add_signed(offset).filter(|&i| i < dimensions[axis])?;
+    let mut q = p;
+    q[axis] = i;
+    Some(get(q[0], q[1], q[2])).filter(|v| v.is_finite())
+}
+
+/// Gradient at a grid point from central differences (one-sided at the boundary).
+///
+/// `get` returns a non-finite value for invalid voxels; a derivative falls back to the
+/// one-sided difference when a neighbour is invalid, and to zero when both are.
+/// On rectilinear grids the three-point formula for unequal steps is used, which
+/// is exact for quadratics. Shared by every extraction path so that whole-volume,
+/// parallel and streamed extraction produce bit-identical normals.
+pub(crate) fn central_gradient(
+    get: impl Fn(usize, usize, usize) -> f32,
+    dimensions: [usize; 3],
+    axes: &GridAxes,
+    x: usize,
+    y: usize,
+    z: usize,
+) -> [f32; 3] {
+    let p = [x, y, z];
+    let here = get(x, y, z);
+    let mut g = [0.0f32; 3];
+
+    for axis in 0..3 {
+        let neighbour = |offset: isize| stencil_sample(&get, dimensions, p, axis, offset);
+
+        let i = p[axis];
+        g[axis] = match (neighbour(-1), neighbour(1)) {
+            (Some(prev), Some(next)) if axes.coordinates.is_none() => {
+                (next - prev) / (2.0 * axes.spacing[axis])
+            }
+            (Some(prev), Some(next)) => {
+                let (hm, hp) = (axes.step(axis, i - 1), axes.step(axis, i));
+                (hm * hm * next - hp * hp * prev + (hp * hp - hm * hm) * here) / (hm * hp * (hm + hp))
+            }
+            (None, Some(next)) => (next - here) / axes.step(axis, i),
+            (Some(prev), None) => (here - prev) / axes.step(axis, i - 1),
+            (None, None) => 0.0,
         };
+    }
+
+    g
+}
+
+/// Read access to grid samples during extraction.
+///
+/// Lets the same cell loop run over a whole `Volume` or over a window of
+/// z-planes streamed in from elsewhere.
+pub(crate) trait GridSamples {
+    /// Scalar value at grid coordinates; non-finite for invalid voxels.
+    fn value(&self, x: usize, y: usize, z: usize) -> f32;
+    /// Gradient at grid coordinates.
+    fn gradient(&self, x: usize, y: usize, z: usize) -> [f32; 3];
+    /// World-space position of grid coordinates.
+    fn world(&self, x: usize, y: usize, z: usize) -> [f32; 3];
+}
 
-        [dx, dy, dz]
+impl GridSamples for Volume {
+    fn value(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.valid_value(x, y, z)
+    }
+
+    fn gradient(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        Volume::gradient(self, x, y, z)
+    }
+
+    fn world(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        self.grid_to_world(x, y, z)
+    }
+}
+
+/// Vertex and triangle buffers filled by an extraction pass.
+#[derive(Debug, Default)]
+pub(crate) struct MeshBuffers {
+    /// Index assigned to the first vertex in these buffers.
+    pub vertex_base: u32,
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub indices: Vec<u32>,
+    /// Cells skipped because a corner is invalid.
+    pub skipped_cells: usize,
+}
+
+impl MeshBuffers {
+    pub(crate) fn next_vertex(&self) -> u32 {
+        self.vertex_base + (self.positions.len() / 3) as u32
     }
 }
 
 /// Extract an isosurface from a volume using the Marching Cubes algorithm.
 ///
 /// Returns a `Mesh` with positions, indices, and gradient-based normals.
+/// Vertices are welded by grid edge: each edge crossing produces exactly one
+/// vertex, shared by every triangle that uses it, so a closed surface comes out
+/// as a manifold indexed mesh. Vertex order follows the cell scan order (x fastest,
+/// then y, then z) and is fully deterministic.
+/// Cells touching invalid voxels are skipped; use `marching_cubes_with_report`
+/// to find out how many.
 /// The mesh has no material_id assigned; the caller must set one before rendering.
 pub fn marching_cubes(volume: &Volume, iso_value: f32) -> Mesh {
+    marching_cubes_with_report(volume, iso_value).0
+}
+
+/// Summary of an isosurface extraction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionReport {
+    /// Number of cells in the volume.
+    pub total_cells: usize,
+    /// Cells skipped because at least one corner voxel is invalid (NaN or masked out).
+    pub skipped_cells: usize,
+}
+
+impl ExtractionReport {
+    /// The report any extraction of `volume` produces.
+    ///
+    /// Which cells are skipped depends only on the volume, not the iso-value
+    /// or the method, so this also describes `surface_nets`,
+    /// `marching_cubes_slabs` and the GPU path.
+    pub fn for_volume(volume: &Volume) -> Self {
+        let [nx, ny, nz] = volume.dimensions;
+        if nx < 2 || ny < 2 || nz < 2 {
+            return Self::default();
+        }
+        let mut skipped_cells = 0;
+        for z in 0..nz - 1 {
+            for y in 0..ny - 1 {
+                for x in 0..nx - 1 {
+                    let touches_invalid =
+                        (0..8).any(|c| !volume.is_valid(x + (c & 1), y + ((c >> 1) & 1), z + (c >> 2)));
+                    skipped_cells += touches_invalid as usize;
+                }
+            }
+        }
+        Self { total_cells: (nx - 1) * (ny - 1) * (nz - 1), skipped_cells }
+    }
+}
+
+/// Marching Cubes, also reporting cells skipped because of invalid voxels.
+///
+/// The mesh is identical to `marching_cubes`.
+pub fn marching_cubes_with_report(volume: &Volume, iso_value: f32) -> (Mesh, ExtractionReport) {
     let [nx, ny, nz] = volume.dimensions;
 
     // Validate dimensions
     if nx < 2 || ny < 2 || nz < 2 {
-        return Mesh::new(vec![], vec![]);
+        return (Mesh::new(vec![], vec![]), ExtractionReport::default());
     }
 
-    let mut positions: Vec<f32> = Vec::new();
-    let mut normals: Vec<f32> = Vec::new();
-    let mut indices: Vec<u32> = Vec::new();
+    let mut buffers = MeshBuffers::default();
 
-    // Edge-to-vertex cache: maps (cell_index, edge_index) -> vertex_index
-    // This avoids duplicate vertices on shared edges
-    let mut edge_cache: std::collections::HashMap<(usize, usize), u32> = std::collections::HashMap::new();
+    // Edge-to-vertex cache covering the current slab of cells
+    let mut edge_cache = EdgeCache::new(nx, ny);
 
-    // Process each cell (cube)
+    // Process each layer of cells (cubes)
     for z in 0..(nz - 1) {
-        for y in 0..(ny - 1) {
-            for x in 0..(nx - 1) {
-                let _cell_index = x + y * (nx - 1) + z * (nx - 1) * (ny - 1);
-
-                // Get the 8 corner values
-                let v = [
-                    volume.get(x, y, z),
-                    volume.get(x + 1, y, z),
-                    volume.get(x + 1, y + 1, z),
-                    volume.get(x, y + 1, z),
-                    volume.get(x, y, z + 1),
-                    volume.get(x + 1, y, z + 1),
-                    volume.get(x + 1, y + 1, z + 1),
-                    volume.get(x, y + 1, z + 1),
-                ];
-
-                // Calculate cube index (which corners are inside the isosurface)
-                let mut cube_index = 0u8;
-                for i in 0..8 {
-                    if v[i] < iso_value {
-                        cube_index |= 1 << i;
-                    }
-                }
+        edge_cache.advance_slab();
+        extract_layer(volume, volume.dimensions, iso_value, z, &mut edge_cache, &mut buffers);
+    }
 
-                // Skip if entirely inside or outside
-                if cube_index == 0 || cube_index == 255 {
-                    continue;
-                }
+    let report = ExtractionReport {
+        total_cells: (nx - 1) * (ny - 1) * (nz - 1),
+        skipped_cells: buffers.skipped_cells,
+    };
+    let mesh = Mesh::new(buffers.positions, buffers.indices).with_normals(buffers.normals);
+    (mesh, report)
+}
+
+/// Run marching cubes over the layer of cells between z and z+1.
+///
+/// The edge cache must already be advanced to this layer. New vertices and
+/// triangles are appended to `out` in cell scan order (x fastest, then y).
+pub(crate) fn extract_layer<S: GridSamples>(
+    volume: &S,
+    dimensions: [usize; 3],
+    iso_value: f32,
+    z: usize,
+    edge_cache: &mut EdgeCache,
+    out: &mut MeshBuffers,
+) {
+    let [nx, ny, _nz] = dimensions;
+
+    for y in 0..(ny - 1) {
+        for x in 0..(nx - 1) {
+            // Get the 8 corner values
+            let v = [
+                volume.value(x, y, z),
+                volume.value(x + 1, y, z),
+                volume.value(x + 1, y + 1, z),
+                volume.value(x, y + 1, z),
+                volume.value(x, y, z + 1),
+                volume.value(x + 1, y, z + 1),
+                volume.value(x + 1, y + 1, z + 1),
+                volume.value(x, y + 1, z + 1),
+            ];
+
+            // Cells touching invalid voxels produce no geometry
+            if v.iter().any(|value| !value.is_finite()) {
+                out.skipped_cells += 1;
+                continue;
+            }
 
-                // Get the edges that are intersected
-                let edge_flags = EDGE_TABLE[cube_index as usize];
-                if edge_flags == 0 {
-                    continue;
+            // Calculate cube index (which corners are inside the isosurface)
+            let mut cube_index = 0u8;
+            for i in 0..8 {
+                if v[i] < iso_value {
+                    cube_index |= 1 << i;
                 }
+            }
+
+            // Skip if entirely inside or outside
+            if cube_index == 0 || cube_index == 255 {
+                continue;
+            }
+
+            // Get the edges that are intersected
+            let edge_flags = EDGE_TABLE[cube_index as usize];
+            if edge_flags == 0 {
+                continue;
+            }
+
+            // Corner positions
+            let p = [
+                volume.world(x, y, z),
+                volume.world(x + 1, y, z),
+                volume.world(x + 1, y + 1, z),
+                volume.world(x, y + 1, z),
+                volume.world(x, y, z + 1),
+                volume.world(x + 1, y, z + 1),
+                volume.world(x + 1, y + 1, z + 1),
+                volume.world(x, y + 1, z + 1),
+            ];
 
-                // Corner positions
-                let p = [
-                    volume.grid_to_world(x, y, z),
-                    volume.grid_to_world(x + 1, y, z),
-                    volume.grid_to_world(x + 1, y + 1, z),
-                    volume.grid_to_world(x, y + 1, z),
-                    volume.grid_to_world(x, y, z + 1),
-                    volume.grid_to_world(x + 1, y, z + 1),
-                    volume.grid_to_world(x + 1, y + 1, z + 1),
-                    volume.grid_to_world(x, y + 1, z + 1),
-                ];
-
-                // Corner gradients for normal interpolation
-                let g = [
-                    volume.gradient(x, y, z),
-                    volume.gradient(x + 1, y, z),
-                    volume.gradient(x + 1, y + 1, z),
-                    volume.gradient(x, y + 1, z),
-                    volume.gradient(x, y, z + 1),
-                    volume.gradient(x + 1, y, z + 1),
-                    volume.gradient(x + 1, y + 1, z + 1),
-                    volume.gradient(x, y + 1, z + 1),
-                ];
-
-                // Find or create vertices for intersected edges
-                let mut edge_vertices = [u32::MAX; 12];
-                for edge in 0..12 {
-                    if edge_flags & (1 << edge) != 0 {
-                        let (c0, c1) = EDGE_CORNERS[edge];
-
-                        // Check cache first
-                        let cache_key = edge_cache_key(x, y, z, edge, nx, ny);
-                        if let Some(&vertex_idx) = edge_cache.get(&cache_key) {
-                            edge_vertices[edge] = vertex_idx;
+            // Corner gradients for normal interpolation
+            let g = [
+                volume.gradient(x, y, z),
+                volume.gradient(x + 1, y, z),
+                volume.gradient(x + 1, y + 1, z),
+                volume.gradient(x, y + 1, z),
+                volume.gradient(x, y, z + 1),
+                volume.gradient(x + 1, y, z + 1),
+                volume.gradient(x + 1, y + 1, z + 1),
+                volume.gradient(x, y + 1, z + 1),
+            ];
+
+            // Find or create vertices for intersected edges
+            let mut edge_vertices = [u32::MAX; 12];
+            for edge in 0..12 {
+                if edge_flags & (1 << edge) != 0 {
+                    let (c0, c1) = EDGE_CORNERS[edge];
+
+                    // Check cache first
+                    let cached = edge_cache.slot(x, y, edge);
+                    if *cached != u32::MAX {
+                        edge_vertices[edge] = *cached;
+                    } else {
+                        // Interpolate vertex position
+                        let t = if (v[c1] - v[c0]).abs() > 1e-10 {
+                            (iso_value - v[c0]) / (v[c1] - v[c0])
                         } else {
-                            // Interpolate vertex position
-                            let t = if (v[c1] - v[c0]).abs() > 1e-10 {
-                                (iso_value - v[c0]) / (v[c1] - v[c0])
-                            } else {
-                                0.5
-                            };
-
-                            let pos = [
-                                p[c0][0] + t * (p[c1][0] - p[c0][0]),
-                                p[c0][1] + t * (p[c1][1] - p[c0][1]),
-                                p[c0][2] + t * (p[c1][2] - p[c0][2]),
-                            ];
-
-                            // Interpolate normal (negative gradient)
-                            let mut normal = [
-                                -(g[c0][0] + t * (g[c1][0] - g[c0][0])),
-                                -(g[c0][1] + t * (g[c1][1] - g[c0][1])),
-                                -(g[c0][2] + t * (g[c1][2] - g[c0][2])),
-                            ];
-
-                            // Normalize
-                            let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
-                            if len > 1e-10 {
-                                normal[0] /= len;
-                                normal[1] /= len;
-                                normal[2] /= len;
-                            }
+                            0.5
+                        };
+
+                        let pos = [
+                            p[c0][0] + t * (p[c1][0] - p[c0][0]),
+                            p[c0][1] + t * (p[c1][1] - p[c0][1]),
+                            p[c0][2] + t * (p[c1][2] - p[c0][2]),
+                        ];
 
-                            let vertex_idx = (positions.len() / 3) as u32;
-                            positions.extend_from_slice(&pos);
-                            normals.extend_from_slice(&normal);
+                        // Interpolate normal (negative gradient)
+                        let mut normal = [
+                            -(g[c0][0] + t * (g[c1][0] - g[c0][0])),
+                            -(g[c0][1] + t * (g[c1][1] - g[c0][1])),
+                            -(g[c0][2] + t * (g[c1][2] - g[c0][2])),
+                        ];
 
-                            edge_cache.insert(cache_key, vertex_idx);
-                            edge_vertices[edge] = vertex_idx;
+                        // Normalize
+                        let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+                        if len > 1e-10 {
+                            normal[0] /= len;
+                            normal[1] /= len;
+                            normal[2] /= len;
                         }
+
+                        let vertex_idx = out.next_vertex();
+                        out.positions.extend_from_slice(&pos);
+                        out.normals.extend_from_slice(&normal);
+
+                        *cached = vertex_idx;
+                        edge_vertices[edge] = vertex_idx;
                     }
                 }
+            }
 
-                // Generate triangles
-                let tri_table = &TRI_TABLE[cube_index as usize];
-                let mut i = 0;
-                while tri_table[i] != -1 {
-                    indices.push(edge_vertices[tri_table[i] as usize]);
-                    indices.push(edge_vertices[tri_table[i + 1] as usize]);
-                    indices.push(edge_vertices[tri_table[i + 2] as usize]);
-                    i += 3;
-                }
+            // Generate triangles
+            let tri_table = &TRI_TABLE[cube_index as usize];
+            let mut i = 0;
+            while tri_table[i] != -1 {
+                out.indices.push(edge_vertices[tri_table[i] as usize]);
+                out.indices.push(edge_vertices[tri_table[i + 1] as usize]);
+                out.indices.push(edge_vertices[tri_table[i + 2] as usize]);
+                i += 3;
             }
         }
     }
+}
 
-    Mesh::new(positions, indices).with_normals(normals)
+/// Edge-to-vertex cache for one slab of cells (the cell layer between z and z+1).
+///
+/// Every grid edge touched by the slab has exactly one slot, so each edge
+/// crossing maps to a single vertex no matter which neighbouring cell reaches it
+/// first. Edges in the bottom plane are shared with the previous slab and carried
+/// over when advancing; memory stays proportional to one slab, not the volume.
+#[derive(Debug)]
+pub(crate) struct EdgeCache {
+    nx: usize,
+    /// X-directed edges in the bottom (z) and top (z+1) planes.
+    x_edges: [Vec<u32>; 2],
+    /// Y-directed edges in the bottom (z) and top (z+1) planes.
+    y_edges: [Vec<u32>; 2],
+    /// Z-directed edges between the two planes.
+    z_edges: Vec<u32>,
 }
 
-/// Generate a unique cache key for an edge.
-/// Edges are shared between cells, so we need to canonicalize the key.
-fn edge_cache_key(x: usize, y: usize, z: usize, edge: usize, nx: usize, ny: usize) -> (usize, usize) {
-    // Map edge to its canonical cell and local edge index
-    // Edges 0-3 are on the bottom face (z), 4-7 on the top face (z+1), 8-11 are vertical
-    let (cx, cy, cz, local_edge) = match edge {
-        0 => (x, y, z, 0),     // Bottom edge along X at (x, y, z)
-        1 => (x + 1, y, z, 3), // Right edge along Y
-        2 => (x, y + 1, z, 0), // Top edge along X
-        3 => (x, y, z, 3),     // Left edge along Y
-        4 => (x, y, z + 1, 0), // Same as 0 but at z+1
-        5 => (x + 1, y, z + 1, 3),
-        6 => (x, y + 1, z + 1, 0),
-        7 => (x, y, z + 1, 3),
-        8 => (x, y, z, 8),     // Vertical edges
-        9 => (x + 1, y, z, 8),
-        10 => (x + 1, y + 1, z, 8),
-        11 => (x, y + 1, z, 8),
-        _ => unreachable!(),
-    };
+impl EdgeCache {
+    pub(crate) fn new(nx: usize, ny: usize) -> Self {
+        let plane = nx * ny;
+        Self {
+            nx,
+            x_edges: [vec![u32::MAX; plane], vec![u32::MAX; plane]],
+            y_edges: [vec![u32::MAX; plane], vec![u32::MAX; plane]],
+            z_edges: vec![u32::MAX; plane],
+        }
+    }
+
+    /// Move to the next slab: the old top plane becomes the new bottom plane.
+    pub(crate) fn advance_slab(&mut self) {
+        self.x_edges.swap(0, 1);
+        self.y_edges.swap(0, 1);
+        self.x_edges[1].fill(u32::MAX);
+        self.y_edges[1].fill(u32::MAX);
+        self.z_edges.fill(u32::MAX);
+    }
+
+    /// Cached vertices on the bottom plane of the current slab: (x-edges, y-edges).
+    pub(crate) fn bottom_plane(&self) -> (&[u32], &[u32]) {
+        (&self.x_edges[0], &self.y_edges[0])
+    }
+
+    /// Cached vertices on the top plane of the current slab: (x-edges, y-edges).
+    pub(crate) fn top_plane(&self) -> (&[u32], &[u32]) {
+        (&self.x_edges[1], &self.y_edges[1])
+    }
 
-    let cell_idx = cx + cy * nx + cz * nx * ny;
-    (cell_idx, local_edge)
+    /// Cache slot for a cube edge of the cell at (x, y) in the current slab.
+    ///
+    /// Edges 0-3 lie in the bottom plane, 4-7 in the top plane, 8-11 are vertical.
+    fn slot(&mut self, x: usize, y: usize, edge: usize) -> &mut u32 {
+        let nx = self.nx;
+        let plane = edge / 4;
+        match edge {
+            0 | 4 => &mut self.x_edges[plane][x + y * nx],
+            1 | 5 => &mut self.y_edges[plane][(x + 1) + y * nx],
+            2 | 6 => &mut self.x_edges[plane][x + (y + 1) * nx],
+            3 | 7 => &mut self.y_edges[plane][x + y * nx],
+            8 => &mut self.z_edges[x + y * nx],
+            9 => &mut self.z_edges[(x + 1) + y * nx],
+            10 => &mut self.z_edges[(x + 1) + (y + 1) * nx],
+            11 => &mut self.z_edges[x + (y + 1) * nx],
+            _ => unreachable!(),
+        }
+    }
 }
 
 /// Edge endpoints: EDGE_CORNERS[edge] = (corner0, corner1)
@@ -263,7 +610,10 @@ const EDGE_CORNERS: [(usize, usize); 12] = [
 ];
 
 /// Edge table: for each of 256 cube configurations, which edges are intersected
-const EDGE_TABLE: [u16; 256] = [
+///
+/// This and `TRI_TABLE` are public so alternative backends (such as the GPU
+/// compute path in `frustum-render`) produce exactly the same topology.
+pub const EDGE_TABLE: [u16; 256] = [
     0x000, 0x109, 0x203, 0x30a, 0x406, 0x50f, 0x605, 0x70c,
     0x80c, 0x905, 0xa0f, 0xb06, 0xc0a, 0xd03, 0xe09, 0xf00,
     0x190, 0x099, 0x393, 0x29a, 0x596, 0x49f, 0x795, 0x69c,
@@ -300,7 +650,7 @@ const EDGE_TABLE: [u16; 256] = [
 
 /// Triangle table: for each of 256 cube configurations, list of edge indices forming triangles
 /// -1 terminates the list
-const TRI_TABLE: [[i8; 16]; 256] = [
+pub const TRI_TABLE: [[i8; 16]; 256] = [
     [-1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1],
     [0, 8, 3, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1],
     [0, 1, 9, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1],
@@ -614,18 +964,27 @@ impl Volume {
             }
         }
 
-        Volume {
-            values,
-            dimensions: self.dimensions,
-            spacing: self.spacing,
-            origin: self.origin,
-        }
+        self.with_values(values)
     }
 
     /// Compute Laplacian (divergence of gradient) at each grid point.
     ///
     /// Returns a new volume where each value is the Laplacian (sum of second
     /// partial derivatives). Useful for detecting edges and inflection points.
+    /// Rectilinear grids use the three-point formula for unequal steps.
+    ///
+    /// # Boundaries and invalid voxels
+    ///
+    /// All derived fields (this one, [`Volume::gradient_magnitude`],
+    /// [`Volume::gaussian_smooth`], [`Volume::local_variance`], the curvatures
+    /// and [`VectorVolume`]'s divergence and curl) share one policy: they read
+    /// only voxels that exist and are valid, as if the field were undefined
+    /// beyond the volume edge and at invalid voxels. Derivative stencils shift
+    /// inward to the nearest valid samples, so they become one-sided at the
+    /// boundary, and a derivative with too few samples is zero. Gaussian weights
+    /// and variance windows are truncated to the samples they keep and
+    /// renormalized, so a constant field stays constant. Invalid voxels are NaN
+    /// in the result, and keep the mask.
     pub fn laplacian(&self) -> Volume {
         let [nx, ny, nz] = self.dimensions;
         let mut values = Vec::with_capacity(nx * ny * nz);
@@ -639,44 +998,37 @@ impl Volume {
             }
         }
 
-        Volume {
-            values,
-            dimensions: self.dimensions,
-            spacing: self.spacing,
-            origin: self.origin,
-        }
+        self.with_values(values)
     }
 
-    /// Compute Laplacian at a single grid point using central differences.
+    /// Laplacian at a single grid point from three-point second differences.
     fn laplacian_at(&self, x: usize, y: usize, z: usize) -> f32 {
-        let [nx, ny, nz] = self.dimensions;
-        let center = self.get(x, y, z);
+        let p = [x, y, z];
+        let get = |x: usize, y: usize, z: usize| self.valid_value(x, y, z);
+        let axes = self.axes();
 
-        // Second derivative in x
-        let d2x = if x > 0 && x < nx - 1 {
-            (self.get(x + 1, y, z) - 2.0 * center + self.get(x - 1, y, z))
-                / (self.spacing[0] * self.spacing[0])
-        } else {
-            0.0
-        };
+        let mut sum = 0.0;
+        for axis in 0..3 {
+            // Centred stencil, else shifted inward; (offset from p, value) pairs
+            let stencil = [[-1, 0, 1], [0, 1, 2], [-2, -1, 0]].into_iter().find_map(|offsets| {
+                let samples = offsets.map(|o| stencil_sample(&get, self.dimensions, p, axis, o).map(|f| (o, f)));
+                samples.iter().all(Option::is_some).then(|| samples.map(Option::unwrap))
+            });
+            let Some(stencil) = stencil else {
+                continue;
+            };
 
-        // Second derivative in y
-        let d2y = if y > 0 && y < ny - 1 {
-            (self.get(x, y + 1, z) - 2.0 * center + self.get(x, y - 1, z))
-                / (self.spacing[1] * self.spacing[1])
-        } else {
-            0.0
-        };
-
-        // Second derivative in z
-        let d2z = if z > 0 && z < nz - 1 {
-            (self.get(x, y, z + 1) - 2.0 * center + self.get(x, y, z - 1))
-                / (self.spacing[2] * self.spacing[2])
-        } else {
-            0.0
-        };
+            // Three-point formula, exact for quadratics on unequal steps
+            let position = |o: isize| match self.coordinates {
+                None => o as f32 * self.spacing[axis],
+                Some(_) => axes.coordinate(axis, p[axis].wrapping_add_signed(o)) - axes.coordinate(axis, p[axis]),
+            };
+            let [(o0, f0), (o1, f1), (o2, f2)] = stencil;
+            let (x0, x1, x2) = (position(o0), position(o1), position(o2));
+            sum += 2.0 * (f0 / ((x0 - x1) * (x0 - x2)) + f1 / ((x1 - x0) * (x1 - x2)) + f2 / ((x2 - x0) * (x2 - x1)));
+        }
 
-        d2x + d2y + d2z
+        sum
     }
 
     /// Normalize values to [0, 1] range.
@@ -698,6 +1050,8 @@ impl Volume {
             dimensions: self.dimensions,
             spacing: self.spacing,
             origin: self.origin,
+            mask: self.mask.clone(),
+            coordinates: self.coordinates.clone(),
         }
     }
 
@@ -707,10 +1061,604 @@ impl Volume {
         let max = self.values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
         (min, max)
     }
+
+    /// Gaussian blur with standard deviation `sigma` in voxels.
+    ///
+    /// Shorthand for `gaussian_smooth(&GaussianKernel::new(sigma))`: the kernel
+    /// spans `ceil(3 * sigma)` voxels on each side.
+    pub fn gaussian_blur(&self, sigma: f32) -> Volume {
+        self.gaussian_smooth(&GaussianKernel::new(sigma))
+    }
+
+    /// Local variance over a `(2 * radius + 1)^3` voxel window.
+    ///
+    /// Returns the population variance of the valid voxels in the window
+    /// around each grid point; at the boundary the window is truncated (see
+    /// [`Volume::laplacian`]). A constant field gives zero everywhere.
+    pub fn local_variance(&self, radius: usize) -> Volume {
+        let len = self.values.len();
+        let mut sum = vec![0.0f64; len];
+        let mut sum_sq = vec![0.0f64; len];
+        let mut count = vec![0.0f64; len];
+        let [nx, ny, _nz] = self.dimensions;
+        for i in 0..len {
+            if self.is_valid(i % nx, (i / nx) % ny, i / (nx * ny)) {
+                let v = self.values[i] as f64;
+                sum[i] = v;
+                sum_sq[i] = v * v;
+                count[i] = 1.0;
+            }
+        }
+        for field in [&mut sum, &mut sum_sq, &mut count] {
+            box_sum(field, self.dimensions, radius);
+        }
+
+        let values = (0..len)
+            .map(|i| {
+                if count[i] == 0.0 {
+                    return f32::NAN;
+                }
+                let mean = sum[i] / count[i];
+                (sum_sq[i] / count[i] - mean * mean).max(0.0) as f32
+            })
+            .collect();
+        self.with_values(values)
+    }
+
+    /// Local standard deviation over a `(2 * radius + 1)^3` voxel window.
+    ///
+    /// Square root of [`Volume::local_variance`], with the same boundary handling.
+    pub fn local_std_dev(&self, radius: usize) -> Volume {
+        let mut volume = self.local_variance(radius);
+        for v in volume.values.iter_mut() {
+            *v = v.sqrt();
+        }
+        volume
+    }
+
+    /// Mean curvature of the level sets at each grid point.
+    ///
+    /// Computed as `div(grad f / |grad f|) / 2` from the gradient and Hessian:
+    /// positive when values increase away from the centre of curvature, so the
+    /// distance from a point gives `1 / r` on the sphere of radius r. The
+    /// Hessian is the central-difference gradient of the gradient, with the
+    /// boundary handling of [`Volume::laplacian`]. Points with a vanishing
+    /// gradient give zero.
+    pub fn mean_curvature(&self) -> Volume {
+        self.level_set_curvature(|g, h, len| {
+            let trace = h[0][0] + h[1][1] + h[2][2];
+            let mut ghg = 0.0;
+            for i in 0..3 {
+                for j in 0..3 {
+                    ghg += g[i] * h[i][j] * g[j];
+                }
+            }
+            (len * len * trace - ghg) / (2.0 * len * len * len)
+        })
+    }
+
+    /// Gaussian curvature of the level sets at each grid point.
+    ///
+    /// Computed as `g^T adj(H) g / |g|^4` from the gradient g and Hessian H,
+    /// so a sphere of radius r gives `1 / r^2` regardless of the field's sign.
+    /// Boundary handling is the same as for [`Volume::mean_curvature`].
+    pub fn gaussian_curvature(&self) -> Volume {
+        self.level_set_curvature(|g, h, len| {
+            // Adjugate of the symmetric Hessian
+            let adj = [
+                [
+                    h[1][1] * h[2][2] - h[1][2] * h[2][1],
+                    h[0][2] * h[2][1] - h[0][1] * h[2][2],
+                    h[0][1] * h[1][2] - h[0][2] * h[1][1],
+                ],
+                [
+                    h[1][2] * h[2][0] - h[1][0] * h[2][2],
+                    h[0][0] * h[2][2] - h[0][2] * h[2][0],
+                    h[0][2] * h[1][0] - h[0][0] * h[1][2],
+                ],
+                [
+                    h[1][0] * h[2][1] - h[1][1] * h[2][0],
+                    h[0][1] * h[2][0] - h[0][0] * h[2][1],
+                    h[0][0] * h[1][1] - h[0][1] * h[1][0],
+                ],
+            ];
+            let mut gag = 0.0;
+            for i in 0..3 {
+                for j in 0..3 {
+                    gag += g[i] * adj[i][j] * g[j];
+                }
+            }
+            gag / (len * len * len * len)
+        })
+    }
+
+    /// Evaluate a curvature formula `f(gradient, hessian, |gradient|)` at every grid point.
+    fn level_set_curvature(&self, f: impl Fn([f32; 3], [[f32; 3]; 3], f32) -> f32) -> Volume {
+        let [nx, ny, nz] = self.dimensions;
+        let axes = self.axes();
+
+        let mut gradients = [
+            Vec::with_capacity(self.values.len()),
+            Vec::with_capacity(self.values.len()),
+            Vec::with_capacity(self.values.len()),
+        ];
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    // Invalid voxels stay out of the Hessian stencils
+                    let g = if self.is_valid(x, y, z) { self.gradient(x, y, z) } else { [f32::NAN; 3] };
+                    for axis in 0..3 {
+                        gradients[axis].push(g[axis]);
+                    }
+                }
+            }
+        }
+
+        let mut values = Vec::with_capacity(self.values.len());
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let i = x + y * nx + z * nx * ny;
+                    let g = [gradients[0][i], gradients[1][i], gradients[2][i]];
+                    let mut h = [[0.0f32; 3]; 3];
+                    for (row, component) in h.iter_mut().zip(gradients.iter()) {
+                        let get = |x: usize, y: usize, z: usize| component[x + y * nx + z * nx * ny];
+                        *row = central_gradient(get, self.dimensions, &axes, x, y, z);
+                    }
+                    // Symmetrize the mixed derivatives
+                    for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+                        let m = 0.5 * (h[a][b] + h[b][a]);
+                        h[a][b] = m;
+                        h[b][a] = m;
+                    }
+
+                    let len = (g[0] * g[0] + g[1] * g[1] + g[2] * g[2]).sqrt();
+                    values.push(if len > 1e-10 { f(g, h, len) } else { 0.0 });
+                }
+            }
+        }
+
+        self.with_values(values)
+    }
+
+    /// A volume on the same grid (spacing, origin, mask, coordinates) with new
+    /// values; voxels invalid here are NaN in the result.
+    fn with_values(&self, mut values: Vec<f32>) -> Volume {
+        for (i, v) in values.iter_mut().enumerate() {
+            if !self.is_valid_index(i) {
+                *v = f32::NAN;
+            }
+        }
+        Volume {
+            values,
+            dimensions: self.dimensions,
+            spacing: self.spacing,
+            origin: self.origin,
+            mask: self.mask.clone(),
+            coordinates: self.coordinates.clone(),
+        }
+    }
+}
+
+/// In-place sum over a `(2 * radius + 1)^3` window, truncated at the boundary.
+fn box_sum(data: &mut [f64], dimensions: [usize; 3], radius: usize) {
+    let [nx, ny, nz] = dimensions;
+    let strides = [1, nx, nx * ny];
+    let mut line = Vec::new();
+    let mut prefix = Vec::new();
+
+    // Separable: prefix sums along each axis in turn
+    for axis in 0..3 {
+        let n = dimensions[axis];
+        let stride = strides[axis];
+        let (outer_a, outer_b) = match axis {
+            0 => (ny, nz),
+            1 => (nx, nz),
+            _ => (nx, ny),
+        };
+        for b in 0..outer_b {
+            for a in 0..outer_a {
+                let start = match axis {
+                    0 => a * nx + b * nx * ny,
+                    1 => a + b * nx * ny,
+                    _ => a + b * nx,
+                };
+                line.clear();
+                line.extend((0..n).map(|k| data[start + k * stride]));
+                prefix.clear();
+                prefix.push(0.0);
+                for &v in &line {
+                    prefix.push(prefix[prefix.len() - 1] + v);
+                }
+                for k in 0..n {
+                    let lo = k.saturating_sub(radius);
+                    let hi = (k + radius + 1).min(n);
+                    data[start + k * stride] = prefix[hi] - prefix[lo];
+                }
+            }
+        }
+    }
+}
+
+/// A three-component vector field sampled on one grid.
+///
+/// Each component is a scalar `Volume` on the same grid (dimensions,
+/// spacing, origin and coordinates). A vector is valid where all three
+/// components are; derived fields follow the boundary policy of
+/// [`Volume::laplacian`].
+#[derive(Debug, Clone)]
+pub struct VectorVolume {
+    /// The x, y and z components.
+    pub components: [Volume; 3],
+}
+
+impl VectorVolume {
+    /// Create a vector volume from its three components.
+    ///
+    /// # Panics
+    /// Panics if the components are not on the same grid.
+    pub fn new(x: Volume, y: Volume, z: Volume) -> Self {
+        let same_grid = |a: &Volume, b: &Volume| {
+            a.dimensions == b.dimensions
+                && a.spacing == b.spacing
+                && a.origin == b.origin
+                && a.coordinates == b.coordinates
+        };
+        assert!(
+            same_grid(&x, &y) && same_grid(&x, &z),
+            "Vector volume components must share a grid"
+        );
+        Self { components: [x, y, z] }
+    }
+
+    /// Whether all three components are valid at flat index `i`.
+    fn is_valid_index(&self, i: usize) -> bool {
+        self.components.iter().all(|c| c.is_valid_index(i))
+    }
+
+    /// A scalar field on the shared grid, invalid wherever any component is.
+    fn with_values(&self, mut values: Vec<f32>) -> Volume {
+        let grid = &self.components[0];
+        let mask = self.components.iter().any(|c| c.mask.is_some()).then(|| {
+            (0..values.len())
+                .map(|i| self.components.iter().all(|c| c.mask.as_ref().is_none_or(|mask| mask[i])))
+                .collect()
+        });
+        for (i, v) in values.iter_mut().enumerate() {
+            if !self.is_valid_index(i) {
+                *v = f32::NAN;
+            }
+        }
+        Volume {
+            values,
+            dimensions: grid.dimensions,
+            spacing: grid.spacing,
+            origin: grid.origin,
+            mask,
+            coordinates: grid.coordinates.clone(),
+        }
+    }
+
+    /// Divergence `dVx/dx + dVy/dy + dVz/dz` at each grid point.
+    pub fn divergence(&self) -> Volume {
+        let d = self.jacobian();
+        let values = (0..d.len()).map(|i| d[i][0][0] + d[i][1][1] + d[i][2][2]).collect();
+        self.with_values(values)
+    }
+
+    /// Curl `(dVz/dy - dVy/dz, dVx/dz - dVz/dx, dVy/dx - dVx/dy)` at each grid point.
+    pub fn curl(&self) -> VectorVolume {
+        let d = self.jacobian();
+        let component = |f: &dyn Fn(&[[f32; 3]; 3]) -> f32| self.with_values(d.iter().map(f).collect());
+        VectorVolume {
+            components: [
+                component(&|j| j[2][1] - j[1][2]),
+                component(&|j| j[0][2] - j[2][0]),
+                component(&|j| j[1][0] - j[0][1]),
+            ],
+        }
+    }
+
+    /// Vector length at each grid point.
+    pub fn magnitude(&self) -> Volume {
+        let [x, y, z] = &self.components;
+        let values = (0..x.values.len())
+            .map(|i| (x.values[i] * x.values[i] + y.values[i] * y.values[i] + z.values[i] * z.values[i]).sqrt())
+            .collect();
+        self.with_values(values)
+    }
+
+    /// Per grid point, `d[component][axis]`: derivative of a component along an axis.
+    fn jacobian(&self) -> Vec<[[f32; 3]; 3]> {
+        let grid = &self.components[0];
+        let [nx, ny, nz] = grid.dimensions;
+        let axes = grid.axes();
+        let mut d = Vec::with_capacity(grid.values.len());
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let mut j = [[0.0f32; 3]; 3];
+                    for (row, component) in j.iter_mut().zip(self.components.iter()) {
+                        let get = |x: usize, y: usize, z: usize| {
+                            let i = x + y * nx + z * nx * ny;
+                            if self.is_valid_index(i) { component.values[i] } else { f32::NAN }
+                        };
+                        *row = central_gradient(get, grid.dimensions, &axes, x, y, z);
+                    }
+                    d.push(j);
+                }
+            }
+        }
+        d
+    }
+}
+
+// =============================================================================
+// Optional Modifiers: Pre-smoothing and Decimation (from 003-marching-cubes.md)
+// =============================================================================
+
+/// Gaussian kernel for volume pre-smoothing, measured in voxels.
+///
+/// Deserialization rejects the sigmas `GaussianKernel::new` panics on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "GaussianKernelFields")]
+pub struct GaussianKernel {
+    /// Standard deviation in voxels (> 0).
+    pub sigma: f32,
+    /// Kernel half-width in voxels; the kernel spans `2 * radius + 1` samples.
+    pub radius: usize,
+}
+
+/// Unchecked `GaussianKernel` fields as read from JSON.
+#[derive(Deserialize)]
+struct GaussianKernelFields {
+    sigma: f32,
+    radius: usize,
+}
+
+impl TryFrom<GaussianKernelFields> for GaussianKernel {
+    type Error = String;
+
+    fn try_from(fields: GaussianKernelFields) -> Result<Self, String> {
+        if !(fields.sigma > 0.0 && fields.sigma.is_finite()) {
+            return Err(format!("Gaussian sigma must be positive and finite, got {}", fields.sigma));
+        }
+        Ok(Self { sigma: fields.sigma, radius: fields.radius })
+    }
+}
+
+impl GaussianKernel {
+    /// Create a kernel with the given sigma and a half-width of `ceil(3 * sigma)` voxels.
+    ///
+    /// # Panics
+    /// Panics if sigma is not strictly positive and finite.
+    pub fn new(sigma: f32) -> Self {
+        assert!(sigma > 0.0 && sigma.is_finite(), "Gaussian sigma must be positive and finite");
+        Self {
+            sigma,
+            radius: (3.0 * sigma).ceil() as usize,
+        }
+    }
+
+    /// Set an explicit kernel half-width in voxels.
+    pub fn with_radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Normalized 1D weights for offsets `-radius..=radius`.
+    fn weights(&self) -> Vec<f32> {
+        let r = self.radius as i64;
+        let raw: Vec<f32> = (-r..=r)
+            .map(|i| (-((i * i) as f32) / (2.0 * self.sigma * self.sigma)).exp())
+            .collect();
+        let sum: f32 = raw.iter().sum();
+        raw.iter().map(|w| w / sum).collect()
+    }
+}
+
+impl Volume {
+    /// Smooth the volume with a separable Gaussian kernel.
+    ///
+    /// The same kernel (in voxels) is applied along each axis in turn, leaving
+    /// out samples beyond the edge and invalid voxels (see [`Volume::laplacian`]),
+    /// so a constant field stays constant. Spacing, origin and mask are unchanged.
+    ///
+    /// This is a signal-processing step: it attenuates high-frequency content
+    /// of the field rather than repairing aliased geometry.
+    pub fn gaussian_smooth(&self, kernel: &GaussianKernel) -> Volume {
+        let weights = kernel.weights();
+        let r = kernel.radius as isize;
+        let [nx, ny, nz] = self.dimensions;
+
+        // Invalid voxels are NaN throughout, so the stencil skips them
+        let mut current: Vec<f32> = (0..self.values.len())
+            .map(|i| if self.is_valid_index(i) { self.values[i] } else { f32::NAN })
+            .collect();
+        let mut next = vec![0.0f32; current.len()];
+
+        for axis in 0..3 {
+            let get = |x: usize, y: usize, z: usize| current[x + y * nx + z * nx * ny];
+            for z in 0..nz {
+                for y in 0..ny {
+                    for x in 0..nx {
+                        let idx = x + y * nx + z * nx * ny;
+                        if !current[idx].is_finite() {
+                            next[idx] = f32::NAN;
+                            continue;
+                        }
+                        let (mut acc, mut total) = (0.0, 0.0);
+                        for (k, w) in (-r..=r).zip(weights.iter()) {
+                            if let Some(v) = stencil_sample(&get, self.dimensions, [x, y, z], axis, k) {
+                                acc += w * v;
+                                total += w;
+                            }
+                        }
+                        next[idx] = acc / total;
+                    }
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+
+        self.with_values(current)
+    }
+
+    /// Gradient at a world-space position, trilinearly interpolated from the grid.
+    ///
+    /// Positions outside the volume are clamped to its boundary. Invalid grid
+    /// points are left out of the interpolation.
+    pub(crate) fn gradient_at_world(&self, p: [f32; 3]) -> [f32; 3] {
+        let mut cell = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for axis in 0..3 {
+            let n = self.dimensions[axis];
+            let f = self.axes().grid_position(axis, p[axis], n);
+            let i = (f.floor() as usize).min(n.saturating_sub(2));
+            cell[axis] = i;
+            frac[axis] = f - i as f32;
+        }
+
+        let mut g = [0.0f32; 3];
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut weight = 1.0;
+            for axis in 0..3 {
+                weight *= if offset[axis] == 1 { frac[axis] } else { 1.0 - frac[axis] };
+            }
+            let (gx, gy, gz) = (cell[0] + offset[0], cell[1] + offset[1], cell[2] + offset[2]);
+            if weight == 0.0 || !self.is_valid(gx, gy, gz) {
+                continue;
+            }
+            let c = self.gradient(gx, gy, gz);
+            for axis in 0..3 {
+                g[axis] += weight * c[axis];
+            }
+        }
+        g
+    }
+}
+
+/// Explicit, optional processing around isosurface extraction.
+///
+/// Every step is off by default: `MarchingCubesOptions::default()` gives the
+/// same mesh as plain `marching_cubes`. Steps run in a fixed order:
+/// pre-smoothing of the volume, extraction, decimation, then post-smoothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarchingCubesOptions {
+    /// Gaussian smoothing of the volume before extraction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_smoothing: Option<GaussianKernel>,
+    /// Quadric-error decimation of the extracted mesh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimation: Option<DecimationTarget>,
+    /// Laplacian or Taubin smoothing of the final mesh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_smoothing: Option<MeshSmoothing>,
+}
+
+impl MarchingCubesOptions {
+    /// Create options with every step disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable Gaussian pre-smoothing of the volume.
+    pub fn with_pre_smoothing(mut self, kernel: GaussianKernel) -> Self {
+        self.pre_smoothing = Some(kernel);
+        self
+    }
+
+    /// Enable mesh decimation to the given target.
+    pub fn with_decimation(mut self, target: DecimationTarget) -> Self {
+        self.decimation = Some(target);
+        self
+    }
+
+    /// Enable smoothing of the output mesh.
+    pub fn with_post_smoothing(mut self, smoothing: MeshSmoothing) -> Self {
+        self.post_smoothing = Some(smoothing);
+        self
+    }
+}
+
+/// Extract an isosurface with explicit pre-smoothing, decimation and post-smoothing.
+///
+/// When decimation or post-smoothing moves vertices, normals are re-sampled
+/// from the gradient of the (possibly pre-smoothed) volume at the final
+/// positions, so they stay gradient-based as for plain `marching_cubes`.
+pub fn marching_cubes_with_options(
+    volume: &Volume,
+    iso_value: f32,
+    options: &MarchingCubesOptions,
+) -> Mesh {
+    let smoothed;
+    let source = match &options.pre_smoothing {
+        Some(kernel) => {
+            smoothed = volume.gaussian_smooth(kernel);
+            &smoothed
+        }
+        None => volume,
+    };
+
+    let mut mesh = marching_cubes(source, iso_value);
+
+    if let Some(target) = options.decimation {
+        mesh = mesh.decimate(target);
+    }
+    if let Some(smoothing) = &options.post_smoothing {
+        mesh = mesh.smooth(smoothing);
+    }
+
+    if options.decimation.is_some() || options.post_smoothing.is_some() {
+        let normals = mesh
+            .positions
+            .chunks(3)
+            .flat_map(|p| {
+                let g = source.gradient_at_world([p[0], p[1], p[2]]);
+                let len = (g[0] * g[0] + g[1] * g[1] + g[2] * g[2]).sqrt();
+                if len > 1e-10 {
+                    [-g[0] / len, -g[1] / len, -g[2] / len]
+                } else {
+                    [0.0; 3]
+                }
+            })
+            .collect();
+        mesh.normals = Some(normals);
+    }
+
+    mesh
+}
+
+/// Fixtures shared by the volume and extraction tests.
+///
+/// Also available to other crates' tests through the `test-support` feature.
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support {
+    use super::Volume;
+
+    /// Signed distance to a sphere of `radius` at the origin, sampled on
+    /// `[-1, 1]^3` with `dims` points per axis (negative inside).
+    pub fn sphere_volume(dims: [usize; 3], radius: f32) -> Volume {
+        let [nx, ny, nz] = dims;
+        let coordinate = |i: usize, n: usize| (i as f32 / (n - 1) as f32) * 2.0 - 1.0;
+        let mut values = Vec::with_capacity(nx * ny * nz);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let (fx, fy, fz) = (coordinate(x, nx), coordinate(y, ny), coordinate(z, nz));
+                    values.push((fx * fx + fy * fy + fz * fz).sqrt() - radius);
+                }
+            }
+        }
+        let spacing = dims.map(|n| 2.0 / (n - 1) as f32);
+        Volume::new(values, dims, spacing, [-1.0; 3])
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::test_support::sphere_volume;
     use super::*;
 
     #[test]
@@ -751,6 +1699,120 @@ mod tests {
         assert!(tri_count > 100, "Expected more triangles for sphere, got {}", tri_count);
     }
 
+    #[test]
+    fn test_one_vertex_per_edge_crossing() {
+        let volume = sphere_volume([17; 3], 0.6);
+        let iso_value = 0.0;
+        let mesh = marching_cubes(&volume, iso_value);
+
+        // Count grid edges whose endpoints straddle the iso-value
+        let [nx, ny, nz] = volume.dimensions;
+        let inside = |x: usize, y: usize, z: usize| volume.get(x, y, z) < iso_value;
+        let mut crossings = 0;
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    if x + 1 < nx && inside(x, y, z) != inside(x + 1, y, z) {
+                        crossings += 1;
+                    }
+                    if y + 1 < ny && inside(x, y, z) != inside(x, y + 1, z) {
+                        crossings += 1;
+                    }
+                    if z + 1 < nz && inside(x, y, z) != inside(x, y, z + 1) {
+                        crossings += 1;
+                    }
+                }
+            }
+        }
+
+        assert_eq!(mesh.vertex_count(), crossings);
+        assert_eq!(mesh.normals.as_ref().unwrap().len(), mesh.positions.len());
+    }
+
+    #[test]
+    fn test_closed_surface_is_manifold() {
+        let mesh = marching_cubes(&sphere_volume([20; 3], 0.7), 0.0);
+
+        // Every edge of a closed surface is shared by exactly two triangles
+        let mut edges = std::collections::HashMap::new();
+        for t in mesh.indices.chunks(3) {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2), "Mesh is not watertight by index");
+
+        // Every vertex is referenced
+        let mut used = vec![false; mesh.vertex_count()];
+        for &i in &mesh.indices {
+            used[i as usize] = true;
+        }
+        assert!(used.iter().all(|&u| u), "Mesh has unreferenced vertices");
+    }
+
+    #[test]
+    fn test_deterministic_vertex_order() {
+        let volume = sphere_volume([15; 3], 0.5);
+        let a = marching_cubes(&volume, 0.0);
+        let b = marching_cubes(&volume, 0.0);
+        assert_eq!(a.positions, b.positions);
+        assert_eq!(a.indices, b.indices);
+        assert_eq!(a.normals, b.normals);
+    }
+
+    #[test]
+    fn test_gaussian_smooth_preserves_constant_and_mass() {
+        let constant = Volume::new(vec![2.5; 5 * 4 * 3], [5, 4, 3], [1.0; 3], [0.0; 3]);
+        let smoothed = constant.gaussian_smooth(&GaussianKernel::new(1.0));
+        assert!(smoothed.values.iter().all(|&v| (v - 2.5).abs() < 1e-5));
+
+        // A single spike away from the edges spreads out but keeps its mass
+        // and its peak at the same voxel
+        let mut values = vec![0.0; 13 * 13 * 13];
+        values[6 + 6 * 13 + 6 * 169] = 1.0;
+        let spike = Volume::new(values, [13, 13, 13], [1.0; 3], [0.0; 3]);
+        let blurred = spike.gaussian_smooth(&GaussianKernel::new(1.0));
+        let total: f32 = blurred.values.iter().sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        let peak = blurred.values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        assert_eq!(blurred.get(6, 6, 6), peak);
+        assert!(peak < 0.1);
+    }
+
+    #[test]
+    fn test_default_options_match_plain_extraction() {
+        let volume = sphere_volume([14; 3], 0.6);
+        let plain = marching_cubes(&volume, 0.0);
+        let with_options = marching_cubes_with_options(&volume, 0.0, &MarchingCubesOptions::default());
+        assert_eq!(plain.positions, with_options.positions);
+        assert_eq!(plain.indices, with_options.indices);
+        assert_eq!(plain.normals, with_options.normals);
+    }
+
+    #[test]
+    fn test_options_pipeline() {
+        let volume = sphere_volume([24; 3], 0.7);
+        let plain = marching_cubes(&volume, 0.0);
+
+        let options = MarchingCubesOptions::new()
+            .with_pre_smoothing(GaussianKernel::new(0.8))
+            .with_decimation(DecimationTarget::TriangleCount { count: plain.triangle_count() / 3 })
+            .with_post_smoothing(MeshSmoothing::taubin(5));
+        let mesh = marching_cubes_with_options(&volume, 0.0, &options);
+
+        assert!(mesh.triangle_count() <= plain.triangle_count() / 3);
+        let normals = mesh.normals.as_ref().unwrap();
+        assert_eq!(normals.len(), mesh.positions.len());
+
+        // Re-sampled normals follow the negative gradient (inward for an SDF sphere)
+        for (p, n) in mesh.positions.chunks(3).zip(normals.chunks(3)) {
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            let radial = (p[0] * n[0] + p[1] * n[1] + p[2] * n[2]) / r;
+            assert!(radial < -0.95, "Normal not aligned with gradient: {}", radial);
+        }
+    }
+
     #[test]
     fn test_empty_volume() {
         // Volume entirely above iso-value
@@ -759,4 +1821,272 @@ mod tests {
         let mesh = marching_cubes(&volume, 0.0);
         assert!(mesh.positions.is_empty());
     }
+
+    #[test]
+    fn test_invalid_voxels_skip_cells() {
+        let volume = sphere_volume([20; 3], 0.7);
+        let (_, clean) = marching_cubes_with_report(&volume, 0.0);
+        assert_eq!(clean.skipped_cells, 0);
+        assert_eq!(clean.total_cells, 19 * 19 * 19);
+
+        // Knock out a block of voxels through the sphere wall, once as NaN and once by mask
+        let [nx, ny, _] = volume.dimensions;
+        let in_block = |i: usize| {
+            let (x, y, z) = (i % nx, (i / nx) % ny, i / (nx * ny));
+            (14..20).contains(&x) && (6..14).contains(&y) && (6..14).contains(&z)
+        };
+        let mut nan_volume = volume.clone();
+        for (i, v) in nan_volume.values.iter_mut().enumerate() {
+            if in_block(i) {
+                *v = f32::NAN;
+            }
+        }
+        let mask: Vec<bool> = (0..volume.values.len()).map(|i| !in_block(i)).collect();
+        let masked_volume = volume.clone().with_mask(mask);
+
+        let (nan_mesh, nan_report) = marching_cubes_with_report(&nan_volume, 0.0);
+        let (masked_mesh, masked_report) = marching_cubes_with_report(&masked_volume, 0.0);
+
+        // Every cell with a corner in the 6x8x8 block: 7x9x9, clipped to the volume in x
+        assert_eq!(nan_report.skipped_cells, 6 * 9 * 9);
+        assert_eq!(nan_report, masked_report);
+        assert_eq!(nan_mesh.positions, masked_mesh.positions);
+        assert_eq!(nan_mesh.indices, masked_mesh.indices);
+
+        assert_eq!(ExtractionReport::for_volume(&nan_volume), nan_report);
+        assert_eq!(ExtractionReport::for_volume(&masked_volume), masked_report);
+        assert_eq!(ExtractionReport::for_volume(&volume), clean);
+
+        let plain = marching_cubes(&volume, 0.0);
+        assert!(nan_mesh.triangle_count() < plain.triangle_count());
+        assert!(nan_mesh.positions.iter().all(|v| v.is_finite()));
+        assert!(nan_mesh.normals.as_ref().unwrap().iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_gaussian_kernel_json_is_checked() {
+        let kernel = GaussianKernel::new(1.5).with_radius(2);
+        let parsed: GaussianKernel = serde_json::from_str(&serde_json::to_string(&kernel).unwrap()).unwrap();
+        assert_eq!(parsed, kernel);
+
+        for sigma in ["0", "-1.0"] {
+            let json = format!(r#"{{"sigma": {sigma}, "radius": 3}}"#);
+            let err = serde_json::from_str::<GaussianKernel>(&json).unwrap_err();
+            assert!(err.to_string().contains("sigma must be positive"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_mask_is_optional_in_json() {
+        let volume = Volume::new(vec![0.0; 8], [2, 2, 2], [1.0; 3], [0.0; 3]);
+        let json = serde_json::to_string(&volume).unwrap();
+        assert!(!json.contains("mask"));
+        let parsed: Volume = serde_json::from_str(&json).unwrap();
+        assert!(parsed.mask.is_none());
+
+        let masked = volume.with_mask(vec![true; 8]);
+        let parsed: Volume = serde_json::from_str(&serde_json::to_string(&masked).unwrap()).unwrap();
+        assert_eq!(parsed.mask, Some(vec![true; 8]));
+    }
+
+    /// Coordinates clustered towards both ends of [-1, 1], like a wall-refined mesh.
+    fn stretched_axis(n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / (n - 1) as f32 * 2.0 - 1.0;
+                t * (0.6 + 0.4 * t * t)
+            })
+            .collect()
+    }
+
+    fn rectilinear_volume(n: usize, f: impl Fn(f32, f32, f32) -> f32) -> Volume {
+        let axis = stretched_axis(n);
+        let mut values = Vec::with_capacity(n * n * n);
+        for &z in &axis {
+            for &y in &axis {
+                for &x in &axis {
+                    values.push(f(x, y, z));
+                }
+            }
+        }
+        Volume::rectilinear(values, axis.clone(), axis.clone(), axis)
+    }
+
+    #[test]
+    fn test_rectilinear_sphere() {
+        let volume = rectilinear_volume(25, |x, y, z| (x * x + y * y + z * z).sqrt() - 0.7);
+        assert!(volume.is_rectilinear());
+        assert_eq!(volume.dimensions, [25, 25, 25]);
+        assert_eq!(volume.origin, [-1.0; 3]);
+
+        let mesh = marching_cubes(&volume, 0.0);
+        assert!(!mesh.indices.is_empty());
+        let normals = mesh.normals.as_ref().unwrap();
+
+        // Largest cell is at the volume edge; the sphere sits in finer cells
+        let max_step = volume.axis_coordinate(0, 1) - volume.axis_coordinate(0, 0);
+        for (p, n) in mesh.positions.chunks(3).zip(normals.chunks(3)) {
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            assert!((r - 0.7).abs() < 0.1 * max_step, "Vertex off surface: r = {}", r);
+            let radial = (p[0] * n[0] + p[1] * n[1] + p[2] * n[2]) / r;
+            assert!(radial < -0.99, "Normal not radial: {}", radial);
+        }
+    }
+
+    #[test]
+    fn test_rectilinear_derived_fields() {
+        // Linear field: exact gradient everywhere, including one-sided boundaries
+        let linear = rectilinear_volume(9, |x, y, z| 2.0 * x + 3.0 * y - z);
+        let expected = 14.0f32.sqrt();
+        for &g in &linear.gradient_magnitude().values {
+            assert!((g - expected).abs() < 1e-3, "Gradient magnitude {} != {}", g, expected);
+        }
+
+        // Quadratic field: the unequal-step three-point Laplacian is exact in the interior
+        let quadratic = rectilinear_volume(9, |x, y, z| x * x + 2.0 * y * y + 3.0 * z * z);
+        let laplacian = quadratic.laplacian();
+        for z in 1..8 {
+            for y in 1..8 {
+                for x in 1..8 {
+                    let l = laplacian.get(x, y, z);
+                    assert!((l - 12.0).abs() < 1e-2, "Laplacian {} != 12 at {:?}", l, (x, y, z));
+                }
+            }
+        }
+        assert!(laplacian.is_rectilinear());
+    }
+
+    #[test]
+    fn test_derived_fields_share_boundary_policy() {
+        let n = 7;
+        let field = |f: fn(f32, f32, f32) -> f32| {
+            let values = (0..n * n * n)
+                .map(|i| f((i % n) as f32 * 0.5, ((i / n) % n) as f32 * 0.5, (i / (n * n)) as f32 * 0.5))
+                .collect();
+            Volume::new(values, [n, n, n], [0.5; 3], [0.0; 3])
+        };
+
+        // Shifted three-point stencils keep the Laplacian exact up to the edge
+        let quadratic = field(|x, y, z| x * x + 2.0 * y * y + 3.0 * z * z);
+        assert!(quadratic.laplacian().values.iter().all(|&l| (l - 12.0).abs() < 1e-3));
+
+        // Masked voxels hold garbage that must not leak into any neighbour
+        let mask: Vec<bool> = (0..n * n * n).map(|i| i % n != 3).collect();
+        let mut garbage = quadratic.clone().with_mask(mask.clone());
+        for (v, &valid) in garbage.values.iter_mut().zip(&mask) {
+            if !valid {
+                *v = 1e6;
+            }
+        }
+        let laplacian = garbage.laplacian();
+        let blurred = Volume::new(vec![2.0; n * n * n], [n, n, n], [0.5; 3], [0.0; 3])
+            .with_mask(mask.clone())
+            .gaussian_smooth(&GaussianKernel::new(1.0));
+        let gradient = garbage.gradient_magnitude();
+        for (i, &valid) in mask.iter().enumerate() {
+            if valid {
+                assert!((laplacian.values[i] - 12.0).abs() < 1e-3);
+                assert!((blurred.values[i] - 2.0).abs() < 1e-5);
+                assert!(gradient.values[i] < 100.0);
+            } else {
+                assert!(laplacian.values[i].is_nan() && blurred.values[i].is_nan());
+                assert!(gradient.values[i].is_nan());
+            }
+        }
+
+        // A vector is invalid where any component is
+        let v = VectorVolume::new(field(|x, _, _| x), field(|_, y, _| y), field(|_, _, z| z).with_mask(mask.clone()));
+        let div = v.divergence();
+        assert_eq!(div.mask.as_deref(), Some(&mask[..]));
+        assert!(div.values[3].is_nan() && (div.values[2] - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    #[should_panic(expected = "share a grid")]
+    fn test_vector_volume_components_share_grid() {
+        let a = Volume::new(vec![0.0; 8], [2, 2, 2], [1.0; 3], [0.0; 3]);
+        let b = Volume::new(vec![0.0; 8], [2, 2, 2], [0.5; 3], [0.0; 3]);
+        VectorVolume::new(a.clone(), a, b);
+    }
+
+    #[test]
+    fn test_level_set_curvature_of_sphere() {
+        // Distance field: level sets are spheres of radius |p|
+        let volume = sphere_volume([41; 3], 0.0);
+        let mean = volume.mean_curvature();
+        let gauss = volume.gaussian_curvature();
+
+        let h = 2.0 / 40.0;
+        for (x, y, z) in [(30, 20, 20), (20, 28, 20), (26, 26, 26), (14, 20, 25)] {
+            let p = [x as f32 * h - 1.0, y as f32 * h - 1.0, z as f32 * h - 1.0];
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            let (km, kg) = (mean.get(x, y, z), gauss.get(x, y, z));
+            assert!((km - 1.0 / r).abs() < 0.05 / r, "Mean curvature {} at r = {}", km, r);
+            assert!((kg - 1.0 / (r * r)).abs() < 0.1 / (r * r), "Gaussian curvature {} at r = {}", kg, r);
+        }
+
+        // Flipping the field flips the mean curvature only
+        let mut flipped = volume.clone();
+        flipped.values.iter_mut().for_each(|v| *v = -*v);
+        assert!((flipped.mean_curvature().get(30, 20, 20) + mean.get(30, 20, 20)).abs() < 1e-5);
+        assert!((flipped.gaussian_curvature().get(30, 20, 20) - gauss.get(30, 20, 20)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_local_variance() {
+        let n = 6;
+        let constant = Volume::new(vec![3.0; n * n * n], [n, n, n], [1.0; 3], [0.0; 3]);
+        assert!(constant.local_variance(1).values.iter().all(|&v| v.abs() < 1e-6));
+
+        // Ramp along x: an interior 3-voxel window sees x-1, x, x+1 equally often
+        let ramp = Volume::new((0..n * n * n).map(|i| (i % n) as f32).collect(), [n, n, n], [1.0; 3], [0.0; 3]);
+        let variance = ramp.local_variance(1);
+        assert!((variance.get(2, 2, 2) - 2.0 / 3.0).abs() < 1e-5);
+        assert!((variance.get(2, 0, 5) - 2.0 / 3.0).abs() < 1e-5);
+        // Truncated window at the x boundary: values 0 and 1
+        assert!((variance.get(0, 2, 2) - 0.25).abs() < 1e-5);
+        assert!((ramp.local_std_dev(1).get(2, 2, 2) - (2.0f32 / 3.0).sqrt()).abs() < 1e-5);
+
+        // Invalid voxels are left out of the window
+        let masked = ramp.with_mask((0..n * n * n).map(|i| i % n != 1).collect());
+        assert!((masked.local_variance(1).get(0, 2, 2) - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_divergence_and_curl() {
+        let n = 7;
+        let h = 0.5;
+        let field = |f: fn([f32; 3]) -> f32| {
+            let values = (0..n * n * n)
+                .map(|i| f([(i % n) as f32 * h, ((i / n) % n) as f32 * h, (i / (n * n)) as f32 * h]))
+                .collect();
+            Volume::new(values, [n, n, n], [h; 3], [0.0; 3])
+        };
+
+        // Rotation about z plus a uniform expansion along z
+        let v = VectorVolume::new(field(|p| -p[1]), field(|p| p[0]), field(|p| 2.0 * p[2]));
+        let div = v.divergence();
+        let curl = v.curl();
+        for i in 0..n * n * n {
+            assert!((div.values[i] - 2.0).abs() < 1e-4);
+            assert!(curl.components[0].values[i].abs() < 1e-4);
+            assert!(curl.components[1].values[i].abs() < 1e-4);
+            assert!((curl.components[2].values[i] - 2.0).abs() < 1e-4);
+        }
+        assert!((curl.magnitude().values[0] - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_uniform_coordinates_match_regular_volume() {
+        let volume = sphere_volume([12; 3], 0.6);
+        let axis: Vec<f32> = (0..12).map(|i| volume.axis_coordinate(0, i)).collect();
+        let rectilinear = Volume::rectilinear(volume.values.clone(), axis.clone(), axis.clone(), axis);
+
+        let a = marching_cubes(&volume, 0.0);
+        let b = marching_cubes(&rectilinear, 0.0);
+        assert_eq!(a.indices, b.indices);
+        for (p, q) in a.positions.iter().zip(&b.positions) {
+            assert!((p - q).abs() < 1e-5);
+        }
+    }
 }
@@ -0,0 +1,460 @@
+//! Parallel and chunked Marching Cubes over z-slabs.
+//!
+//! Both variants are implementation strategies only: they produce exactly the
+//! mesh `marching_cubes` produces, bit for bit, with the same vertex and
+//! triangle order.
+//!
+//! - `marching_cubes_parallel` splits the cell layers into z-slabs, extracts
+//!   them concurrently and stitches vertices on the shared planes.
+//! - `StreamingMarchingCubes` is fed z-planes in order (e.g. slab-by-slab
+//!   from disk) and emits the mesh incrementally, holding only a few planes.
+
+use std::collections::VecDeque;
+
+use crate::geometry::Mesh;
+use crate::marching_cubes::{
+    central_gradient, extract_layer, marching_cubes, EdgeCache, GridAxes, GridSamples, MeshBuffers,
+    Volume,
+};
+
+/// Extract an isosurface using all available CPU cores.
+///
+/// Output is bit-for-bit identical to `marching_cubes`, including vertex
+/// and triangle order.
+pub fn marching_cubes_parallel(volume: &Volume, iso_value: f32) -> Mesh {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    marching_cubes_slabs(volume, iso_value, threads)
+}
+
+/// Extract an isosurface by processing `slab_count` z-slabs concurrently.
+///
+/// Each slab runs the serial algorithm on its own range of cell layers.
+/// Vertices on the plane shared by two slabs are taken from the lower slab,
+/// exactly as the serial scan creates them, so the stitched result equals
+/// `marching_cubes` regardless of the slab count.
+pub fn marching_cubes_slabs(volume: &Volume, iso_value: f32, slab_count: usize) -> Mesh {
+    let [nx, ny, nz] = volume.dimensions;
+    if nx < 2 || ny < 2 || nz < 2 {
+        return Mesh::new(vec![], vec![]);
+    }
+
+    let layers = nz - 1;
+    let slab_count = slab_count.clamp(1, layers);
+    if slab_count == 1 {
+        return marching_cubes(volume, iso_value);
+    }
+
+    // Contiguous, near-equal ranges of cell layers
+    let ranges: Vec<(usize, usize)> = (0..slab_count)
+        .map(|i| (i * layers / slab_count, (i + 1) * layers / slab_count))
+        .collect();
+
+    let slabs: Vec<SlabOutput> = std::thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|&(z0, z1)| scope.spawn(move || extract_slab(volume, iso_value, z0, z1)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("marching cubes worker panicked"))
+            .collect()
+    });
+
+    stitch(slabs)
+}
+
+/// Result of running the serial algorithm over one slab of cell layers.
+struct SlabOutput {
+    buffers: MeshBuffers,
+    /// Local vertex ids on the slab's bottom plane: (x-edges, y-edges).
+    bottom: (Vec<u32>, Vec<u32>),
+    /// Local vertex ids on the slab's top plane: (x-edges, y-edges).
+    top: (Vec<u32>, Vec<u32>),
+}
+
+fn extract_slab(volume: &Volume, iso_value: f32, z0: usize, z1: usize) -> SlabOutput {
+    let [nx, ny, _nz] = volume.dimensions;
+    let mut cache = EdgeCache::new(nx, ny);
+    let mut buffers = MeshBuffers::default();
+    let mut bottom = (Vec::new(), Vec::new());
+
+    for z in z0..z1 {
+        cache.advance_slab();
+        extract_layer(volume, volume.dimensions, iso_value, z, &mut cache, &mut buffers);
+        if z == z0 {
+            let (xs, ys) = cache.bottom_plane();
+            bottom = (xs.to_vec(), ys.to_vec());
+        }
+    }
+
+    let (xs, ys) = cache.top_plane();
+    SlabOutput {
+        buffers,
+        bottom,
+        top: (xs.to_vec(), ys.to_vec()),
+    }
+}
+
+/// Concatenate slab outputs in z order, reusing the lower slab's vertices on shared planes.
+fn stitch(slabs: Vec<SlabOutput>) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    // Global ids of the previous slab's top-plane vertices
+    let mut previous_top: Option<(Vec<u32>, Vec<u32>)> = None;
+
+    for slab in slabs {
+        let local_count = slab.buffers.positions.len() / 3;
+        let mut remap = vec![u32::MAX; local_count];
+
+        // Bottom-plane vertices were already created by the slab below
+        if let Some((top_x, top_y)) = &previous_top {
+            let pairs = slab.bottom.0.iter().zip(top_x).chain(slab.bottom.1.iter().zip(top_y));
+            // A vertex the lower slab skipped (invalid voxels below the plane)
+            // stays unmapped and is created here, as the serial scan does.
+            for (&local, &global) in pairs {
+                if local != u32::MAX {
+                    remap[local as usize] = global;
+                }
+            }
+        }
+
+        for (local, slot) in remap.iter_mut().enumerate() {
+            if *slot == u32::MAX {
+                *slot = (positions.len() / 3) as u32;
+                positions.extend_from_slice(&slab.buffers.positions[local * 3..local * 3 + 3]);
+                normals.extend_from_slice(&slab.buffers.normals[local * 3..local * 3 + 3]);
+            }
+        }
+
+        indices.extend(slab.buffers.indices.iter().map(|&i| remap[i as usize]));
+
+        let to_global = |ids: &[u32]| -> Vec<u32> {
+            ids.iter()
+                .map(|&i| if i == u32::MAX { u32::MAX } else { remap[i as usize] })
+                .collect()
+        };
+        previous_top = Some((to_global(&slab.top.0), to_global(&slab.top.1)));
+    }
+
+    Mesh::new(positions, indices).with_normals(normals)
+}
+
+/// A piece of an isosurface emitted by `StreamingMarchingCubes`.
+///
+/// Chunks are emitted in order. `indices` refer to global vertex ids, counting
+/// all vertices of earlier chunks, so appending every chunk in turn rebuilds
+/// the full mesh exactly.
+#[derive(Debug, Clone, Default)]
+pub struct IsosurfaceChunk {
+    /// Global id of the first vertex in this chunk.
+    pub vertex_offset: u32,
+    /// New vertex positions [x0, y0, z0, ...].
+    pub positions: Vec<f32>,
+    /// Normals for the new vertices.
+    pub normals: Vec<f32>,
+    /// New triangles, indexing into all vertices emitted so far.
+    pub indices: Vec<u32>,
+}
+
+impl IsosurfaceChunk {
+    /// Check if the chunk adds no geometry.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.indices.is_empty()
+    }
+
+    /// Append this chunk to a mesh assembled from the preceding chunks.
+    ///
+    /// # Panics
+    /// Panics if the mesh does not hold exactly the vertices of the earlier chunks.
+    pub fn append_to(&self, mesh: &mut Mesh) {
+        assert_eq!(
+            mesh.vertex_count(),
+            self.vertex_offset as usize,
+            "Chunks must be appended in order"
+        );
+        mesh.positions.extend_from_slice(&self.positions);
+        mesh.normals
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(&self.normals);
+        mesh.indices.extend_from_slice(&self.indices);
+    }
+}
+
+/// Marching cubes over a volume delivered one z-plane at a time.
+///
+/// Planes are x-fastest slices of `nx * ny` values, pushed in increasing z.
+/// The extractor keeps at most four planes in memory (central-difference
+/// normals need one plane on either side of each cell layer) and emits each
+/// cell layer as soon as its planes are available. The concatenated chunks
+/// are identical to `marching_cubes` on the assembled volume. Invalid voxels
+/// are marked by non-finite values; streamed planes carry no validity mask.
+/// Rectilinear grids are supported through `with_coordinates`.
+#[derive(Debug)]
+pub struct StreamingMarchingCubes {
+    dimensions: [usize; 3],
+    spacing: [f32; 3],
+    origin: [f32; 3],
+    coordinates: Option<[Vec<f32>; 3]>,
+    iso_value: f32,
+    /// Recent planes as (z, values), oldest first.
+    window: VecDeque<(usize, Vec<f32>)>,
+    planes_received: usize,
+    next_layer: usize,
+    cache: EdgeCache,
+    vertices_emitted: u32,
+}
+
+impl StreamingMarchingCubes {
+    /// Create an extractor for a volume of the given dimensions and geometry.
+    pub fn new(dimensions: [usize; 3], spacing: [f32; 3], origin: [f32; 3], iso_value: f32) -> Self {
+        Self {
+            dimensions,
+            spacing,
+            origin,
+            coordinates: None,
+            iso_value,
+            window: VecDeque::with_capacity(4),
+            planes_received: 0,
+            next_layer: 0,
+            cache: EdgeCache::new(dimensions[0], dimensions[1]),
+            vertices_emitted: 0,
+        }
+    }
+
+    /// Place grid points by explicit per-axis coordinates (rectilinear grid).
+    ///
+    /// # Panics
+    /// Panics if a coordinate vector's length does not match the dimensions.
+    pub fn with_coordinates(mut self, coordinates: [Vec<f32>; 3]) -> Self {
+        for (axis, c) in coordinates.iter().enumerate() {
+            assert_eq!(c.len(), self.dimensions[axis], "Coordinate length must match dimensions");
+        }
+        self.coordinates = Some(coordinates);
+        self
+    }
+
+    /// Whether every z-plane of the volume has been pushed.
+    pub fn is_complete(&self) -> bool {
+        self.planes_received == self.dimensions[2]
+    }
+
+    /// Push one or more consecutive z-planes and extract every cell layer they complete.
+    ///
+    /// # Panics
+    /// Panics if `values` is not a whole number of `nx * ny` planes, or if more
+    /// planes are pushed than the volume has.
+    pub fn push_planes(&mut self, values: &[f32]) -> IsosurfaceChunk {
+        let [nx, ny, nz] = self.dimensions;
+        let plane_len = nx * ny;
+        assert!(
+            plane_len > 0 && values.len().is_multiple_of(plane_len),
+            "Pushed values must be whole planes of nx * ny = {} values",
+            plane_len
+        );
+        assert!(
+            self.planes_received + values.len() / plane_len <= nz,
+            "More planes pushed than the volume's z dimension ({})",
+            nz
+        );
+
+        let mut buffers = MeshBuffers {
+            vertex_base: self.vertices_emitted,
+            ..Default::default()
+        };
+
+        for plane in values.chunks(plane_len) {
+            if self.window.len() == 4 {
+                self.window.pop_front();
+            }
+            self.window.push_back((self.planes_received, plane.to_vec()));
+            self.planes_received += 1;
+
+            // Layer z needs planes up to z + 2 (or the last plane)
+            let last = self.planes_received - 1;
+            while self.next_layer + 1 < nz && (self.next_layer + 2 <= last || last == nz - 1) {
+                self.extract_next_layer(&mut buffers);
+            }
+        }
+
+        self.vertices_emitted = buffers.next_vertex();
+        IsosurfaceChunk {
+            vertex_offset: buffers.vertex_base,
+            positions: buffers.positions,
+            normals: buffers.normals,
+            indices: buffers.indices,
+        }
+    }
+
+    /// Push every remaining plane of a full volume and return the assembled mesh.
+    ///
+    /// Convenience for testing and small inputs; equals `marching_cubes(volume, iso)`.
+    pub fn extract_all(volume: &Volume, iso_value: f32) -> Mesh {
+        let mut extractor =
+            StreamingMarchingCubes::new(volume.dimensions, volume.spacing, volume.origin, iso_value);
+        if let Some(coordinates) = &volume.coordinates {
+            extractor = extractor.with_coordinates(coordinates.clone());
+        }
+        let mut mesh = Mesh::new(vec![], vec![]).with_normals(vec![]);
+        let [nx, ny, nz] = volume.dimensions;
+        if nx * ny > 0 {
+            // Masked-out voxels are streamed as NaN
+            for z in 0..nz {
+                let plane: Vec<f32> = (0..nx * ny)
+                    .map(|i| volume.valid_value(i % nx, i / nx, z))
+                    .collect();
+                extractor.push_planes(&plane).append_to(&mut mesh);
+            }
+        }
+        mesh
+    }
+
+    fn extract_next_layer(&mut self, buffers: &mut MeshBuffers) {
+        let [nx, ny, _nz] = self.dimensions;
+        let z = self.next_layer;
+        self.next_layer += 1;
+        if nx < 2 || ny < 2 {
+            return;
+        }
+
+        let window = PlaneWindow {
+            planes: &self.window,
+            dimensions: self.dimensions,
+            axes: GridAxes {
+                spacing: self.spacing,
+                origin: self.origin,
+                coordinates: self.coordinates.as_ref(),
+            },
+        };
+        self.cache.advance_slab();
+        extract_layer(&window, self.dimensions, self.iso_value, z, &mut self.cache, buffers);
+    }
+}
+
+/// Grid samples backed by the planes currently held by a streaming extractor.
+struct PlaneWindow<'a> {
+    planes: &'a VecDeque<(usize, Vec<f32>)>,
+    dimensions: [usize; 3],
+    axes: GridAxes<'a>,
+}
+
+impl GridSamples for PlaneWindow<'_> {
+    fn value(&self, x: usize, y: usize, z: usize) -> f32 {
+        let (first, _) = self.planes[0];
+        let (_, plane) = &self.planes[z - first];
+        plane[x + y * self.dimensions[0]]
+    }
+
+    fn gradient(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        central_gradient(|x, y, z| self.value(x, y, z), self.dimensions, &self.axes, x, y, z)
+    }
+
+    fn world(&self, x: usize, y: usize, z: usize) -> [f32; 3] {
+        self.axes.world(x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two overlapping blobs on an anisotropic grid, so every slab has geometry.
+    fn blob_volume(dims: [usize; 3]) -> Volume {
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    let p = [
+                        x as f32 / (dims[0] - 1) as f32,
+                        y as f32 / (dims[1] - 1) as f32,
+                        z as f32 / (dims[2] - 1) as f32,
+                    ];
+                    let d = |c: [f32; 3]| {
+                        ((p[0] - c[0]).powi(2) + (p[1] - c[1]).powi(2) + (p[2] - c[2]).powi(2)).sqrt()
+                    };
+                    values.push(d([0.4, 0.5, 0.35]).min(d([0.6, 0.45, 0.65])) - 0.25);
+                }
+            }
+        }
+        Volume::new(values, dims, [0.1, 0.07, 0.05], [-1.0, 0.5, 2.0])
+    }
+
+    fn assert_identical(a: &Mesh, b: &Mesh) {
+        assert_eq!(a.positions, b.positions);
+        assert_eq!(a.indices, b.indices);
+        assert_eq!(a.normals, b.normals);
+    }
+
+    #[test]
+    fn test_slabs_match_serial() {
+        let volume = blob_volume([19, 17, 23]);
+        let serial = marching_cubes(&volume, 0.0);
+        assert!(serial.triangle_count() > 100);
+
+        for slabs in [1, 2, 3, 7, 22, 64] {
+            assert_identical(&serial, &marching_cubes_slabs(&volume, 0.0, slabs));
+        }
+        assert_identical(&serial, &marching_cubes_parallel(&volume, 0.0));
+
+        // Invalid voxels on slab boundary planes skip the cells below them
+        let [nx, ny, _] = volume.dimensions;
+        let on_planes = |i: usize| (i / nx) % ny == 8 && [4, 7, 11].contains(&(i / (nx * ny)));
+        let mut nan_volume = volume.clone();
+        for (i, v) in nan_volume.values.iter_mut().enumerate() {
+            if on_planes(i) {
+                *v = f32::NAN;
+            }
+        }
+        let masked_volume = volume.clone().with_mask((0..volume.values.len()).map(|i| !on_planes(i)).collect());
+        for invalid in [nan_volume, masked_volume] {
+            let serial = marching_cubes(&invalid, 0.0);
+            for slabs in [2, 3, 7, 22] {
+                assert_identical(&serial, &marching_cubes_slabs(&invalid, 0.0, slabs));
+            }
+            assert_identical(&serial, &marching_cubes_parallel(&invalid, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_serial() {
+        let volume = blob_volume([15, 12, 18]);
+        let serial = marching_cubes(&volume, 0.0);
+        assert_identical(&serial, &StreamingMarchingCubes::extract_all(&volume, 0.0));
+
+        // Uneven batches of planes give the same result
+        let plane_len = 15 * 12;
+        let mut extractor = StreamingMarchingCubes::new(volume.dimensions, volume.spacing, volume.origin, 0.0);
+        let mut mesh = Mesh::new(vec![], vec![]);
+        for batch in volume.values.chunks(plane_len * 5) {
+            extractor.push_planes(batch).append_to(&mut mesh);
+        }
+        assert!(extractor.is_complete());
+        assert_identical(&serial, &mesh);
+    }
+
+    #[test]
+    fn test_rectilinear_streaming_matches_serial() {
+        let uniform = blob_volume([13, 11, 16]);
+        let stretch = |n: usize| (0..n).map(|i| (i as f32 / (n - 1) as f32).powi(2) * 2.0).collect::<Vec<_>>();
+        let volume = Volume::rectilinear(uniform.values, stretch(13), stretch(11), stretch(16));
+
+        let serial = marching_cubes(&volume, 0.0);
+        assert_identical(&serial, &marching_cubes_slabs(&volume, 0.0, 3));
+        assert_identical(&serial, &StreamingMarchingCubes::extract_all(&volume, 0.0));
+    }
+
+    #[test]
+    fn test_thin_volumes() {
+        let volume = blob_volume([6, 6, 2]);
+        assert_identical(&marching_cubes(&volume, 0.0), &marching_cubes_slabs(&volume, 0.0, 4));
+        assert_identical(&marching_cubes(&volume, 0.0), &StreamingMarchingCubes::extract_all(&volume, 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "whole planes")]
+    fn test_partial_plane_panics() {
+        let mut extractor = StreamingMarchingCubes::new([4, 4, 4], [1.0; 3], [0.0; 3], 0.0);
+        extractor.push_planes(&[0.0; 10]);
+    }
+}
@@ -3,10 +3,11 @@
 //! Materials control how geometry looks, never what it is.
 //! Geometry references materials by ID.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// A material that can be referenced by geometry primitives.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Material {
     /// Uniform solid color.
@@ -26,7 +27,7 @@ impl Material {
 }
 
 /// A material with uniform solid color.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SolidMaterial {
     /// Unique identifier.
     pub id: String,
@@ -53,7 +54,7 @@ impl SolidMaterial {
 }
 
 /// A material that maps scalar values to colors via a colormap.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScalarMappedMaterial {
     /// Unique identifier.
     pub id: String,
@@ -100,6 +101,32 @@ impl ScalarMappedMaterial {
         self.missing_color = color;
         self
     }
+
+    /// Map a scalar to an RGB color.
+    ///
+    /// Non-finite values get the missing color; unknown colormaps fall back to grayscale.
+    pub fn color(&self, scalar: f32) -> [f32; 3] {
+        if !scalar.is_finite() {
+            return [self.missing_color[0], self.missing_color[1], self.missing_color[2]];
+        }
+
+        let [min, max] = self.range;
+        let t = if max > min {
+            (scalar - min) / (max - min)
+        } else {
+            0.5
+        };
+
+        let t = if self.clamp { t.clamp(0.0, 1.0) } else { t };
+
+        if let Some(cmap) = Colormap::from_name(&self.colormap) {
+            cmap.sample(t)
+        } else {
+            // Unknown colormap, use grayscale
+            let v = t.clamp(0.0, 1.0);
+            [v, v, v]
+        }
+    }
 }
 
 /// Available colormap identifiers.
@@ -0,0 +1,256 @@
+//! Per-vertex geometric attributes of triangle meshes.
+//!
+//! Discrete curvature (cotangent Laplacian and angle defect), vertex areas and
+//! distances, computed from positions and topology only. Each attribute can be
+//! written into `Mesh::scalars` for colouring through a `ScalarMappedMaterial`.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Mesh;
+use crate::normals::{vertex_normals, NormalWeighting};
+
+/// A per-vertex scalar attribute derived from mesh geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MeshAttribute {
+    /// Discrete mean curvature, see [`Mesh::mean_curvature`].
+    MeanCurvature,
+    /// Discrete Gaussian curvature, see [`Mesh::gaussian_curvature`].
+    GaussianCurvature,
+    /// Mixed Voronoi area, see [`Mesh::vertex_areas`].
+    VertexArea,
+    /// Euclidean distance to a point.
+    DistanceToPoint { point: [f32; 3] },
+    /// Signed distance to a plane, positive on the side the normal points to.
+    DistanceToPlane { point: [f32; 3], normal: [f32; 3] },
+}
+
+impl Mesh {
+    /// Replace the scalars with the given attribute.
+    pub fn with_attribute(self, attribute: MeshAttribute) -> Mesh {
+        let scalars = self.attribute(attribute);
+        self.with_scalars(scalars)
+    }
+
+    /// Evaluate an attribute at every vertex.
+    pub fn attribute(&self, attribute: MeshAttribute) -> Vec<f32> {
+        match attribute {
+            MeshAttribute::MeanCurvature => self.mean_curvature(),
+            MeshAttribute::GaussianCurvature => self.gaussian_curvature(),
+            MeshAttribute::VertexArea => self.vertex_areas(),
+            MeshAttribute::DistanceToPoint { point } => self.distance_to_point(point),
+            MeshAttribute::DistanceToPlane { point, normal } => self.distance_to_plane(point, normal),
+        }
+    }
+
+    /// Mixed Voronoi area of each vertex (Meyer et al.).
+    ///
+    /// Non-obtuse triangles contribute their Voronoi region; obtuse triangles
+    /// give half their area to the obtuse corner and a quarter to the others.
+    /// The areas sum to the surface area of the mesh.
+    pub fn vertex_areas(&self) -> Vec<f32> {
+        self.cotangent_data().area
+    }
+
+    /// Discrete mean curvature per vertex from the cotangent Laplacian.
+    ///
+    /// The Laplace-Beltrami operator of the positions, `Δx = -2 H n`, is
+    /// projected onto the vertex normal: positive where the surface bends
+    /// away from its normals, so a sphere with outward normals gives `1 / r`.
+    /// Meshes without normals use area-weighted face normals. Boundary and
+    /// isolated vertices get zero.
+    pub fn mean_curvature(&self) -> Vec<f32> {
+        let data = self.cotangent_data();
+        let normals = match self.normals.as_ref() {
+            Some(normals) if normals.len() == self.positions.len() => normals.clone(),
+            _ => vertex_normals(self, NormalWeighting::Area),
+        };
+
+        (0..self.vertex_count())
+            .map(|v| {
+                if data.boundary[v] || data.area[v] <= 0.0 {
+                    return 0.0;
+                }
+                let n = Vec3::from_slice(&normals[v * 3..v * 3 + 3]).normalize_or_zero();
+                -0.5 * (data.laplacian[v] / data.area[v]).dot(n)
+            })
+            .collect()
+    }
+
+    /// Discrete Gaussian curvature per vertex from the angle defect.
+    ///
+    /// `(2π - Σθ) / A` with θ the triangle angles at the vertex and A its mixed
+    /// area; the values times the areas sum to `2π χ` on a closed mesh.
+    /// Boundary and isolated vertices get zero.
+    pub fn gaussian_curvature(&self) -> Vec<f32> {
+        let data = self.cotangent_data();
+        (0..self.vertex_count())
+            .map(|v| {
+                if data.boundary[v] || data.area[v] <= 0.0 {
+                    0.0
+                } else {
+                    (2.0 * PI - data.angle_sum[v]) / data.area[v]
+                }
+            })
+            .collect()
+    }
+
+    /// Euclidean distance of each vertex to `point`.
+    pub fn distance_to_point(&self, point: [f32; 3]) -> Vec<f32> {
+        let point = Vec3::from(point);
+        self.positions.chunks_exact(3).map(|p| Vec3::from_slice(p).distance(point)).collect()
+    }
+
+    /// Signed distance of each vertex to the plane through `point` with `normal`.
+    ///
+    /// # Panics
+    /// Panics if the normal is zero.
+    pub fn distance_to_plane(&self, point: [f32; 3], normal: [f32; 3]) -> Vec<f32> {
+        let normal = Vec3::from(normal);
+        assert!(normal.length_squared() > 0.0, "Plane normal must be non-zero");
+        let (point, normal) = (Vec3::from(point), normal.normalize());
+        self.positions.chunks_exact(3).map(|p| (Vec3::from_slice(p) - point).dot(normal)).collect()
+    }
+
+    /// Cotangent-weighted Laplacian sums, mixed areas, angle sums and boundary flags.
+    fn cotangent_data(&self) -> CotangentData {
+        let n = self.vertex_count();
+        let mut data = CotangentData {
+            laplacian: vec![Vec3::ZERO; n],
+            area: vec![0.0; n],
+            angle_sum: vec![0.0; n],
+            boundary: vec![false; n],
+        };
+        let p = |v: u32| Vec3::from_slice(&self.positions[v as usize * 3..v as usize * 3 + 3]);
+
+        let mut edge_count: HashMap<(u32, u32), u32> = HashMap::new();
+        for t in self.indices.chunks_exact(3) {
+            let corners = [p(t[0]), p(t[1]), p(t[2])];
+            let double_area = (corners[1] - corners[0]).cross(corners[2] - corners[0]).length();
+            if double_area <= f32::EPSILON * (corners[1] - corners[0]).length_squared() {
+                continue;
+            }
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edge_count.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+
+            // Angle and cotangent at each corner
+            let mut angle = [0.0f32; 3];
+            let mut cot = [0.0f32; 3];
+            for k in 0..3 {
+                let (u, w) = (corners[(k + 1) % 3] - corners[k], corners[(k + 2) % 3] - corners[k]);
+                let (dot, cross) = (u.dot(w), u.cross(w).length());
+                angle[k] = cross.atan2(dot);
+                cot[k] = dot / cross;
+            }
+
+            for k in 0..3 {
+                let (i, j, o) = (k, (k + 1) % 3, (k + 2) % 3);
+                let (vi, vj) = (t[i] as usize, t[j] as usize);
+
+                // Edge (i, j) is opposite corner o
+                let w = 0.5 * cot[o];
+                data.laplacian[vi] += w * (corners[j] - corners[i]);
+                data.laplacian[vj] += w * (corners[i] - corners[j]);
+                data.angle_sum[t[k] as usize] += angle[k];
+
+                // Mixed area of corner i
+                let area = 0.5 * double_area;
+                data.area[vi] += if angle[i] > 0.5 * PI {
+                    0.5 * area
+                } else if angle[j] > 0.5 * PI || angle[o] > 0.5 * PI {
+                    0.25 * area
+                } else {
+                    let (eij, eio) = (corners[j] - corners[i], corners[o] - corners[i]);
+                    (eij.length_squared() * cot[o] + eio.length_squared() * cot[j]) / 8.0
+                };
+            }
+        }
+
+        for ((a, b), count) in edge_count {
+            if count == 1 {
+                data.boundary[a as usize] = true;
+                data.boundary[b as usize] = true;
+            }
+        }
+        data
+    }
+}
+
+struct CotangentData {
+    /// Per vertex: `Σ (cot α + cot β) / 2 * (x_j - x_i)` over incident edges.
+    laplacian: Vec<Vec3>,
+    /// Mixed Voronoi area.
+    area: Vec<f32>,
+    /// Sum of incident triangle angles.
+    angle_sum: Vec<f32>,
+    /// Whether the vertex lies on an edge used by a single triangle.
+    boundary: Vec<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marching_cubes::marching_cubes;
+    use crate::marching_cubes::test_support::sphere_volume;
+
+    /// Closed sphere of the given radius, with outward normals.
+    fn sphere_mesh(radius: f32) -> Mesh {
+        // Negate the distance so values decrease outward and normals (down the gradient) point out
+        let mut volume = sphere_volume([32; 3], radius);
+        volume.values.iter_mut().for_each(|v| *v = -*v);
+        marching_cubes(&volume, 0.0)
+    }
+
+    #[test]
+    fn test_sphere_curvature() {
+        let radius = 0.7;
+        let mesh = sphere_mesh(radius);
+        let area = mesh.vertex_areas();
+        let total_area: f32 = area.iter().sum();
+        assert!((total_area - 4.0 * PI * radius * radius).abs() < 0.02 * total_area);
+
+        // Gauss-Bonnet: total curvature of a closed genus-0 surface is 4π
+        let gauss = mesh.gaussian_curvature();
+        let total: f32 = gauss.iter().zip(&area).map(|(k, a)| k * a).sum();
+        assert!((total - 4.0 * PI).abs() < 1e-3, "Total Gaussian curvature {}", total);
+
+        // Area-weighted mean of the mean curvature approaches 1 / r
+        let mean = mesh.mean_curvature();
+        let average: f32 = mean.iter().zip(&area).map(|(h, a)| h * a).sum::<f32>() / total_area;
+        assert!((average - 1.0 / radius).abs() < 0.03 / radius, "Mean curvature {}", average);
+
+        // Without stored normals the sign follows the winding, which matches
+        let mut bare = mesh.clone();
+        bare.normals = None;
+        let average_bare: f32 = bare.mean_curvature().iter().zip(&area).map(|(h, a)| h * a).sum::<f32>() / total_area;
+        assert!((average_bare - average).abs() < 0.01 * average);
+    }
+
+    #[test]
+    fn test_flat_patch_and_distances() {
+        // Square fan around a centre vertex in the z = 0 plane
+        let mesh = Mesh::new(
+            vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+            vec![0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 1],
+        );
+        assert_eq!(mesh.mean_curvature(), vec![0.0; 5]);
+        assert_eq!(mesh.gaussian_curvature()[0], 0.0);
+        // Right isosceles triangles are not obtuse: the centre gets a quarter of each
+        assert!((mesh.vertex_areas()[0] - 4.0 * 0.5 * 0.5).abs() < 1e-6);
+        assert!((mesh.vertex_areas().iter().sum::<f32>() - 2.0).abs() < 1e-6);
+
+        let mesh = mesh.with_attribute(MeshAttribute::DistanceToPoint { point: [0.0, 0.0, 2.0] });
+        assert_eq!(mesh.scalars.as_ref().unwrap()[0], 2.0);
+        let d = mesh.attribute(MeshAttribute::DistanceToPlane {
+            point: [0.5, 0.0, 0.0],
+            normal: [2.0, 0.0, 0.0],
+        });
+        assert_eq!(d, vec![-0.5, 0.5, -0.5, -1.5, -0.5]);
+    }
+}
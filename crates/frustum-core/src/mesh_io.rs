@@ -0,0 +1,1024 @@
+//! Mesh file readers and writers.
+//!
+//! Loads and saves a `Mesh` as PLY (ASCII and binary), Wavefront OBJ, STL
+//! (ASCII and binary) and OFF. Positions, triangle indices, per-vertex
+//! normals and (PLY only) per-vertex scalars map directly onto the `Mesh`
+//! fields; other attributes such as texture coordinates, colours and
+//! materials are ignored.
+//!
+//! Polygons with more than three vertices are fan-triangulated around their
+//! first vertex by default, or rejected with `FacePolicy::Reject`. Every
+//! reader is deterministic, and malformed input produces a `MeshIoError`,
+//! never a panic.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::geometry::Mesh;
+
+/// Errors that can occur while reading or writing a mesh file.
+#[derive(Error, Debug)]
+pub enum MeshIoError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid {format} data: {message}")]
+    Parse { format: &'static str, message: String },
+    #[error("{format} face with {vertices} vertices; only triangles are accepted")]
+    NonTriangleFace { format: &'static str, vertices: usize },
+    #[error("{format} face references vertex {index}, but there are {vertex_count} vertices")]
+    IndexOutOfRange {
+        format: &'static str,
+        index: i64,
+        vertex_count: usize,
+    },
+    #[error("Unknown mesh file extension: {0:?}")]
+    UnknownFormat(String),
+}
+
+/// Mesh file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeshFormat {
+    Ply,
+    Obj,
+    Stl,
+    Off,
+}
+
+impl MeshFormat {
+    /// Format for a file extension (case-insensitive), e.g. "ply".
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "ply" => Some(MeshFormat::Ply),
+            "obj" => Some(MeshFormat::Obj),
+            "stl" => Some(MeshFormat::Stl),
+            "off" => Some(MeshFormat::Off),
+            _ => None,
+        }
+    }
+
+    fn from_path(path: &Path) -> Result<Self, MeshIoError> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        Self::from_extension(extension).ok_or_else(|| MeshIoError::UnknownFormat(extension.to_string()))
+    }
+}
+
+/// Text or binary encoding for formats that have both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeshEncoding {
+    Ascii,
+    /// Little-endian binary.
+    #[default]
+    Binary,
+}
+
+/// Handling of polygons with more than three vertices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FacePolicy {
+    /// Split into a fan of triangles around the first vertex.
+    #[default]
+    Triangulate,
+    /// Fail with `MeshIoError::NonTriangleFace`.
+    Reject,
+}
+
+/// Options for reading meshes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeshReadOptions {
+    /// Handling of non-triangle faces.
+    #[serde(default)]
+    pub faces: FacePolicy,
+    /// PLY vertex property read into `Mesh::scalars` (MeshLab uses "quality").
+    #[serde(default = "default_scalar_property")]
+    pub scalar_property: String,
+}
+
+fn default_scalar_property() -> String {
+    "scalar".to_string()
+}
+
+impl Default for MeshReadOptions {
+    fn default() -> Self {
+        Self {
+            faces: FacePolicy::default(),
+            scalar_property: default_scalar_property(),
+        }
+    }
+}
+
+impl MeshReadOptions {
+    /// Triangulate polygons and read PLY scalars from the "scalar" property.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the handling of non-triangle faces.
+    pub fn with_faces(mut self, faces: FacePolicy) -> Self {
+        self.faces = faces;
+        self
+    }
+
+    /// Set the PLY vertex property read as scalars.
+    pub fn with_scalar_property(mut self, name: impl Into<String>) -> Self {
+        self.scalar_property = name.into();
+        self
+    }
+}
+
+/// Read a mesh, choosing the format from the file extension.
+pub fn read_mesh(path: impl AsRef<Path>, options: &MeshReadOptions) -> Result<Mesh, MeshIoError> {
+    let path = path.as_ref();
+    let format = MeshFormat::from_path(path)?;
+    let bytes = fs::read(path)?;
+    match format {
+        MeshFormat::Ply => parse_ply(&bytes, options),
+        MeshFormat::Obj => parse_obj(text("OBJ", &bytes)?, options),
+        MeshFormat::Stl => parse_stl(&bytes),
+        MeshFormat::Off => parse_off(text("OFF", &bytes)?, options),
+    }
+}
+
+/// Write a mesh, choosing the format from the file extension.
+///
+/// PLY and STL are written in binary.
+pub fn write_mesh(mesh: &Mesh, path: impl AsRef<Path>) -> Result<(), MeshIoError> {
+    let path = path.as_ref();
+    let bytes = match MeshFormat::from_path(path)? {
+        MeshFormat::Ply => write_ply(mesh, MeshEncoding::Binary),
+        MeshFormat::Obj => write_obj(mesh).into_bytes(),
+        MeshFormat::Stl => write_stl(mesh, MeshEncoding::Binary),
+        MeshFormat::Off => write_off(mesh).into_bytes(),
+    };
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+// ============================================================================
+// Shared helpers
+// ============================================================================
+
+fn parse_error(format: &'static str, message: impl Into<String>) -> MeshIoError {
+    MeshIoError::Parse {
+        format,
+        message: message.into(),
+    }
+}
+
+fn text<'a>(format: &'static str, bytes: &'a [u8]) -> Result<&'a str, MeshIoError> {
+    std::str::from_utf8(bytes).map_err(|_| parse_error(format, "file is not valid UTF-8"))
+}
+
+fn parse_number<T: std::str::FromStr>(format: &'static str, token: Option<&str>) -> Result<T, MeshIoError> {
+    let token = token.ok_or_else(|| parse_error(format, "unexpected end of data"))?;
+    token
+        .parse()
+        .map_err(|_| parse_error(format, format!("invalid number {:?}", token)))
+}
+
+/// Append the triangles of a polygon with vertex indices `face`.
+fn push_face(
+    format: &'static str,
+    face: &[u32],
+    vertex_count: usize,
+    policy: FacePolicy,
+    indices: &mut Vec<u32>,
+) -> Result<(), MeshIoError> {
+    if let Some(&index) = face.iter().find(|&&i| i as usize >= vertex_count) {
+        return Err(MeshIoError::IndexOutOfRange {
+            format,
+            index: index as i64,
+            vertex_count,
+        });
+    }
+    match face.len() {
+        0..=2 => Err(parse_error(format, format!("face with {} vertices", face.len()))),
+        3 => {
+            indices.extend_from_slice(face);
+            Ok(())
+        }
+        n if policy == FacePolicy::Reject => Err(MeshIoError::NonTriangleFace { format, vertices: n }),
+        _ => {
+            for k in 1..face.len() - 1 {
+                indices.extend_from_slice(&[face[0], face[k], face[k + 1]]);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Unit normal of triangle `t` from its winding, or zero if degenerate.
+fn face_normal(positions: &[f32], t: &[u32]) -> [f32; 3] {
+    let p = |k: usize| {
+        let v = t[k] as usize * 3;
+        [positions[v], positions[v + 1], positions[v + 2]]
+    };
+    let (a, b, c) = (p(0), p(1), p(2));
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let w = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [u[1] * w[2] - u[2] * w[1], u[2] * w[0] - u[0] * w[2], u[0] * w[1] - u[1] * w[0]];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 {
+        [n[0] / len, n[1] / len, n[2] / len]
+    } else {
+        [0.0; 3]
+    }
+}
+
+// ============================================================================
+// PLY
+// ============================================================================
+
+/// Scalar type of a PLY property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlyType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyType {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => PlyType::I8,
+            "uchar" | "uint8" => PlyType::U8,
+            "short" | "int16" => PlyType::I16,
+            "ushort" | "uint16" => PlyType::U16,
+            "int" | "int32" => PlyType::I32,
+            "uint" | "uint32" => PlyType::U32,
+            "float" | "float32" => PlyType::F32,
+            "double" | "float64" => PlyType::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            PlyType::I8 | PlyType::U8 => 1,
+            PlyType::I16 | PlyType::U16 => 2,
+            PlyType::I32 | PlyType::U32 | PlyType::F32 => 4,
+            PlyType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum PlyProperty {
+    Scalar { name: String, ty: PlyType },
+    List { name: String, count: PlyType, item: PlyType },
+}
+
+#[derive(Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+/// Source of PLY property values: whitespace-separated text or packed binary.
+enum PlyValues<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary { bytes: &'a [u8], pos: usize, big_endian: bool },
+}
+
+impl PlyValues<'_> {
+    fn read(&mut self, ty: PlyType) -> Result<f64, MeshIoError> {
+        match self {
+            PlyValues::Ascii(tokens) => parse_number("PLY", tokens.next()),
+            PlyValues::Binary { bytes, pos, big_endian } => {
+                let size = ty.size();
+                let b = bytes
+                    .get(*pos..*pos + size)
+                    .ok_or_else(|| parse_error("PLY", "unexpected end of binary data"))?;
+                *pos += size;
+
+                macro_rules! decode {
+                    ($t:ty) => {{
+                        let b = b.try_into().unwrap();
+                        (if *big_endian { <$t>::from_be_bytes(b) } else { <$t>::from_le_bytes(b) }) as f64
+                    }};
+                }
+                Ok(match ty {
+                    PlyType::I8 => b[0] as i8 as f64,
+                    PlyType::U8 => b[0] as f64,
+                    PlyType::I16 => decode!(i16),
+                    PlyType::U16 => decode!(u16),
+                    PlyType::I32 => decode!(i32),
+                    PlyType::U32 => decode!(u32),
+                    PlyType::F32 => decode!(f32),
+                    PlyType::F64 => decode!(f64),
+                })
+            }
+        }
+    }
+}
+
+/// Parse an ASCII or binary PLY file.
+///
+/// Reads `x`, `y`, `z`, optional `nx`, `ny`, `nz` and the scalar property of
+/// the `vertex` element, and the `vertex_indices` (or `vertex_index`) list of
+/// the `face` element. Other elements and properties are skipped.
+pub fn parse_ply(bytes: &[u8], options: &MeshReadOptions) -> Result<Mesh, MeshIoError> {
+    const FORMAT: &str = "PLY";
+
+    // Header lines up to and including "end_header"
+    let end = find_subslice(bytes, b"end_header")
+        .ok_or_else(|| parse_error(FORMAT, "missing end_header"))?;
+    let body_start = bytes[end..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|p| end + p + 1)
+        .unwrap_or(bytes.len());
+    let header = text(FORMAT, &bytes[..end])?;
+
+    let mut lines = header.lines().map(str::trim);
+    if lines.next() != Some("ply") {
+        return Err(parse_error(FORMAT, "missing 'ply' magic"));
+    }
+
+    let mut encoding = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    for line in lines {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("format") => {
+                encoding = Some(match words.next() {
+                    Some("ascii") => None,
+                    Some("binary_little_endian") => Some(false),
+                    Some("binary_big_endian") => Some(true),
+                    other => return Err(parse_error(FORMAT, format!("unknown format {:?}", other))),
+                });
+            }
+            Some("element") => {
+                let name = words.next().unwrap_or("").to_string();
+                let count = parse_number(FORMAT, words.next())?;
+                elements.push(PlyElement {
+                    name,
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| parse_error(FORMAT, "property before any element"))?;
+                let ty_name = words.next().unwrap_or("");
+                let ty = |name: &str| {
+                    PlyType::from_name(name).ok_or_else(|| parse_error(FORMAT, format!("unknown property type {:?}", name)))
+                };
+                let property = if ty_name == "list" {
+                    let count = ty(words.next().unwrap_or(""))?;
+                    let item = ty(words.next().unwrap_or(""))?;
+                    PlyProperty::List {
+                        name: words.next().unwrap_or("").to_string(),
+                        count,
+                        item,
+                    }
+                } else {
+                    PlyProperty::Scalar {
+                        name: words.next().unwrap_or("").to_string(),
+                        ty: ty(ty_name)?,
+                    }
+                };
+                element.properties.push(property);
+            }
+            Some("comment") | Some("obj_info") | None => {}
+            Some(other) => return Err(parse_error(FORMAT, format!("unexpected header line {:?}", other))),
+        }
+    }
+    let encoding = encoding.ok_or_else(|| parse_error(FORMAT, "missing format line"))?;
+
+    let body = &bytes[body_start..];
+    let mut values = match encoding {
+        None => PlyValues::Ascii(text(FORMAT, body)?.split_ascii_whitespace()),
+        Some(big_endian) => PlyValues::Binary {
+            bytes: body,
+            pos: 0,
+            big_endian,
+        },
+    };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut scalars = Vec::new();
+    let mut faces: Vec<Vec<u32>> = Vec::new();
+    let mut vertex_count = 0;
+
+    for element in &elements {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+        let has = |name: &str| {
+            element
+                .properties
+                .iter()
+                .any(|p| matches!(p, PlyProperty::Scalar { name: n, .. } if n == name))
+        };
+        let with_normals = is_vertex && has("nx") && has("ny") && has("nz");
+        let with_scalars = is_vertex && has(&options.scalar_property);
+        if is_vertex {
+            vertex_count = element.count;
+            if !(has("x") && has("y") && has("z")) {
+                return Err(parse_error(FORMAT, "vertex element without x, y and z"));
+            }
+        }
+
+        // Instances of an element without properties occupy no input
+        if element.properties.is_empty() {
+            continue;
+        }
+        for _ in 0..element.count {
+            let mut p = [0.0f32; 3];
+            let mut n = [0.0f32; 3];
+            let mut s = 0.0f32;
+            for property in &element.properties {
+                match property {
+                    PlyProperty::Scalar { name, ty } => {
+                        let v = values.read(*ty)? as f32;
+                        if is_vertex {
+                            match name.as_str() {
+                                "x" => p[0] = v,
+                                "y" => p[1] = v,
+                                "z" => p[2] = v,
+                                "nx" => n[0] = v,
+                                "ny" => n[1] = v,
+                                "nz" => n[2] = v,
+                                _ => {}
+                            }
+                            if *name == options.scalar_property {
+                                s = v;
+                            }
+                        }
+                    }
+                    PlyProperty::List { name, count, item } => {
+                        let len = values.read(*count)?;
+                        if !(0.0..=u32::MAX as f64).contains(&len) {
+                            return Err(parse_error(FORMAT, format!("invalid list length {}", len)));
+                        }
+                        let mut list = Vec::with_capacity((len as usize).min(64));
+                        for _ in 0..len as usize {
+                            list.push(values.read(*item)?);
+                        }
+                        if is_face && (name == "vertex_indices" || name == "vertex_index") {
+                            if let Some(&bad) = list.iter().find(|&&i| i < 0.0 || i >= vertex_count as f64) {
+                                return Err(MeshIoError::IndexOutOfRange {
+                                    format: FORMAT,
+                                    index: bad as i64,
+                                    vertex_count,
+                                });
+                            }
+                            faces.push(list.into_iter().map(|i| i as u32).collect());
+                        }
+                    }
+                }
+            }
+            if is_vertex {
+                positions.extend_from_slice(&p);
+                if with_normals {
+                    normals.extend_from_slice(&n);
+                }
+                if with_scalars {
+                    scalars.push(s);
+                }
+            }
+        }
+    }
+
+    let mut indices = Vec::new();
+    for face in &faces {
+        push_face(FORMAT, face, vertex_count, options.faces, &mut indices)?;
+    }
+
+    let mut mesh = Mesh::new(positions, indices);
+    if !normals.is_empty() {
+        mesh = mesh.with_normals(normals);
+    }
+    if !scalars.is_empty() {
+        mesh = mesh.with_scalars(scalars);
+    }
+    Ok(mesh)
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Write a PLY file with float positions, optional normals and a `scalar` property.
+pub fn write_ply(mesh: &Mesh, encoding: MeshEncoding) -> Vec<u8> {
+    let vertex_count = mesh.vertex_count();
+    let normals = mesh.normals.as_ref().filter(|n| n.len() == mesh.positions.len());
+    let scalars = mesh.scalars.as_ref().filter(|s| s.len() == vertex_count);
+
+    let mut header = String::from("ply\n");
+    header.push_str(match encoding {
+        MeshEncoding::Ascii => "format ascii 1.0\n",
+        MeshEncoding::Binary => "format binary_little_endian 1.0\n",
+    });
+    header.push_str("comment Frustum\n");
+    header.push_str(&format!("element vertex {}\n", vertex_count));
+    header.push_str("property float x\nproperty float y\nproperty float z\n");
+    if normals.is_some() {
+        header.push_str("property float nx\nproperty float ny\nproperty float nz\n");
+    }
+    if scalars.is_some() {
+        header.push_str("property float scalar\n");
+    }
+    header.push_str(&format!("element face {}\n", mesh.triangle_count()));
+    header.push_str("property list uchar uint vertex_indices\nend_header\n");
+
+    let mut out = header.into_bytes();
+    for v in 0..vertex_count {
+        let mut row: Vec<f32> = mesh.positions[v * 3..v * 3 + 3].to_vec();
+        if let Some(normals) = normals {
+            row.extend_from_slice(&normals[v * 3..v * 3 + 3]);
+        }
+        if let Some(scalars) = scalars {
+            row.push(scalars[v]);
+        }
+        match encoding {
+            MeshEncoding::Ascii => {
+                let line: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+                out.extend_from_slice(line.join(" ").as_bytes());
+                out.push(b'\n');
+            }
+            MeshEncoding::Binary => row.iter().for_each(|x| out.extend_from_slice(&x.to_le_bytes())),
+        }
+    }
+    for t in mesh.indices.chunks_exact(3) {
+        match encoding {
+            MeshEncoding::Ascii => out.extend_from_slice(format!("3 {} {} {}\n", t[0], t[1], t[2]).as_bytes()),
+            MeshEncoding::Binary => {
+                out.push(3);
+                t.iter().for_each(|i| out.extend_from_slice(&i.to_le_bytes()));
+            }
+        }
+    }
+    out
+}
+
+// ============================================================================
+// OBJ
+// ============================================================================
+
+/// Parse a Wavefront OBJ file.
+///
+/// Reads `v` positions (extra colour components are ignored), `vn` normals
+/// and `f` faces, including negative (relative) indices. Vertices keep the
+/// order of the `v` lines. When corners pair a position with different
+/// normals, the position is duplicated (in order of first use) so that every
+/// vertex has a single normal. Texture coordinates, groups and materials are
+/// ignored.
+pub fn parse_obj(text: &str, options: &MeshReadOptions) -> Result<Mesh, MeshIoError> {
+    const FORMAT: &str = "OBJ";
+    let mut positions: Vec<f32> = Vec::new();
+    let mut obj_normals: Vec<[f32; 3]> = Vec::new();
+    let mut faces: Vec<Vec<(u32, Option<u32>)>> = Vec::new();
+
+    // Resolve a 1-based or negative index against `count` items
+    let resolve = |token: &str, count: usize| -> Result<u32, MeshIoError> {
+        let i: i64 = parse_number(FORMAT, Some(token))?;
+        let index = if i < 0 { count as i64 + i } else { i - 1 };
+        if i == 0 || index < 0 || index >= count as i64 {
+            return Err(MeshIoError::IndexOutOfRange {
+                format: FORMAT,
+                index: i,
+                vertex_count: count,
+            });
+        }
+        Ok(index as u32)
+    };
+
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => {
+                for _ in 0..3 {
+                    positions.push(parse_number(FORMAT, words.next())?);
+                }
+            }
+            Some("vn") => {
+                let mut n = [0.0f32; 3];
+                for c in n.iter_mut() {
+                    *c = parse_number(FORMAT, words.next())?;
+                }
+                obj_normals.push(n);
+            }
+            Some("f") => {
+                let mut face = Vec::new();
+                for corner in words {
+                    let mut parts = corner.split('/');
+                    let v = resolve(parts.next().unwrap_or(""), positions.len() / 3)?;
+                    let n = match parts.nth(1) {
+                        Some(token) if !token.is_empty() => Some(resolve(token, obj_normals.len())?),
+                        _ => None,
+                    };
+                    face.push((v, n));
+                }
+                faces.push(face);
+            }
+            _ => {}
+        }
+    }
+
+    // Assign one normal per output vertex, splitting positions used with several normals
+    let has_normals = faces.iter().flatten().any(|&(_, n)| n.is_some());
+    let mut vertex_normal: Vec<Option<u32>> = vec![None; positions.len() / 3];
+    let mut splits: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut indices = Vec::new();
+    let mut face_indices = Vec::new();
+    for face in &faces {
+        face_indices.clear();
+        for &(v, n) in face {
+            let index = match n {
+                Some(n) if has_normals => match vertex_normal[v as usize] {
+                    None => {
+                        vertex_normal[v as usize] = Some(n);
+                        v
+                    }
+                    Some(existing) if existing == n => v,
+                    Some(_) => *splits.entry((v, n)).or_insert_with(|| {
+                        let copy = vertex_normal.len() as u32;
+                        positions.extend_from_within(v as usize * 3..v as usize * 3 + 3);
+                        vertex_normal.push(Some(n));
+                        copy
+                    }),
+                },
+                _ => v,
+            };
+            face_indices.push(index);
+        }
+        push_face(FORMAT, &face_indices, vertex_normal.len(), options.faces, &mut indices)?;
+    }
+
+    let mut mesh = Mesh::new(positions, indices);
+    if has_normals {
+        let normals = vertex_normal
+            .iter()
+            .flat_map(|n| n.map(|n| obj_normals[n as usize]).unwrap_or([0.0; 3]))
+            .collect();
+        mesh = mesh.with_normals(normals);
+    }
+    Ok(mesh)
+}
+
+/// Write a Wavefront OBJ file with positions, optional normals and triangles.
+///
+/// OBJ has no per-vertex scalars; they are not written.
+pub fn write_obj(mesh: &Mesh) -> String {
+    let mut out = String::from("# Frustum\n");
+    for p in mesh.positions.chunks_exact(3) {
+        out.push_str(&format!("v {} {} {}\n", p[0], p[1], p[2]));
+    }
+    let normals = mesh.normals.as_ref().filter(|n| n.len() == mesh.positions.len());
+    if let Some(normals) = normals {
+        for n in normals.chunks_exact(3) {
+            out.push_str(&format!("vn {} {} {}\n", n[0], n[1], n[2]));
+        }
+    }
+    for t in mesh.indices.chunks_exact(3) {
+        let (a, b, c) = (t[0] + 1, t[1] + 1, t[2] + 1);
+        if normals.is_some() {
+            out.push_str(&format!("f {a}//{a} {b}//{b} {c}//{c}\n"));
+        } else {
+            out.push_str(&format!("f {a} {b} {c}\n"));
+        }
+    }
+    out
+}
+
+// ============================================================================
+// STL
+// ============================================================================
+
+/// Parse an ASCII or binary STL file.
+///
+/// STL stores unconnected triangles; vertices with bit-identical positions
+/// are welded, in order of first appearance, so the result is an indexed
+/// mesh. Facet normals are not kept (they are per face, not per vertex).
+pub fn parse_stl(bytes: &[u8]) -> Result<Mesh, MeshIoError> {
+    const FORMAT: &str = "STL";
+    let mut corners: Vec<[f32; 3]> = Vec::new();
+
+    // Binary files can also start with "solid", so check the size first
+    let binary_count = bytes
+        .get(80..84)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize);
+    let is_binary = binary_count.is_some_and(|n| n.checked_mul(50).and_then(|s| s.checked_add(84)) == Some(bytes.len()));
+
+    if is_binary {
+        for facet in bytes[84..].chunks_exact(50) {
+            for k in 0..3 {
+                let mut p = [0.0f32; 3];
+                for (c, value) in p.iter_mut().enumerate() {
+                    let at = 12 + k * 12 + c * 4;
+                    *value = f32::from_le_bytes(facet[at..at + 4].try_into().unwrap());
+                }
+                corners.push(p);
+            }
+        }
+    } else {
+        let text = text(FORMAT, bytes)?;
+        if !text.trim_start().starts_with("solid") {
+            return Err(parse_error(FORMAT, "neither binary STL nor ASCII 'solid'"));
+        }
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            if words.next() == Some("vertex") {
+                let mut p = [0.0f32; 3];
+                for c in p.iter_mut() {
+                    *c = parse_number(FORMAT, words.next())?;
+                }
+                corners.push(p);
+            }
+        }
+        if !corners.len().is_multiple_of(3) {
+            return Err(parse_error(FORMAT, "vertex count is not a multiple of three"));
+        }
+    }
+
+    let mut welded: HashMap<[u32; 3], u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut indices = Vec::with_capacity(corners.len());
+    for p in corners {
+        let key = p.map(f32::to_bits);
+        let index = *welded.entry(key).or_insert_with(|| {
+            positions.extend_from_slice(&p);
+            (positions.len() / 3 - 1) as u32
+        });
+        indices.push(index);
+    }
+    Ok(Mesh::new(positions, indices))
+}
+
+/// Write an STL file; facet normals are computed from the triangle winding.
+pub fn write_stl(mesh: &Mesh, encoding: MeshEncoding) -> Vec<u8> {
+    let corner = |v: u32| &mesh.positions[v as usize * 3..v as usize * 3 + 3];
+    match encoding {
+        MeshEncoding::Ascii => {
+            let mut out = String::from("solid frustum\n");
+            for t in mesh.indices.chunks_exact(3) {
+                let n = face_normal(&mesh.positions, t);
+                out.push_str(&format!("  facet normal {} {} {}\n    outer loop\n", n[0], n[1], n[2]));
+                for &v in t {
+                    let p = corner(v);
+                    out.push_str(&format!("      vertex {} {} {}\n", p[0], p[1], p[2]));
+                }
+                out.push_str("    endloop\n  endfacet\n");
+            }
+            out.push_str("endsolid frustum\n");
+            out.into_bytes()
+        }
+        MeshEncoding::Binary => {
+            let mut out = vec![0u8; 80];
+            out[..7].copy_from_slice(b"Frustum");
+            out.extend_from_slice(&(mesh.triangle_count() as u32).to_le_bytes());
+            for t in mesh.indices.chunks_exact(3) {
+                let n = face_normal(&mesh.positions, t);
+                n.iter().for_each(|x| out.extend_from_slice(&x.to_le_bytes()));
+                for &v in t {
+                    corner(v).iter().for_each(|x| out.extend_from_slice(&x.to_le_bytes()));
+                }
+                out.extend_from_slice(&[0, 0]);
+            }
+            out
+        }
+    }
+}
+
+// ============================================================================
+// OFF
+// ============================================================================
+
+/// Parse an OFF file (also `NOFF` with per-vertex normals and `COFF`, whose
+/// colours are ignored). `#` starts a comment.
+pub fn parse_off(text: &str, options: &MeshReadOptions) -> Result<Mesh, MeshIoError> {
+    const FORMAT: &str = "OFF";
+    let mut tokens = text.lines().flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace());
+
+    let magic = tokens.next().ok_or_else(|| parse_error(FORMAT, "empty file"))?;
+    let (with_normals, with_colours) = match magic {
+        "OFF" => (false, false),
+        "NOFF" => (true, false),
+        "COFF" => (false, true),
+        "CNOFF" => (true, true),
+        other => return Err(parse_error(FORMAT, format!("unknown header {:?}", other))),
+    };
+    let vertex_count: usize = parse_number(FORMAT, tokens.next())?;
+    let face_count: usize = parse_number(FORMAT, tokens.next())?;
+    let _edge_count: usize = parse_number(FORMAT, tokens.next())?;
+
+    let mut positions = Vec::with_capacity(vertex_count.min(1 << 20) * 3);
+    let mut normals = Vec::new();
+    for _ in 0..vertex_count {
+        for _ in 0..3 {
+            positions.push(parse_number(FORMAT, tokens.next())?);
+        }
+        if with_normals {
+            for _ in 0..3 {
+                normals.push(parse_number(FORMAT, tokens.next())?);
+            }
+        }
+        if with_colours {
+            // RGBA per vertex
+            for _ in 0..4 {
+                parse_number::<f32>(FORMAT, tokens.next())?;
+            }
+        }
+    }
+
+    let mut indices = Vec::new();
+    let mut face = Vec::new();
+    for _ in 0..face_count {
+        let n: usize = parse_number(FORMAT, tokens.next())?;
+        face.clear();
+        for _ in 0..n {
+            let index: i64 = parse_number(FORMAT, tokens.next())?;
+            if index < 0 || index as usize >= vertex_count {
+                return Err(MeshIoError::IndexOutOfRange {
+                    format: FORMAT,
+                    index,
+                    vertex_count,
+                });
+            }
+            face.push(index as u32);
+        }
+        push_face(FORMAT, &face, vertex_count, options.faces, &mut indices)?;
+        // Optional face colours run to the end of the line; OFF counts make
+        // them ambiguous in a token stream, so they are not supported
+    }
+
+    let mut mesh = Mesh::new(positions, indices);
+    if with_normals {
+        mesh = mesh.with_normals(normals);
+    }
+    Ok(mesh)
+}
+
+/// Write an OFF file (`NOFF` when the mesh has normals).
+pub fn write_off(mesh: &Mesh) -> String {
+    let normals = mesh.normals.as_ref().filter(|n| n.len() == mesh.positions.len());
+    let mut out = String::from(if normals.is_some() { "NOFF\n" } else { "OFF\n" });
+    out.push_str(&format!("{} {} 0\n", mesh.vertex_count(), mesh.triangle_count()));
+    for v in 0..mesh.vertex_count() {
+        let p = &mesh.positions[v * 3..v * 3 + 3];
+        out.push_str(&format!("{} {} {}", p[0], p[1], p[2]));
+        if let Some(normals) = normals {
+            let n = &normals[v * 3..v * 3 + 3];
+            out.push_str(&format!(" {} {} {}", n[0], n[1], n[2]));
+        }
+        out.push('\n');
+    }
+    for t in mesh.indices.chunks_exact(3) {
+        out.push_str(&format!("3 {} {} {}\n", t[0], t[1], t[2]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tetrahedron with normals and scalars.
+    fn tetrahedron() -> Mesh {
+        #[rustfmt::skip]
+        let positions = vec![
+            0.0, 0.0, 0.0,  1.0, 0.0, 0.0,  0.0, 1.0, 0.0,  0.0, 0.0, 1.5,
+        ];
+        let indices = vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+        let normals = vec![-0.577, -0.577, -0.577, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        Mesh::new(positions, indices)
+            .with_normals(normals)
+            .with_scalars(vec![0.25, -1.0, 2.5, 1e-3])
+    }
+
+    fn assert_same(a: &Mesh, b: &Mesh) {
+        assert_eq!(a.positions, b.positions);
+        assert_eq!(a.indices, b.indices);
+        assert_eq!(a.normals, b.normals);
+        assert_eq!(a.scalars, b.scalars);
+    }
+
+    #[test]
+    fn test_ply_round_trip() {
+        let mesh = tetrahedron();
+        let options = MeshReadOptions::new();
+        for encoding in [MeshEncoding::Ascii, MeshEncoding::Binary] {
+            let parsed = parse_ply(&write_ply(&mesh, encoding), &options).unwrap();
+            assert_same(&parsed, &mesh);
+        }
+    }
+
+    #[test]
+    fn test_ply_big_endian_quads_and_custom_scalar() {
+        let mut bytes = b"ply\nformat binary_big_endian 1.0\ncomment square\n\
+            element vertex 4\nproperty double x\nproperty double y\nproperty double z\nproperty uchar quality\n\
+            element face 1\nproperty list uchar int vertex_index\nelement edge 0\nproperty int vertex1\nend_header\n"
+            .to_vec();
+        for (k, p) in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].iter().enumerate() {
+            for c in [p[0], p[1], 0.0f64] {
+                bytes.extend_from_slice(&c.to_be_bytes());
+            }
+            bytes.push(10 * k as u8);
+        }
+        bytes.push(4);
+        for i in [0i32, 1, 2, 3] {
+            bytes.extend_from_slice(&i.to_be_bytes());
+        }
+
+        let options = MeshReadOptions::new().with_scalar_property("quality");
+        let mesh = parse_ply(&bytes, &options).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.scalars, Some(vec![0.0, 10.0, 20.0, 30.0]));
+        assert!(mesh.normals.is_none());
+
+        let rejected = parse_ply(&bytes, &options.with_faces(FacePolicy::Reject));
+        assert!(matches!(rejected, Err(MeshIoError::NonTriangleFace { vertices: 4, .. })));
+        assert!(parse_ply(&bytes[..bytes.len() - 3], &MeshReadOptions::new()).is_err());
+    }
+
+    #[test]
+    fn test_ply_huge_element_without_properties() {
+        let text = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                    element junk 18446744073709551615\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n\
+                    0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+        let mesh = parse_ply(text.as_bytes(), &MeshReadOptions::new()).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_obj_round_trip_and_features() {
+        let mesh = tetrahedron();
+        let parsed = parse_obj(&write_obj(&mesh), &MeshReadOptions::new()).unwrap();
+        assert_eq!(parsed.positions, mesh.positions);
+        assert_eq!(parsed.indices, mesh.indices);
+        assert_eq!(parsed.normals, mesh.normals);
+
+        // Quad with relative indices and a position shared with two normals
+        let text = "# quad\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nvn 0 0 -1\n\
+                    f -4//1 -3//1 -2//1 -1//1\nf 1//2 3//2 2//2\n";
+        let parsed = parse_obj(text, &MeshReadOptions::new()).unwrap();
+        assert_eq!(parsed.indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6]);
+        assert_eq!(parsed.vertex_count(), 7);
+        assert_eq!(&parsed.positions[12..15], &[0.0, 0.0, 0.0]);
+        assert_eq!(&parsed.normals.unwrap()[12..15], &[0.0, 0.0, -1.0]);
+
+        let bad = parse_obj("v 0 0 0\nf 1 2 3\n", &MeshReadOptions::new());
+        assert!(matches!(bad, Err(MeshIoError::IndexOutOfRange { index: 2, .. })));
+    }
+
+    #[test]
+    fn test_stl_round_trip_welds_vertices() {
+        let mesh = tetrahedron();
+        for encoding in [MeshEncoding::Ascii, MeshEncoding::Binary] {
+            let parsed = parse_stl(&write_stl(&mesh, encoding)).unwrap();
+            // Vertices come back welded in order of first use
+            assert_eq!(parsed.vertex_count(), 4);
+            assert_eq!(parsed.triangle_count(), 4);
+            for (t, u) in parsed.indices.chunks(3).zip(mesh.indices.chunks(3)) {
+                for (&a, &b) in t.iter().zip(u) {
+                    assert_eq!(parsed.positions[a as usize * 3..a as usize * 3 + 3], mesh.positions[b as usize * 3..b as usize * 3 + 3]);
+                }
+            }
+        }
+        assert!(parse_stl(b"not a mesh").is_err());
+    }
+
+    #[test]
+    fn test_off_round_trip_and_comments() {
+        let mesh = tetrahedron();
+        let parsed = parse_off(&write_off(&mesh), &MeshReadOptions::new()).unwrap();
+        assert_eq!(parsed.positions, mesh.positions);
+        assert_eq!(parsed.indices, mesh.indices);
+        assert_eq!(parsed.normals, mesh.normals);
+
+        let text = "OFF # square\n4 1 4\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n# face\n4 0 1 2 3\n";
+        let parsed = parse_off(text, &MeshReadOptions::new()).unwrap();
+        assert_eq!(parsed.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert!(parse_off("OFF\n1 1 0\n0 0 0\n3 0 1 2\n", &MeshReadOptions::new()).is_err());
+    }
+
+    #[test]
+    fn test_read_write_by_extension() {
+        let dir = std::env::temp_dir().join(format!("frustum_mesh_io_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mesh = tetrahedron();
+        for name in ["m.ply", "m.obj", "m.STL", "m.off"] {
+            let path = dir.join(name);
+            write_mesh(&mesh, &path).unwrap();
+            let parsed = read_mesh(&path, &MeshReadOptions::new()).unwrap();
+            assert_eq!(parsed.triangle_count(), 4, "{}", name);
+        }
+        assert!(matches!(write_mesh(&mesh, dir.join("m.xyz")), Err(MeshIoError::UnknownFormat(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+//! Scene schema versions and upgrades of older scene JSON.
+//!
+//! Scenes carry a namespaced `version` string, `frustum/scene/v<N>`. Loading
+//! upgrades the raw JSON one version at a time until it matches
+//! `SCHEMA_VERSION`, then deserializes it. Versions newer than this crate
+//! understands are rejected rather than guessed at.
+//!
+//! Version 0 is scene JSON written before the field existed; it has no
+//! `version` key.
+//!
+//! To change the schema: bump `SCHEMA_VERSION`, and append to `MIGRATIONS` a
+//! function that rewrites the previous version's JSON into the new shape.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::scene::SceneError;
+
+/// Schema version written by this crate.
+pub const SCHEMA_VERSION: &str = "frustum/scene/v1";
+
+const VERSION_PREFIX: &str = "frustum/scene/v";
+
+/// `MIGRATIONS[n]` upgrades a version `n` scene to version `n + 1`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v0_to_v1];
+
+/// Numeric part of a schema version string, e.g. 1 for `frustum/scene/v1`.
+pub fn schema_version_number(version: &str) -> Option<u32> {
+    let digits = version.strip_prefix(VERSION_PREFIX)?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Whether scene JSON is already at `SCHEMA_VERSION`, reading only its `version` field.
+pub(crate) fn is_current(json: &str) -> Result<bool, SceneError> {
+    #[derive(Deserialize)]
+    struct Version {
+        version: Option<Value>,
+    }
+    let Version { version } = serde_json::from_str(json)?;
+    Ok(matches!(version, Some(Value::String(v)) if v == SCHEMA_VERSION))
+}
+
+/// Upgrade raw scene JSON to the current schema version.
+///
+/// Non-object values are returned unchanged for deserialization to reject.
+pub fn migrate_scene(mut value: Value) -> Result<Value, SceneError> {
+    let current = MIGRATIONS.len() as u32;
+    let Some(scene) = value.as_object_mut() else {
+        return Ok(value);
+    };
+
+    let mut version = match scene.get("version") {
+        None => 0,
+        Some(Value::String(s)) => {
+            schema_version_number(s).ok_or_else(|| SceneError::InvalidVersion(s.clone()))?
+        }
+        Some(other) => return Err(SceneError::InvalidVersion(other.to_string())),
+    };
+    if version > current {
+        return Err(SceneError::UnsupportedVersion {
+            found: format!("{VERSION_PREFIX}{version}"),
+            supported: SCHEMA_VERSION,
+        });
+    }
+
+    while version < current {
+        MIGRATIONS[version as usize](scene);
+        version += 1;
+        scene.insert("version".to_string(), Value::String(format!("{VERSION_PREFIX}{version}")));
+    }
+    Ok(value)
+}
+
+/// v1 introduced the `version` field; the rest of the format is unchanged.
+fn v0_to_v1(_scene: &mut Map<String, Value>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_version_numbers() {
+        assert_eq!(schema_version_number(SCHEMA_VERSION), Some(MIGRATIONS.len() as u32));
+        assert_eq!(schema_version_number("frustum/scene/v12"), Some(12));
+        assert_eq!(schema_version_number("frustum/scene/v"), None);
+        assert_eq!(schema_version_number("frustum/scene/v1.1"), None);
+        assert_eq!(schema_version_number("other/scene/v1"), None);
+    }
+
+    #[test]
+    fn test_unversioned_scene_is_upgraded() {
+        let migrated = migrate_scene(json!({ "elements": [] })).unwrap();
+        assert_eq!(migrated["version"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_future_and_malformed_versions_rejected() {
+        let err = migrate_scene(json!({ "version": "frustum/scene/v99" })).unwrap_err();
+        assert!(matches!(err, SceneError::UnsupportedVersion { ref found, .. } if found == "frustum/scene/v99"));
+        assert!(err.to_string().contains(SCHEMA_VERSION));
+
+        let err = migrate_scene(json!({ "version": "scene-1" })).unwrap_err();
+        assert!(matches!(err, SceneError::InvalidVersion(_)));
+        let err = migrate_scene(json!({ "version": 1 })).unwrap_err();
+        assert!(matches!(err, SceneError::InvalidVersion(_)));
+    }
+
+    #[test]
+    fn test_scene_from_json_migrates() {
+        use crate::scene::{test_support::empty_scene, Scene};
+
+        let scene = empty_scene();
+        let json = scene.to_json().unwrap();
+        assert!(is_current(&json).unwrap());
+        let mut legacy: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(legacy["version"], SCHEMA_VERSION);
+        legacy.as_object_mut().unwrap().remove("version");
+        assert!(!is_current(&legacy.to_string()).unwrap());
+
+        let loaded = Scene::from_json(&legacy.to_string()).unwrap();
+        assert_eq!(loaded.version, SCHEMA_VERSION);
+
+        legacy["version"] = json!("frustum/scene/v2");
+        assert!(matches!(
+            Scene::from_json(&legacy.to_string()),
+            Err(SceneError::UnsupportedVersion { .. })
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! Vertex normal generation for triangle meshes.
+//!
+//! Smooth normals are weighted sums of the adjacent face normals. An optional
+//! crease angle keeps sharp edges sharp by splitting vertices whose faces
+//! meet at a steeper angle. Generation is deterministic: output vertices
+//! follow the input order, with split copies appended in face order.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Mesh;
+
+/// Weighting of face normals when averaging them at a vertex.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalWeighting {
+    /// By the face's corner angle at the vertex; independent of tessellation.
+    #[default]
+    Angle,
+    /// By face area; large faces dominate.
+    Area,
+}
+
+/// Options for [`Mesh::compute_normals`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NormalOptions {
+    /// Weighting of adjacent face normals.
+    #[serde(default)]
+    pub weighting: NormalWeighting,
+    /// Crease angle in degrees. Faces around a vertex whose normals differ by
+    /// more than this get separate vertices, keeping the edge between them
+    /// sharp. `None` smooths across every edge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crease_angle: Option<f32>,
+}
+
+impl NormalOptions {
+    /// Angle-weighted normals without creases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the face weighting.
+    pub fn with_weighting(mut self, weighting: NormalWeighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Split vertices where adjacent faces meet at more than `degrees`.
+    pub fn with_crease_angle(mut self, degrees: f32) -> Self {
+        self.crease_angle = Some(degrees);
+        self
+    }
+}
+
+impl Mesh {
+    /// Compute smooth per-vertex normals, replacing any existing ones.
+    ///
+    /// Normals follow the triangle winding (counter-clockwise faces point
+    /// towards the viewer). Without a crease angle the topology is unchanged.
+    /// With one, vertices shared by faces on both sides of a crease are
+    /// duplicated (scalars are copied along), so the vertex count can grow.
+    /// Vertices not used by any non-degenerate face get a zero normal.
+    pub fn compute_normals(&self, options: &NormalOptions) -> Mesh {
+        let faces = face_normals(self);
+        let Some(crease_angle) = options.crease_angle else {
+            let normals = vertex_normals_from_faces(self, &faces, options.weighting);
+            return self.clone().with_normals(normals);
+        };
+        let cos_crease = crease_angle.to_radians().cos();
+
+        // Faces incident to each vertex, in face order
+        let mut incident: Vec<Vec<usize>> = vec![Vec::new(); self.vertex_count()];
+        for (f, t) in self.indices.chunks_exact(3).enumerate() {
+            for &v in t {
+                incident[v as usize].push(f);
+            }
+        }
+        let mut edge_faces: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (f, t) in self.indices.chunks_exact(3).enumerate() {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                edge_faces.entry((a.min(b), a.max(b))).or_default().push(f);
+            }
+        }
+
+        let mut positions = self.positions.clone();
+        let mut scalars = self.scalars.clone();
+        let mut normals = vec![0.0f32; self.positions.len()];
+        let mut indices = self.indices.clone();
+
+        for (v, list) in incident.iter().enumerate() {
+            // Group faces around the vertex that are connected through smooth edges
+            let mut group: Vec<usize> = (0..list.len()).collect();
+            for (a, &fa) in list.iter().enumerate() {
+                for &w in &self.indices[fa * 3..fa * 3 + 3] {
+                    if w as usize == v {
+                        continue;
+                    }
+                    let key = ((v as u32).min(w), (v as u32).max(w));
+                    for &fb in &edge_faces[&key] {
+                        let Some(b) = list.iter().position(|&f| f == fb) else {
+                            continue;
+                        };
+                        let (na, nb) = (faces[fa].normal, faces[fb].normal);
+                        let smooth = na == Vec3::ZERO || nb == Vec3::ZERO || na.dot(nb) >= cos_crease;
+                        if smooth {
+                            let (ra, rb) = (find(&mut group, a), find(&mut group, b));
+                            group[ra.max(rb)] = ra.min(rb);
+                        }
+                    }
+                }
+            }
+
+            // The group of the first face keeps the vertex; others get copies
+            let mut targets: Vec<(usize, u32, Vec3)> = Vec::new();
+            for (a, &f) in list.iter().enumerate() {
+                let root = find(&mut group, a);
+                let slot = match targets.iter().position(|&(r, _, _)| r == root) {
+                    Some(slot) => slot,
+                    None => {
+                        let index = if targets.is_empty() {
+                            v as u32
+                        } else {
+                            let copy = (positions.len() / 3) as u32;
+                            positions.extend_from_slice(&self.positions[v * 3..v * 3 + 3]);
+                            normals.extend_from_slice(&[0.0; 3]);
+                            if let Some(scalars) = scalars.as_mut() {
+                                scalars.push(scalars[v]);
+                            }
+                            copy
+                        };
+                        targets.push((root, index, Vec3::ZERO));
+                        targets.len() - 1
+                    }
+                };
+                let corner = self.indices[f * 3..f * 3 + 3].iter().position(|&w| w as usize == v).unwrap();
+                targets[slot].2 += faces[f].weight(corner, options.weighting);
+                indices[f * 3 + corner] = targets[slot].1;
+            }
+            for (_, index, sum) in targets {
+                let n = sum.normalize_or_zero();
+                normals[index as usize * 3..index as usize * 3 + 3].copy_from_slice(&n.to_array());
+            }
+        }
+
+        Mesh {
+            positions,
+            indices,
+            normals: Some(normals),
+            scalars,
+            material_id: self.material_id.clone(),
+            shading: self.shading,
+        }
+    }
+}
+
+/// Union-find root of `i`, with path halving.
+fn find(group: &mut [usize], mut i: usize) -> usize {
+    while group[i] != i {
+        group[i] = group[group[i]];
+        i = group[i];
+    }
+    i
+}
+
+/// Unit normal, area and corner angles of a triangle.
+struct FaceNormal {
+    normal: Vec3,
+    area: f32,
+    angles: [f32; 3],
+}
+
+impl FaceNormal {
+    fn weight(&self, corner: usize, weighting: NormalWeighting) -> Vec3 {
+        match weighting {
+            NormalWeighting::Angle => self.normal * self.angles[corner],
+            NormalWeighting::Area => self.normal * self.area,
+        }
+    }
+}
+
+fn face_normals(mesh: &Mesh) -> Vec<FaceNormal> {
+    let p = |v: u32| Vec3::from_slice(&mesh.positions[v as usize * 3..v as usize * 3 + 3]);
+    mesh.indices
+        .chunks_exact(3)
+        .map(|t| {
+            let corners = [p(t[0]), p(t[1]), p(t[2])];
+            let cross = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+            let angles = [0, 1, 2].map(|k| {
+                let u = corners[(k + 1) % 3] - corners[k];
+                let w = corners[(k + 2) % 3] - corners[k];
+                u.cross(w).length().atan2(u.dot(w))
+            });
+            FaceNormal {
+                normal: cross.normalize_or_zero(),
+                area: 0.5 * cross.length(),
+                angles,
+            }
+        })
+        .collect()
+}
+
+fn vertex_normals_from_faces(mesh: &Mesh, faces: &[FaceNormal], weighting: NormalWeighting) -> Vec<f32> {
+    let mut sums = vec![Vec3::ZERO; mesh.vertex_count()];
+    for (face, t) in faces.iter().zip(mesh.indices.chunks_exact(3)) {
+        for (corner, &v) in t.iter().enumerate() {
+            sums[v as usize] += face.weight(corner, weighting);
+        }
+    }
+    sums.into_iter().flat_map(|n| n.normalize_or_zero().to_array()).collect()
+}
+
+/// Smooth per-vertex normals without creases, as flattened [nx, ny, nz, ...].
+pub(crate) fn vertex_normals(mesh: &Mesh, weighting: NormalWeighting) -> Vec<f32> {
+    vertex_normals_from_faces(mesh, &face_normals(mesh), weighting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube with 8 shared vertices and outward-facing triangles.
+    fn cube() -> Mesh {
+        #[rustfmt::skip]
+        let positions = vec![
+            0.0, 0.0, 0.0,  1.0, 0.0, 0.0,  1.0, 1.0, 0.0,  0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,  1.0, 0.0, 1.0,  1.0, 1.0, 1.0,  0.0, 1.0, 1.0,
+        ];
+        #[rustfmt::skip]
+        let indices = vec![
+            0, 2, 1, 0, 3, 2,  // -z
+            4, 5, 6, 4, 6, 7,  // +z
+            0, 1, 5, 0, 5, 4,  // -y
+            3, 7, 6, 3, 6, 2,  // +y
+            0, 4, 7, 0, 7, 3,  // -x
+            1, 2, 6, 1, 6, 5,  // +x
+        ];
+        Mesh::new(positions, indices).with_scalars((0..8).map(|v| v as f32).collect())
+    }
+
+    #[test]
+    fn test_smooth_cube_normals_point_along_diagonals() {
+        let mesh = cube().compute_normals(&NormalOptions::new());
+        assert_eq!(mesh.vertex_count(), 8);
+        let normals = mesh.normals.unwrap();
+        let inv = 1.0 / 3f32.sqrt();
+        for v in 0..8 {
+            let p = Vec3::from_slice(&mesh.positions[v * 3..v * 3 + 3]) - Vec3::splat(0.5);
+            let n = Vec3::from_slice(&normals[v * 3..v * 3 + 3]);
+            assert!((n - p.signum() * inv).length() < 1e-5, "Vertex {} normal {:?}", v, n);
+        }
+    }
+
+    #[test]
+    fn test_crease_angle_splits_cube_corners() {
+        let mesh = cube().compute_normals(&NormalOptions::new().with_crease_angle(30.0));
+
+        // Every corner splits into one vertex per adjacent face
+        assert_eq!(mesh.vertex_count(), 24);
+        assert_eq!(mesh.triangle_count(), 12);
+        let normals = mesh.normals.as_ref().unwrap();
+        let scalars = mesh.scalars.as_ref().unwrap();
+        for t in mesh.indices.chunks_exact(3) {
+            let p = |v: u32| Vec3::from_slice(&mesh.positions[v as usize * 3..v as usize * 3 + 3]);
+            let face = (p(t[1]) - p(t[0])).cross(p(t[2]) - p(t[0])).normalize();
+            for &v in t {
+                let n = Vec3::from_slice(&normals[v as usize * 3..v as usize * 3 + 3]);
+                assert!((n - face).length() < 1e-5);
+            }
+        }
+        // Copies keep their source vertex's position and scalar
+        for (v, &scalar) in scalars.iter().enumerate().skip(8) {
+            let source = scalar as usize;
+            assert_eq!(mesh.positions[v * 3..v * 3 + 3], mesh.positions[source * 3..source * 3 + 3]);
+        }
+
+        // A crease angle above 90 degrees smooths the cube again
+        let smooth = cube().compute_normals(&NormalOptions::new().with_crease_angle(100.0));
+        assert_eq!(smooth.vertex_count(), 8);
+    }
+
+    #[test]
+    fn test_angle_and_area_weighting_differ() {
+        // Vertex 0 shared by a large face in z = 0 and a small, steep face
+        let mesh = Mesh::new(
+            vec![0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, -0.1, 0.1],
+            vec![0, 1, 2, 0, 3, 1],
+        );
+        let n = |weighting| {
+            let normals = mesh.compute_normals(&NormalOptions::new().with_weighting(weighting)).normals.unwrap();
+            Vec3::from_slice(&normals[0..3])
+        };
+        let (angle, area) = (n(NormalWeighting::Angle), n(NormalWeighting::Area));
+        assert!((angle.length() - 1.0).abs() < 1e-5 && (area.length() - 1.0).abs() < 1e-5);
+        // The large face dominates the area-weighted normal
+        assert!(area.z > angle.z);
+    }
+}
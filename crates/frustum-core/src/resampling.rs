@@ -0,0 +1,352 @@
+//! Region-of-interest utilities for volumes: cropping, downsampling,
+//! resampling and padding.
+//!
+//! Every operation keeps the grid anchored in world space: `origin`,
+//! `spacing` and (for rectilinear grids) `coordinates` are updated so that an
+//! isosurface of the result lands where the same surface of the input does.
+
+use crate::marching_cubes::Volume;
+
+impl Volume {
+    /// Crop to the grid index range `min..max` along each axis (half-open).
+    ///
+    /// The mask and rectilinear coordinates are cropped alongside the values.
+    ///
+    /// # Panics
+    /// Panics if a range is empty or extends past the volume.
+    pub fn crop(&self, min: [usize; 3], max: [usize; 3]) -> Volume {
+        for axis in 0..3 {
+            assert!(
+                min[axis] < max[axis] && max[axis] <= self.dimensions[axis],
+                "Crop range must be non-empty and inside the volume"
+            );
+        }
+        let count = [0, 1, 2].map(|axis| max[axis] - min[axis]);
+        self.subgrid(min, [1; 3], count)
+    }
+
+    /// Crop to the grid points inside the world-space box `[min, max]` (inclusive).
+    ///
+    /// Returns `None` if no grid point lies inside the box.
+    pub fn crop_world(&self, min: [f32; 3], max: [f32; 3]) -> Option<Volume> {
+        let mut lo = [0; 3];
+        let mut hi = [0; 3];
+        for axis in 0..3 {
+            let n = self.dimensions[axis];
+            let coordinates: Vec<f32> = (0..n).map(|i| self.axis_coordinate(axis, i)).collect();
+            lo[axis] = coordinates.partition_point(|&c| c < min[axis]);
+            hi[axis] = coordinates.partition_point(|&c| c <= max[axis]);
+            if lo[axis] >= hi[axis] {
+                return None;
+            }
+        }
+        Some(self.crop(lo, hi))
+    }
+
+    /// Keep every `stride`-th grid point along each axis, starting at the first.
+    ///
+    /// Spacing is multiplied by the stride and the origin is unchanged.
+    ///
+    /// # Panics
+    /// Panics if a stride is zero or the volume is empty.
+    pub fn downsample(&self, stride: [usize; 3]) -> Volume {
+        assert!(stride.iter().all(|&s| s > 0), "Downsample stride must be positive");
+        assert!(self.dimensions.iter().all(|&n| n > 0), "Downsample requires a non-empty volume");
+        let count = [0, 1, 2].map(|axis| (self.dimensions[axis] - 1) / stride[axis] + 1);
+        self.subgrid([0; 3], stride, count)
+    }
+
+    /// Average non-overlapping blocks of `block` voxels along each axis.
+    ///
+    /// Each output point sits at the centre of its block, so the origin moves
+    /// by half a block and spacing is multiplied by the block size. Trailing
+    /// voxels that do not fill a whole block are dropped (an axis shorter than
+    /// its block keeps one partial block). Invalid voxels are left out of the
+    /// mean; blocks without a valid voxel become NaN. The result has no mask.
+    ///
+    /// # Panics
+    /// Panics if a block size is zero or the volume is empty.
+    pub fn downsample_mean(&self, block: [usize; 3]) -> Volume {
+        assert!(block.iter().all(|&b| b > 0), "Downsample block size must be positive");
+        assert!(self.dimensions.iter().all(|&n| n > 0), "Downsample requires a non-empty volume");
+        let block = [0, 1, 2].map(|axis| block[axis].min(self.dimensions[axis]));
+        let dims = [0, 1, 2].map(|axis| self.dimensions[axis] / block[axis]);
+
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for bz in 0..dims[2] {
+            for by in 0..dims[1] {
+                for bx in 0..dims[0] {
+                    let (mut sum, mut count) = (0.0f64, 0usize);
+                    for z in bz * block[2]..(bz + 1) * block[2] {
+                        for y in by * block[1]..(by + 1) * block[1] {
+                            for x in bx * block[0]..(bx + 1) * block[0] {
+                                if self.is_valid(x, y, z) {
+                                    sum += self.get(x, y, z) as f64;
+                                    count += 1;
+                                }
+                            }
+                        }
+                    }
+                    values.push(if count > 0 { (sum / count as f64) as f32 } else { f32::NAN });
+                }
+            }
+        }
+
+        // Block centres: mean of the block's coordinates along each axis
+        let centres = [0, 1, 2].map(|axis| {
+            (0..dims[axis])
+                .map(|b| {
+                    let range = b * block[axis]..(b + 1) * block[axis];
+                    let sum: f32 = range.map(|i| self.axis_coordinate(axis, i)).sum();
+                    sum / block[axis] as f32
+                })
+                .collect::<Vec<f32>>()
+        });
+
+        if self.is_rectilinear() {
+            let [x, y, z] = centres;
+            Volume::rectilinear(values, x, y, z)
+        } else {
+            let spacing = [0, 1, 2].map(|axis| self.spacing[axis] * block[axis] as f32);
+            let origin = [0, 1, 2].map(|axis| centres[axis][0]);
+            Volume::new(values, dims, spacing, origin)
+        }
+    }
+
+    /// Trilinearly resample onto a regular grid with the given spacing.
+    ///
+    /// The new grid starts at the first grid point of the input and covers
+    /// its extent, so the origin is unchanged; the far edge is dropped if the
+    /// extent is not a multiple of the new spacing. Rectilinear inputs become
+    /// regular. Points whose interpolation touches an invalid voxel are NaN.
+    /// The result has no mask.
+    ///
+    /// # Panics
+    /// Panics if a spacing is not strictly positive and finite, or the volume is empty.
+    pub fn resample(&self, spacing: [f32; 3]) -> Volume {
+        assert!(
+            spacing.iter().all(|&s| s > 0.0 && s.is_finite()),
+            "Resample spacing must be positive and finite"
+        );
+        assert!(self.dimensions.iter().all(|&n| n > 0), "Resample requires a non-empty volume");
+        let origin = [0, 1, 2].map(|axis| self.axis_coordinate(axis, 0));
+        let dims = [0, 1, 2].map(|axis| {
+            let extent = self.axis_coordinate(axis, self.dimensions[axis] - 1) - origin[axis];
+            // Tolerate rounding when the extent is a multiple of the spacing
+            (extent / spacing[axis] + 1e-4).floor() as usize + 1
+        });
+
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    let p = [
+                        origin[0] + x as f32 * spacing[0],
+                        origin[1] + y as f32 * spacing[1],
+                        origin[2] + z as f32 * spacing[2],
+                    ];
+                    values.push(self.sample_world(p).unwrap_or(f32::NAN));
+                }
+            }
+        }
+
+        Volume::new(values, dims, spacing, origin)
+    }
+
+    /// Pad with `width` grid points of `value` on both sides of each axis.
+    ///
+    /// The origin moves out by `width * spacing`; rectilinear grids extend
+    /// their coordinates by repeating the first and last step. Padded voxels
+    /// are valid in the mask (unless `value` itself is non-finite).
+    pub fn pad(&self, width: [usize; 3], value: f32) -> Volume {
+        let [nx, ny, nz] = self.dimensions;
+        let dims = [0, 1, 2].map(|axis| self.dimensions[axis] + 2 * width[axis]);
+
+        let mut values = vec![value; dims[0] * dims[1] * dims[2]];
+        let mut mask = self.mask.as_ref().map(|_| vec![true; values.len()]);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let src = x + y * nx + z * nx * ny;
+                    let dst = (x + width[0]) + (y + width[1]) * dims[0] + (z + width[2]) * dims[0] * dims[1];
+                    values[dst] = self.values[src];
+                    if let (Some(mask), Some(source)) = (mask.as_mut(), self.mask.as_ref()) {
+                        mask[dst] = source[src];
+                    }
+                }
+            }
+        }
+
+        let mut padded = if let Some(coordinates) = self.coordinates.as_ref() {
+            let [x, y, z] = [0, 1, 2].map(|axis| {
+                let c = &coordinates[axis];
+                let n = c.len();
+                let (first, last) = if n > 1 { (c[1] - c[0], c[n - 1] - c[n - 2]) } else { (1.0, 1.0) };
+                let w = width[axis];
+                let mut extended: Vec<f32> = (0..w).map(|k| c[0] - (w - k) as f32 * first).collect();
+                extended.extend_from_slice(c);
+                extended.extend((1..=w).map(|k| c[n - 1] + k as f32 * last));
+                extended
+            });
+            Volume::rectilinear(values, x, y, z)
+        } else {
+            let origin = [0, 1, 2].map(|axis| self.origin[axis] - width[axis] as f32 * self.spacing[axis]);
+            Volume::new(values, dims, self.spacing, origin)
+        };
+        padded.mask = mask;
+        padded
+    }
+
+    /// The `count` grid points starting at `start` with step `stride` along each axis.
+    fn subgrid(&self, start: [usize; 3], stride: [usize; 3], count: [usize; 3]) -> Volume {
+        let [nx, ny, _] = self.dimensions;
+        let index = |axis: usize, k: usize| start[axis] + k * stride[axis];
+
+        let mut values = Vec::with_capacity(count[0] * count[1] * count[2]);
+        let mut mask = self.mask.as_ref().map(|_| Vec::with_capacity(values.capacity()));
+        for k in 0..count[2] {
+            for j in 0..count[1] {
+                for i in 0..count[0] {
+                    let src = index(0, i) + index(1, j) * nx + index(2, k) * nx * ny;
+                    values.push(self.values[src]);
+                    if let (Some(mask), Some(source)) = (mask.as_mut(), self.mask.as_ref()) {
+                        mask.push(source[src]);
+                    }
+                }
+            }
+        }
+
+        let mut volume = if let Some(coordinates) = self.coordinates.as_ref() {
+            let [x, y, z] = [0, 1, 2].map(|axis| (0..count[axis]).map(|k| coordinates[axis][index(axis, k)]).collect());
+            Volume::rectilinear(values, x, y, z)
+        } else {
+            let spacing = [0, 1, 2].map(|axis| self.spacing[axis] * stride[axis] as f32);
+            let origin = [0, 1, 2].map(|axis| self.axis_coordinate(axis, start[axis]));
+            Volume::new(values, count, spacing, origin)
+        };
+        volume.mask = mask;
+        volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marching_cubes::marching_cubes;
+    use crate::marching_cubes::test_support::sphere_volume;
+
+    /// Linear field f = x + 2y - z on a regular grid.
+    fn linear_volume(dims: [usize; 3], spacing: [f32; 3], origin: [f32; 3]) -> Volume {
+        let mut values = Vec::with_capacity(dims[0] * dims[1] * dims[2]);
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    let p = [
+                        origin[0] + x as f32 * spacing[0],
+                        origin[1] + y as f32 * spacing[1],
+                        origin[2] + z as f32 * spacing[2],
+                    ];
+                    values.push(p[0] + 2.0 * p[1] - p[2]);
+                }
+            }
+        }
+        Volume::new(values, dims, spacing, origin)
+    }
+
+    fn assert_linear(volume: &Volume) {
+        let [nx, ny, nz] = volume.dimensions;
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let p = volume.grid_to_world(x, y, z);
+                    let expected = p[0] + 2.0 * p[1] - p[2];
+                    assert!((volume.get(x, y, z) - expected).abs() < 1e-4, "Value at {:?} off", p);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crop_keeps_isosurface_in_place() {
+        let volume = sphere_volume([24; 3], 0.5);
+
+        // The sphere lies well inside both crops
+        let cropped = volume.crop([3, 3, 3], [21, 21, 21]);
+        assert_eq!(cropped.dimensions, [18, 18, 18]);
+        let world = volume.crop_world([-0.8, -0.8, -0.8], [0.8, 0.8, 0.8]).unwrap();
+
+        let full = marching_cubes(&volume, 0.0);
+        for mesh in [marching_cubes(&cropped, 0.0), marching_cubes(&world, 0.0)] {
+            assert_eq!(mesh.indices, full.indices);
+            for (a, b) in mesh.positions.iter().zip(&full.positions) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+
+        assert!(volume.crop_world([2.0; 3], [3.0; 3]).is_none());
+    }
+
+    #[test]
+    fn test_downsample_stride_and_mean() {
+        let volume = linear_volume([9, 7, 5], [0.5, 0.25, 1.0], [1.0, -1.0, 0.0]);
+
+        let strided = volume.downsample([2, 3, 1]);
+        assert_eq!(strided.dimensions, [5, 3, 5]);
+        assert_eq!(strided.spacing, [1.0, 0.75, 1.0]);
+        assert_eq!(strided.origin, volume.origin);
+        assert_linear(&strided);
+
+        // Means of a linear field equal the value at the block centre
+        let mean = volume.downsample_mean([2, 2, 2]);
+        assert_eq!(mean.dimensions, [4, 3, 2]);
+        assert_eq!(mean.spacing, [1.0, 0.5, 2.0]);
+        assert_eq!(mean.origin, [1.25, -0.875, 0.5]);
+        assert_linear(&mean);
+
+        // Invalid voxels are left out; fully invalid blocks become NaN
+        let masked = volume.clone().with_mask((0..volume.values.len()).map(|i| i % 9 >= 2).collect());
+        let mean = masked.downsample_mean([2, 1, 1]);
+        assert!(mean.get(0, 0, 0).is_nan());
+        assert!(mean.get(1, 0, 0).is_finite());
+    }
+
+    #[test]
+    #[should_panic(expected = "non-empty volume")]
+    fn test_downsample_mean_rejects_empty_volume() {
+        Volume::new(vec![], [0, 4, 4], [1.0; 3], [0.0; 3]).downsample_mean([2, 2, 2]);
+    }
+
+    #[test]
+    fn test_resample_and_pad() {
+        let volume = linear_volume([5, 5, 5], [0.5; 3], [0.0, 1.0, -1.0]);
+
+        let fine = volume.resample([0.25, 0.5, 0.4]);
+        assert_eq!(fine.dimensions, [9, 5, 6]);
+        assert_eq!(fine.origin, volume.origin);
+        assert_linear(&fine);
+
+        let padded = volume.clone().with_mask(vec![true; 125]).pad([1, 0, 2], -7.0);
+        assert_eq!(padded.dimensions, [7, 5, 9]);
+        assert_eq!(padded.origin, [-0.5, 1.0, -2.0]);
+        assert_eq!(padded.get(0, 0, 0), -7.0);
+        assert_eq!(padded.get(1, 0, 2), volume.get(0, 0, 0));
+        assert_eq!(padded.grid_to_world(1, 0, 2), volume.grid_to_world(0, 0, 0));
+        assert_eq!(padded.mask.as_ref().map(|m| m.len()), Some(padded.values.len()));
+    }
+
+    #[test]
+    fn test_rectilinear_crop_and_pad() {
+        let (xs, ys, zs) = (vec![0.0, 0.5, 2.0, 3.0], vec![0.0, 1.0, 1.5], vec![-1.0, 1.0]);
+        let values = (0..24).map(|i| i as f32).collect();
+        let volume = Volume::rectilinear(values, xs, ys, zs);
+
+        let cropped = volume.crop([1, 1, 0], [3, 3, 2]);
+        assert_eq!(cropped.coordinates.as_ref().unwrap()[0], vec![0.5, 2.0]);
+        assert_eq!(cropped.grid_to_world(0, 0, 0), volume.grid_to_world(1, 1, 0));
+        assert_eq!(cropped.get(1, 1, 1), volume.get(2, 2, 1));
+
+        let padded = volume.pad([1, 1, 1], 0.0);
+        assert_eq!(padded.coordinates.as_ref().unwrap()[0], vec![-0.5, 0.0, 0.5, 2.0, 3.0, 4.0]);
+        assert_eq!(padded.grid_to_world(1, 1, 1), volume.grid_to_world(0, 0, 0));
+    }
+}
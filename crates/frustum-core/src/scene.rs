@@ -1,14 +1,30 @@
 //! Scene definition as an immutable container for geometry and camera.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::camera::Camera;
 use crate::geometry::{AxisBundle, Mesh, PointCloud, Polyline};
 use crate::lighting::Light;
 use crate::materials::Material;
+use crate::migration::{is_current, migrate_scene, SCHEMA_VERSION};
+
+/// Errors that can occur while loading a scene.
+#[derive(Error, Debug)]
+pub enum SceneError {
+    #[error("Invalid scene JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid scene schema version: {0}")]
+    InvalidVersion(String),
+    #[error("Scene schema version {found} is newer than the supported {supported}")]
+    UnsupportedVersion { found: String, supported: &'static str },
+    #[error("Invalid binary scene: {0}")]
+    InvalidBinary(String),
+}
 
 /// A scene element that can be rendered.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SceneElement {
     PointCloud(PointCloud),
@@ -18,15 +34,18 @@ pub enum SceneElement {
 }
 
 /// Axis-aligned bounding box.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub struct Bounds {
     pub min: [f32; 3],
     pub max: [f32; 3],
 }
 
 /// A complete scene with camera, geometry, and explicit bounds.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Scene {
+    /// Schema version identifier, e.g. `frustum/scene/v1`.
+    #[schemars(regex(pattern = r"^frustum/scene/v[0-9]+$"))]
+    pub version: String,
     /// Camera for viewing the scene.
     pub camera: Camera,
     /// All geometry elements in the scene.
@@ -46,6 +65,7 @@ impl Scene {
     /// Create a new scene with the given camera and bounds.
     pub fn new(camera: Camera, bounds: Bounds) -> Self {
         Self {
+            version: SCHEMA_VERSION.to_string(),
             camera,
             elements: Vec::new(),
             materials: Vec::new(),
@@ -101,7 +121,29 @@ impl Scene {
     }
 
     /// Deserialize a scene from JSON.
-    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
+    ///
+    /// Scenes at the current schema version are deserialized directly.
+    /// Older versions are upgraded first; newer versions are rejected with
+    /// `SceneError::UnsupportedVersion`.
+    pub fn from_json(json: &str) -> Result<Self, SceneError> {
+        if is_current(json)? {
+            return Ok(serde_json::from_str(json)?);
+        }
+        let value = migrate_scene(serde_json::from_str(json)?)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Fixtures shared by the tests of the scene modules.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// An empty scene: camera on +z looking at the origin, unit bounds.
+    pub(crate) fn empty_scene() -> Scene {
+        Scene::new(
+            Camera::perspective([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], 45.0),
+            Bounds { min: [-1.0; 3], max: [1.0; 3] },
+        )
     }
 }
@@ -0,0 +1,291 @@
+//! Binary scene container for large geometry.
+//!
+//! A JSON scene spends most of its size and parse time on geometry arrays
+//! written as decimal text. The binary form keeps everything except those
+//! arrays as JSON and stores the arrays as raw little-endian buffers:
+//!
+//! ```text
+//! offset  size  content
+//! 0       8     magic "FRUSTUMB"
+//! 8       4     container version (u32 LE, currently 1)
+//! 12      4     header length in bytes (u32 LE)
+//! 16      n     header JSON: { "scene": <scene>, "buffers": [<buffer>, ...] }
+//! ...           zero padding to an 8-byte boundary
+//! data          buffers, each starting on an 8-byte boundary
+//! ```
+//!
+//! In the header scene every mesh, point cloud and polyline array is empty;
+//! each `buffer` entry says which element and field it fills, its element
+//! type (`f32` or `u32`), and its byte offset from the start of the data
+//! section and element count. Values are stored bit for bit, so converting
+//! JSON → binary → JSON reproduces the original scene exactly.
+//!
+//! Loading copies each buffer once into its `Vec`. The header scene goes
+//! through the same schema migration as `Scene::from_json`, and the result
+//! can be checked with `Scene::validate`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{Mesh, PointCloud, Polyline};
+use crate::migration::migrate_scene;
+use crate::scene::{Scene, SceneElement, SceneError};
+
+/// First bytes of every binary scene file.
+pub const BINARY_MAGIC: &[u8; 8] = b"FRUSTUMB";
+
+const CONTAINER_VERSION: u32 = 1;
+const PREAMBLE_LEN: usize = 16;
+const ALIGN: usize = 8;
+
+/// Geometry array stored in a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Field {
+    Positions,
+    Indices,
+    Normals,
+    Scalars,
+}
+
+/// Element type of a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Dtype {
+    F32,
+    U32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BufferRef {
+    element: usize,
+    field: Field,
+    dtype: Dtype,
+    offset: usize,
+    count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    scene: serde_json::Value,
+    buffers: Vec<BufferRef>,
+}
+
+fn words(bytes: &[u8]) -> impl Iterator<Item = [u8; 4]> + '_ {
+    bytes.chunks_exact(4).map(|chunk| chunk.try_into().unwrap())
+}
+
+fn align(n: usize) -> usize {
+    n.div_ceil(ALIGN) * ALIGN
+}
+
+/// Collects buffers while the header scene is built.
+struct Writer {
+    buffers: Vec<BufferRef>,
+    data: Vec<u8>,
+}
+
+impl Writer {
+    fn push_bytes(&mut self, element: usize, field: Field, dtype: Dtype, count: usize, bytes: impl Iterator<Item = [u8; 4]>) {
+        self.data.resize(align(self.data.len()), 0);
+        let offset = self.data.len();
+        self.data.extend(bytes.flatten());
+        self.buffers.push(BufferRef { element, field, dtype, offset, count });
+    }
+
+    /// Move an `f32` array into a buffer, returning the empty placeholder.
+    fn f32s(&mut self, element: usize, field: Field, values: &[f32]) -> Vec<f32> {
+        self.push_bytes(element, field, Dtype::F32, values.len(), values.iter().map(|v| v.to_le_bytes()));
+        Vec::new()
+    }
+
+    fn u32s(&mut self, element: usize, field: Field, values: &[u32]) -> Vec<u32> {
+        self.push_bytes(element, field, Dtype::U32, values.len(), values.iter().map(|v| v.to_le_bytes()));
+        Vec::new()
+    }
+
+    fn optional(&mut self, element: usize, field: Field, values: &Option<Vec<f32>>) -> Option<Vec<f32>> {
+        values.as_ref().map(|v| self.f32s(element, field, v))
+    }
+}
+
+impl Scene {
+    /// Encode the scene in the binary container format.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut writer = Writer { buffers: Vec::new(), data: Vec::new() };
+
+        let elements = self
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| match element {
+                SceneElement::Mesh(m) => SceneElement::Mesh(Mesh {
+                    positions: writer.f32s(i, Field::Positions, &m.positions),
+                    indices: writer.u32s(i, Field::Indices, &m.indices),
+                    normals: writer.optional(i, Field::Normals, &m.normals),
+                    scalars: writer.optional(i, Field::Scalars, &m.scalars),
+                    material_id: m.material_id.clone(),
+                    shading: m.shading,
+                }),
+                SceneElement::PointCloud(pc) => SceneElement::PointCloud(PointCloud {
+                    positions: writer.f32s(i, Field::Positions, &pc.positions),
+                    scalars: writer.optional(i, Field::Scalars, &pc.scalars),
+                    point_size: pc.point_size,
+                    material_id: pc.material_id.clone(),
+                }),
+                SceneElement::Polyline(line) => SceneElement::Polyline(Polyline {
+                    positions: writer.f32s(i, Field::Positions, &line.positions),
+                    scalars: writer.optional(i, Field::Scalars, &line.scalars),
+                    line_width: line.line_width,
+                    material_id: line.material_id.clone(),
+                }),
+                SceneElement::Axes(axes) => SceneElement::Axes(axes.clone()),
+            })
+            .collect();
+
+        let header_scene = Scene {
+            version: self.version.clone(),
+            camera: self.camera.clone(),
+            elements,
+            materials: self.materials.clone(),
+            light: self.light,
+            bounds: self.bounds,
+        };
+        let header = Header {
+            scene: serde_json::to_value(&header_scene).expect("scene serializes to JSON"),
+            buffers: writer.buffers,
+        };
+        let header = serde_json::to_vec(&header).expect("header serializes to JSON");
+
+        let data_start = align(PREAMBLE_LEN + header.len());
+        let mut out = Vec::with_capacity(data_start + writer.data.len());
+        out.extend_from_slice(BINARY_MAGIC);
+        out.extend_from_slice(&CONTAINER_VERSION.to_le_bytes());
+        out.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        out.extend_from_slice(&header);
+        out.resize(data_start, 0);
+        out.extend_from_slice(&writer.data);
+        out
+    }
+
+    /// Decode a scene from the binary container format.
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, SceneError> {
+        let invalid = |message: String| SceneError::InvalidBinary(message);
+
+        if bytes.len() < PREAMBLE_LEN || &bytes[..8] != BINARY_MAGIC {
+            return Err(invalid("missing FRUSTUMB magic".to_string()));
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != CONTAINER_VERSION {
+            return Err(invalid(format!("unsupported container version {version}")));
+        }
+        let header_len = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let header_bytes = bytes
+            .get(PREAMBLE_LEN..PREAMBLE_LEN + header_len)
+            .ok_or_else(|| invalid("header extends past end of file".to_string()))?;
+        let header: Header = serde_json::from_slice(header_bytes)?;
+        let data = bytes.get(align(PREAMBLE_LEN + header_len)..).unwrap_or(&[]);
+
+        let mut scene: Scene = serde_json::from_value(migrate_scene(header.scene)?)?;
+
+        for buffer in &header.buffers {
+            let expected = if buffer.field == Field::Indices { Dtype::U32 } else { Dtype::F32 };
+            if buffer.dtype != expected {
+                return Err(invalid(format!("{:?} buffer must be {:?}", buffer.field, expected)));
+            }
+            let raw = buffer
+                .count
+                .checked_mul(4)
+                .and_then(|len| data.get(buffer.offset..buffer.offset.checked_add(len)?))
+                .ok_or_else(|| invalid(format!("buffer at offset {} extends past end of file", buffer.offset)))?;
+            let element = scene
+                .elements
+                .get_mut(buffer.element)
+                .ok_or_else(|| invalid(format!("buffer refers to missing element {}", buffer.element)))?;
+
+            let floats = || words(raw).map(f32::from_le_bytes).collect::<Vec<f32>>();
+            match (element, buffer.field) {
+                (SceneElement::Mesh(m), Field::Indices) => m.indices = words(raw).map(u32::from_le_bytes).collect(),
+                (SceneElement::Mesh(m), Field::Positions) => m.positions = floats(),
+                (SceneElement::Mesh(m), Field::Normals) => m.normals = Some(floats()),
+                (SceneElement::Mesh(m), Field::Scalars) => m.scalars = Some(floats()),
+                (SceneElement::PointCloud(pc), Field::Positions) => pc.positions = floats(),
+                (SceneElement::PointCloud(pc), Field::Scalars) => pc.scalars = Some(floats()),
+                (SceneElement::Polyline(line), Field::Positions) => line.positions = floats(),
+                (SceneElement::Polyline(line), Field::Scalars) => line.scalars = Some(floats()),
+                (_, field) => {
+                    return Err(invalid(format!("element {} has no {field:?} array", buffer.element)));
+                }
+            }
+        }
+        Ok(scene)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{AxisBounds, AxisBundle};
+    use crate::lighting::Light;
+    use crate::materials::{Material, ScalarMappedMaterial};
+    use crate::scene::test_support::empty_scene;
+
+    fn scene() -> Scene {
+        let mut mesh = Mesh::new(vec![0.1, 0.2, 0.3, 1.0, 0.0, 0.0, 0.0, 1.0, 1e-30], vec![0, 1, 2]);
+        mesh.normals = Some(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+        mesh.scalars = Some(vec![0.5, -2.25, 3.0e8]);
+        let mut points = PointCloud::new(vec![0.25, 0.5, 0.75], 3.0);
+        points.scalars = Some(vec![1.0 / 3.0]);
+        empty_scene()
+            .with_light(Light::new([0.0, 1.0, 1.0], 0.8))
+            .add_material(Material::ScalarMapped(ScalarMappedMaterial::new("m", "viridis", [0.0, 1.0])))
+            .add_mesh(mesh.with_material("m"))
+            .add_point_cloud(points)
+            .add_polyline(Polyline::new(vec![0.0, 0.0, 0.0, 0.5, 0.5, 0.5], 2.0))
+            .add_axes(AxisBundle::new("axes", AxisBounds { min: [-1.0; 3], max: [1.0; 3] }))
+    }
+
+    #[test]
+    fn test_round_trip_matches_json() {
+        let scene = scene();
+        let bytes = scene.to_binary();
+        assert_eq!(&bytes[..8], BINARY_MAGIC);
+
+        let header_len = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let header: Header = serde_json::from_slice(&bytes[16..16 + header_len]).unwrap();
+        assert_eq!(header.buffers.len(), 7);
+        let data_start = align(PREAMBLE_LEN + header_len);
+        assert!(header.buffers.iter().all(|b| (data_start + b.offset).is_multiple_of(ALIGN)));
+
+        let loaded = Scene::from_binary(&bytes).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), scene.to_json().unwrap());
+        assert_eq!(loaded.validate(), Ok(()));
+        assert_eq!(loaded.to_binary(), bytes, "encoding is deterministic");
+    }
+
+    #[test]
+    fn test_float_bits_preserved() {
+        let mut scene = scene();
+        if let SceneElement::Mesh(m) = &mut scene.elements[0] {
+            m.scalars = Some(vec![f32::NAN, -0.0, f32::MIN_POSITIVE]);
+        }
+        let loaded = Scene::from_binary(&scene.to_binary()).unwrap();
+        let SceneElement::Mesh(m) = &loaded.elements[0] else { panic!("expected mesh") };
+        let bits: Vec<u32> = m.scalars.as_ref().unwrap().iter().map(|v| v.to_bits()).collect();
+        assert_eq!(bits, [f32::NAN.to_bits(), (-0.0f32).to_bits(), f32::MIN_POSITIVE.to_bits()]);
+    }
+
+    #[test]
+    fn test_malformed_input_is_error() {
+        let bytes = scene().to_binary();
+        assert!(matches!(Scene::from_binary(b"{}"), Err(SceneError::InvalidBinary(_))));
+        assert!(matches!(Scene::from_binary(&bytes[..bytes.len() - 4]), Err(SceneError::InvalidBinary(_))));
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 9;
+        assert!(matches!(Scene::from_binary(&wrong_version), Err(SceneError::InvalidBinary(_))));
+
+        let mut garbled = bytes;
+        garbled[16] = b'!';
+        assert!(matches!(Scene::from_binary(&garbled), Err(SceneError::Json(_))));
+    }
+}
@@ -0,0 +1,104 @@
+//! JSON Schema for serialized scenes.
+//!
+//! The schema is derived from the `Scene` types, so it cannot disagree with
+//! what `Scene::to_json` writes. The copy committed at
+//! `schema/scene.schema.json` is checked against it by the tests; regenerate
+//! it with
+//!
+//! ```text
+//! FRUSTUM_UPDATE_SCHEMA=1 cargo test -p frustum-core schema
+//! ```
+//!
+//! The schema covers structure and types only. Cross-field rules such as
+//! index bounds and material references are checked by `Scene::validate`.
+
+use schemars::gen::SchemaSettings;
+use serde_json::Value;
+
+use crate::scene::Scene;
+
+/// Canonical URL of the committed scene schema.
+pub const SCHEMA_ID: &str = "https://github.com/gcol33/frustum/schema/scene.schema.json";
+
+/// The draft-07 JSON Schema describing serialized scenes.
+pub fn scene_json_schema() -> Value {
+    let generator = SchemaSettings::draft07().into_generator();
+    let mut schema = generator.into_root_schema_for::<Scene>();
+    let metadata = schema.schema.metadata();
+    metadata.id = Some(SCHEMA_ID.to_string());
+    metadata.title = Some("Frustum Scene".to_string());
+    metadata.description = Some("Canonical scene schema for Frustum 3D rendering".to_string());
+    serde_json::to_value(schema).expect("schema serializes to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn repo_path(relative: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..").join(relative)
+    }
+
+    fn schema_text() -> String {
+        serde_json::to_string_pretty(&scene_json_schema()).unwrap() + "\n"
+    }
+
+    #[test]
+    fn test_committed_schema_is_current() {
+        let path = repo_path("schema/scene.schema.json");
+        if std::env::var_os("FRUSTUM_UPDATE_SCHEMA").is_some() {
+            std::fs::write(&path, schema_text()).unwrap();
+        }
+        let committed = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            committed == schema_text(),
+            "schema/scene.schema.json is out of date; regenerate with \
+             FRUSTUM_UPDATE_SCHEMA=1 cargo test -p frustum-core schema"
+        );
+    }
+
+    #[test]
+    fn test_top_level_fields_covered() {
+        let schema = scene_json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for field in ["version", "camera", "elements", "materials", "light", "bounds"] {
+            assert!(properties.contains_key(field), "schema is missing '{field}'");
+        }
+    }
+
+    #[test]
+    fn test_example_scenes_match_schema() {
+        let schema = jsonschema::JSONSchema::compile(&scene_json_schema()).unwrap();
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(repo_path("schema/examples"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no example scenes found");
+
+        for path in paths {
+            let text = std::fs::read_to_string(&path).unwrap();
+            let instance: Value = serde_json::from_str(&text).unwrap();
+            if let Err(errors) = schema.validate(&instance) {
+                let messages: Vec<String> = errors.map(|e| format!("{}: {e}", e.instance_path)).collect();
+                panic!("{} does not match the schema:\n{}", path.display(), messages.join("\n"));
+            }
+
+            let scene = Scene::from_json(&text).unwrap();
+            assert_eq!(scene.validate(), Ok(()), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_schema_rejects_malformed_scene() {
+        let schema = jsonschema::JSONSchema::compile(&scene_json_schema()).unwrap();
+        let mut scene: Value = serde_json::from_str(
+            &std::fs::read_to_string(repo_path("schema/examples/triangle.json")).unwrap(),
+        )
+        .unwrap();
+        scene["camera"]["projection"] = Value::from("fisheye");
+        assert!(!schema.is_valid(&scene));
+    }
+}
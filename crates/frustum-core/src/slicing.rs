@@ -0,0 +1,455 @@
+//! Planar slices through volumes.
+//!
+//! A slice samples a `Volume` on a regular grid spanning the section of a
+//! plane with the volume's bounding box. The result is a flat `Mesh` whose
+//! per-vertex scalars are the trilinearly interpolated volume values, so it
+//! renders through a `ScalarMappedMaterial`, plus optional contour lines.
+//! Slicing is pure and deterministic, like isosurface extraction.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::contour::{contour_lines, ContourLine};
+use crate::geometry::Mesh;
+use crate::marching_cubes::Volume;
+
+/// Coordinate axis a slice plane is perpendicular to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SliceAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl SliceAxis {
+    fn index(self) -> usize {
+        match self {
+            SliceAxis::X => 0,
+            SliceAxis::Y => 1,
+            SliceAxis::Z => 2,
+        }
+    }
+}
+
+/// A plane cutting a volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlicePlane {
+    /// Plane perpendicular to a coordinate axis at a world-space position along it.
+    Axis { axis: SliceAxis, position: f32 },
+    /// Arbitrary plane through `point` with the given (not necessarily unit) normal.
+    Oblique { point: [f32; 3], normal: [f32; 3] },
+}
+
+impl SlicePlane {
+    /// Axis-aligned plane at `position` along `axis`.
+    pub fn axis(axis: SliceAxis, position: f32) -> Self {
+        SlicePlane::Axis { axis, position }
+    }
+
+    /// Plane through `point` with normal `normal`.
+    pub fn oblique(point: [f32; 3], normal: [f32; 3]) -> Self {
+        SlicePlane::Oblique { point, normal }
+    }
+
+    /// Point on the plane, unit normal, and in-plane axes (u, v) with u x v = normal.
+    ///
+    /// Axis-aligned planes use the cyclic axes: a z-slice spans (x, y), an
+    /// x-slice (y, z) and a y-slice (z, x).
+    fn frame(&self) -> (Vec3, Vec3, Vec3, Vec3) {
+        let (point, normal) = match *self {
+            SlicePlane::Axis { axis, position } => {
+                let mut point = Vec3::ZERO;
+                point[axis.index()] = position;
+                let mut normal = Vec3::ZERO;
+                normal[axis.index()] = 1.0;
+                (point, normal)
+            }
+            SlicePlane::Oblique { point, normal } => {
+                let normal = Vec3::from(normal);
+                assert!(
+                    normal.is_finite() && normal.length_squared() > 0.0,
+                    "Slice plane normal must be finite and non-zero"
+                );
+                (Vec3::from(point), normal.normalize())
+            }
+        };
+
+        // Helper axis following the dominant normal component, so that a plane
+        // close to axis-aligned gets the same orientation as the exact one
+        let a = normal.abs();
+        let dominant = if a.x >= a.y && a.x >= a.z {
+            0
+        } else if a.y >= a.z {
+            1
+        } else {
+            2
+        };
+        let mut helper = Vec3::ZERO;
+        helper[(dominant + 1) % 3] = 1.0;
+        let u = (helper - helper.dot(normal) * normal).normalize();
+        let v = normal.cross(u);
+        (point, normal, u, v)
+    }
+}
+
+/// Volume samples on a regular grid over a plane section.
+///
+/// Sample (i, j) lies at `origin + i * u + j * v`; `values` is stored
+/// i-fastest and holds NaN where the sample falls outside the volume or
+/// touches an invalid voxel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceGrid {
+    /// Number of samples along u and v.
+    pub resolution: [usize; 2],
+    /// World-space position of sample (0, 0).
+    pub origin: [f32; 3],
+    /// World-space offset between neighbouring samples along the first grid axis.
+    pub u: [f32; 3],
+    /// World-space offset between neighbouring samples along the second grid axis.
+    pub v: [f32; 3],
+    /// Unit plane normal; equal to the direction of u x v.
+    pub normal: [f32; 3],
+    /// Sampled scalar values.
+    pub values: Vec<f32>,
+}
+
+impl SliceGrid {
+    /// Sample `volume` on `plane` with `resolution[0] x resolution[1]` samples.
+    ///
+    /// The grid spans the bounding rectangle (in the plane's u, v axes) of the
+    /// plane's intersection with the volume bounds. Returns `None` if the
+    /// plane misses the volume.
+    ///
+    /// # Panics
+    /// Panics if either resolution is below 2, or an oblique normal is zero.
+    pub fn sample(volume: &Volume, plane: &SlicePlane, resolution: [usize; 2]) -> Option<Self> {
+        assert!(
+            resolution[0] >= 2 && resolution[1] >= 2,
+            "Slice resolution must be at least 2 x 2"
+        );
+        let (point, normal, u_axis, v_axis) = plane.frame();
+        let (min, max) = volume_bounds(volume);
+
+        // Plane crossings of the twelve bounding box edges, in plane coordinates
+        let corner = |c: usize| {
+            Vec3::new(
+                if c & 1 == 0 { min.x } else { max.x },
+                if c & 2 == 0 { min.y } else { max.y },
+                if c & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        let mut lo = [f32::INFINITY; 2];
+        let mut hi = [f32::NEG_INFINITY; 2];
+        let mut hits = 0;
+        for a in 0..8 {
+            for bit in [1, 2, 4] {
+                if a & bit != 0 {
+                    continue;
+                }
+                let (p0, p1) = (corner(a), corner(a | bit));
+                let (d0, d1) = ((p0 - point).dot(normal), (p1 - point).dot(normal));
+                let crossing = if d0 == 0.0 {
+                    p0
+                } else if d1 == 0.0 {
+                    p1
+                } else if (d0 < 0.0) != (d1 < 0.0) {
+                    p0 + (p1 - p0) * (d0 / (d0 - d1))
+                } else {
+                    continue;
+                };
+                let local = crossing - point;
+                for (k, axis) in [u_axis, v_axis].into_iter().enumerate() {
+                    let c = local.dot(axis);
+                    lo[k] = lo[k].min(c);
+                    hi[k] = hi[k].max(c);
+                }
+                hits += 1;
+            }
+        }
+
+        let extent = (max - min).max_element().max(f32::MIN_POSITIVE);
+        if hits == 0 || hi[0] - lo[0] <= extent * 1e-6 || hi[1] - lo[1] <= extent * 1e-6 {
+            return None;
+        }
+
+        let origin = point + lo[0] * u_axis + lo[1] * v_axis;
+        let du = u_axis * ((hi[0] - lo[0]) / (resolution[0] - 1) as f32);
+        let dv = v_axis * ((hi[1] - lo[1]) / (resolution[1] - 1) as f32);
+
+        let mut values = Vec::with_capacity(resolution[0] * resolution[1]);
+        for j in 0..resolution[1] {
+            for i in 0..resolution[0] {
+                let p = origin + i as f32 * du + j as f32 * dv;
+                values.push(volume.sample_world(p.to_array()).unwrap_or(f32::NAN));
+            }
+        }
+
+        Some(Self {
+            resolution,
+            origin: origin.to_array(),
+            u: du.to_array(),
+            v: dv.to_array(),
+            normal: normal.to_array(),
+            values,
+        })
+    }
+
+    /// World-space position of sample (i, j); fractional indices interpolate.
+    pub fn position(&self, i: f32, j: f32) -> [f32; 3] {
+        (Vec3::from(self.origin) + i * Vec3::from(self.u) + j * Vec3::from(self.v)).to_array()
+    }
+
+    /// Triangulate the grid into a flat mesh with per-vertex scalars and normals.
+    ///
+    /// Cells with a NaN corner (outside the volume or over invalid voxels) are
+    /// left out; unreferenced samples are dropped. Triangles wind
+    /// counter-clockwise around the plane normal.
+    pub fn to_mesh(&self) -> Mesh {
+        let [ni, nj] = self.resolution;
+        let mut remap = vec![u32::MAX; ni * nj];
+        let mut positions = Vec::new();
+        let mut scalars = Vec::new();
+        let mut indices = Vec::new();
+
+        for j in 0..(nj - 1) {
+            for i in 0..(ni - 1) {
+                let corners = [i + j * ni, i + 1 + j * ni, i + 1 + (j + 1) * ni, i + (j + 1) * ni];
+                if corners.iter().any(|&k| self.values[k].is_nan()) {
+                    continue;
+                }
+                let mut quad = [0u32; 4];
+                for (slot, &k) in quad.iter_mut().zip(corners.iter()) {
+                    if remap[k] == u32::MAX {
+                        remap[k] = scalars.len() as u32;
+                        positions.extend_from_slice(&self.position((k % ni) as f32, (k / ni) as f32));
+                        scalars.push(self.values[k]);
+                    }
+                    *slot = remap[k];
+                }
+                indices.extend_from_slice(&[quad[0], quad[1], quad[2], quad[0], quad[2], quad[3]]);
+            }
+        }
+
+        let normals = self.normal.repeat(scalars.len());
+        Mesh::new(positions, indices).with_normals(normals).with_scalars(scalars)
+    }
+
+    /// Contour lines of the sampled values at each of `levels`, lying in the plane.
+    ///
+    /// Lines stop where samples are NaN.
+    pub fn contours(&self, levels: &[f32], line_width: f32) -> Vec<ContourLine> {
+        contour_lines(&self.values, self.resolution, levels, line_width, |i, j, _| self.position(i, j))
+    }
+}
+
+/// Slice `volume` with `plane` into a flat scalar-mapped mesh.
+///
+/// `resolution` is the number of samples along the plane's two in-plane axes.
+/// Returns an empty mesh if the plane misses the volume.
+/// See [`SliceGrid::sample`] and [`SliceGrid::to_mesh`].
+pub fn slice_volume(volume: &Volume, plane: &SlicePlane, resolution: [usize; 2]) -> Mesh {
+    match SliceGrid::sample(volume, plane, resolution) {
+        Some(grid) => grid.to_mesh(),
+        None => Mesh::new(Vec::new(), Vec::new()),
+    }
+}
+
+/// Contour lines of `volume` on a plane slice sampled at `resolution`.
+///
+/// Returns no lines if the plane misses the volume. See [`SliceGrid::contours`].
+pub fn slice_contours(
+    volume: &Volume,
+    plane: &SlicePlane,
+    resolution: [usize; 2],
+    levels: &[f32],
+    line_width: f32,
+) -> Vec<ContourLine> {
+    SliceGrid::sample(volume, plane, resolution)
+        .map(|grid| grid.contours(levels, line_width))
+        .unwrap_or_default()
+}
+
+/// Axis-aligned world-space bounds of the grid points.
+fn volume_bounds(volume: &Volume) -> (Vec3, Vec3) {
+    let mut min = Vec3::ZERO;
+    let mut max = Vec3::ZERO;
+    for axis in 0..3 {
+        let n = volume.dimensions[axis];
+        min[axis] = volume.axis_coordinate(axis, 0);
+        max[axis] = volume.axis_coordinate(axis, n.saturating_sub(1));
+    }
+    (min, max)
+}
+
+impl Volume {
+    /// Trilinearly interpolated value at a world-space position.
+    ///
+    /// Returns `None` outside the volume bounds (up to rounding at the
+    /// boundary) or if an invalid voxel contributes to the interpolation.
+    pub fn sample_world(&self, p: [f32; 3]) -> Option<f32> {
+        let (min, max) = volume_bounds(self);
+        let tolerance = (max - min).max_element() * 1e-5;
+        let axes = self.axes();
+
+        let mut cell = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for axis in 0..3 {
+            if !(p[axis] >= min[axis] - tolerance && p[axis] <= max[axis] + tolerance) {
+                return None;
+            }
+            let n = self.dimensions[axis];
+            let f = axes.grid_position(axis, p[axis], n);
+            let i = (f.floor() as usize).min(n.saturating_sub(2));
+            cell[axis] = i;
+            frac[axis] = f - i as f32;
+        }
+
+        let mut value = 0.0;
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut weight = 1.0;
+            for axis in 0..3 {
+                weight *= if offset[axis] == 1 { frac[axis] } else { 1.0 - frac[axis] };
+            }
+            if weight == 0.0 {
+                continue;
+            }
+            let (x, y, z) = (cell[0] + offset[0], cell[1] + offset[1], cell[2] + offset[2]);
+            if !self.is_valid(x, y, z) {
+                return None;
+            }
+            value += weight * self.get(x, y, z);
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Linear field f = x + 2y + 3z on [-1, 1]^3.
+    fn linear_volume(n: usize) -> Volume {
+        let h = 2.0 / (n - 1) as f32;
+        let mut values = Vec::with_capacity(n * n * n);
+        for z in 0..n {
+            for y in 0..n {
+                for x in 0..n {
+                    let (fx, fy, fz) = (x as f32 * h - 1.0, y as f32 * h - 1.0, z as f32 * h - 1.0);
+                    values.push(fx + 2.0 * fy + 3.0 * fz);
+                }
+            }
+        }
+        Volume::new(values, [n, n, n], [h; 3], [-1.0; 3])
+    }
+
+    fn check_linear(mesh: &Mesh) {
+        let scalars = mesh.scalars.as_ref().unwrap();
+        for (p, &s) in mesh.positions.chunks(3).zip(scalars) {
+            let expected = p[0] + 2.0 * p[1] + 3.0 * p[2];
+            assert!((s - expected).abs() < 1e-4, "Scalar {} vs {} at {:?}", s, expected, p);
+        }
+    }
+
+    #[test]
+    fn test_axis_slice() {
+        let volume = linear_volume(9);
+        let mesh = slice_volume(&volume, &SlicePlane::axis(SliceAxis::Z, 0.25), [5, 7]);
+
+        assert_eq!(mesh.vertex_count(), 35);
+        assert_eq!(mesh.triangle_count(), 2 * 4 * 6);
+        assert!(mesh.positions.chunks(3).all(|p| (p[2] - 0.25).abs() < 1e-6));
+        check_linear(&mesh);
+
+        // Winding follows the +z normal
+        let p = |k: u32| Vec3::from_slice(&mesh.positions[k as usize * 3..k as usize * 3 + 3]);
+        let t = &mesh.indices[0..3];
+        let n = (p(t[1]) - p(t[0])).cross(p(t[2]) - p(t[0]));
+        assert!(n.z > 0.0);
+        assert_eq!(&mesh.normals.as_ref().unwrap()[0..3], &[0.0, 0.0, 1.0]);
+
+        // Planes outside the volume give an empty mesh
+        let outside = slice_volume(&volume, &SlicePlane::axis(SliceAxis::X, 1.5), [4, 4]);
+        assert_eq!(outside.vertex_count(), 0);
+    }
+
+    #[test]
+    fn test_oblique_slice_stays_inside() {
+        let volume = linear_volume(9);
+        let plane = SlicePlane::oblique([0.1, 0.0, 0.0], [1.0, 1.0, 1.0]);
+        let mesh = slice_volume(&volume, &plane, [32, 32]);
+
+        assert!(mesh.triangle_count() > 0);
+        for p in mesh.positions.chunks(3) {
+            let d = (p[0] - 0.1 + p[1] + p[2]) / 3f32.sqrt();
+            assert!(d.abs() < 1e-5, "Vertex off plane: {:?}", p);
+            assert!(p.iter().all(|c| c.abs() <= 1.0 + 1e-5), "Vertex outside volume: {:?}", p);
+        }
+        check_linear(&mesh);
+    }
+
+    #[test]
+    fn test_slice_skips_invalid_voxels() {
+        let volume = linear_volume(9);
+        let mut masked = volume.clone();
+        // Invalidate the voxel column at grid (4, 4, *)
+        for z in 0..9 {
+            masked.values[4 + 4 * 9 + z * 81] = f32::NAN;
+        }
+        let plane = SlicePlane::axis(SliceAxis::Z, 0.0);
+        let full = slice_volume(&volume, &plane, [9, 9]);
+        let holed = slice_volume(&masked, &plane, [9, 9]);
+        assert_eq!(full.triangle_count(), 2 * 64);
+        assert_eq!(holed.triangle_count(), 2 * 60);
+        assert!(holed.scalars.unwrap().iter().all(|s| s.is_finite()));
+    }
+
+    #[test]
+    fn test_slice_contours() {
+        let volume = linear_volume(9);
+        let grid = SliceGrid::sample(&volume, &SlicePlane::axis(SliceAxis::Z, 0.0), [17, 17]).unwrap();
+        let lines = grid.contours(&[-1.0, 0.0, 1.0], 2.0);
+
+        // One straight line x + 2y = level per level
+        assert_eq!(lines.len(), 3);
+        for (line, level) in lines.iter().zip([-1.0, 0.0, 1.0]) {
+            assert_eq!(line.level, level);
+            assert!(!line.closed);
+            assert!(line.polyline.positions.len() >= 6);
+            for p in line.polyline.positions.chunks(3) {
+                assert!((p[0] + 2.0 * p[1] - level).abs() < 1e-4);
+                assert!(p[2].abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_world_rectilinear() {
+        let xs = vec![0.0, 0.5, 2.0, 3.0];
+        let ys = vec![0.0, 1.0];
+        let zs = vec![-1.0, 1.0];
+        let mut values = Vec::new();
+        for &z in &zs {
+            for &y in &ys {
+                for &x in &xs {
+                    values.push(x - y + 0.5 * z);
+                }
+            }
+        }
+        let volume = Volume::rectilinear(values, xs, ys, zs);
+        let s = volume.sample_world([1.25, 0.5, 0.2]).unwrap();
+        assert!((s - (1.25 - 0.5 + 0.1)).abs() < 1e-5);
+        assert_eq!(volume.sample_world([3.5, 0.5, 0.0]), None);
+    }
+
+    #[test]
+    fn test_slice_plane_json() {
+        let plane = SlicePlane::axis(SliceAxis::Y, 0.5);
+        let json = serde_json::to_string(&plane).unwrap();
+        assert_eq!(json, r#"{"type":"axis","axis":"y","position":0.5}"#);
+        let parsed: SlicePlane = serde_json::from_str(r#"{"type":"oblique","point":[0,0,0],"normal":[0,1,1]}"#).unwrap();
+        assert_eq!(parsed, SlicePlane::oblique([0.0; 3], [0.0, 1.0, 1.0]));
+    }
+}
@@ -0,0 +1,187 @@
+//! Mesh smoothing filters.
+//!
+//! Explicit, optional post-processing for generated meshes: Laplacian
+//! smoothing and Taubin's shrink-free λ|μ variant. Vertices on open
+//! boundaries stay fixed so surfaces cut by the volume edge keep their outline.
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Mesh;
+
+/// Mesh smoothing method and parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum MeshSmoothing {
+    /// Repeated umbrella-operator smoothing: p += lambda * (mean(neighbours) - p).
+    ///
+    /// Shrinks closed surfaces noticeably for many iterations.
+    Laplacian { iterations: u32, lambda: f32 },
+    /// Taubin smoothing: alternating steps with `lambda > 0` and `mu < -lambda`.
+    ///
+    /// Removes high-frequency noise without the shrinkage of plain Laplacian smoothing.
+    Taubin { iterations: u32, lambda: f32, mu: f32 },
+}
+
+impl MeshSmoothing {
+    /// Taubin smoothing with the usual parameters (lambda = 0.5, mu = -0.53).
+    pub fn taubin(iterations: u32) -> Self {
+        MeshSmoothing::Taubin {
+            iterations,
+            lambda: 0.5,
+            mu: -0.53,
+        }
+    }
+
+    /// Laplacian smoothing with the given step size.
+    pub fn laplacian(iterations: u32, lambda: f32) -> Self {
+        MeshSmoothing::Laplacian { iterations, lambda }
+    }
+}
+
+impl Mesh {
+    /// Smooth vertex positions with the given filter.
+    ///
+    /// Topology, scalars and normals are left unchanged; normals should be
+    /// recomputed or re-sampled by the caller if exact shading matters.
+    /// Neighbourhoods use uniform weights over the mesh edges, and boundary
+    /// vertices (on edges used by a single triangle) are held fixed.
+    pub fn smooth(&self, smoothing: &MeshSmoothing) -> Mesh {
+        let n = self.vertex_count();
+        let (neighbours, boundary) = vertex_neighbours(self);
+
+        let steps: Vec<f32> = match *smoothing {
+            MeshSmoothing::Laplacian { iterations, lambda } => vec![lambda; iterations as usize],
+            MeshSmoothing::Taubin { iterations, lambda, mu } => (0..iterations)
+                .flat_map(|_| [lambda, mu])
+                .collect(),
+        };
+
+        let mut positions = self.positions.clone();
+        let mut next = positions.clone();
+        for factor in steps {
+            for v in 0..n {
+                if boundary[v] || neighbours[v].is_empty() {
+                    continue;
+                }
+                let mut mean = [0.0f32; 3];
+                for &w in &neighbours[v] {
+                    for k in 0..3 {
+                        mean[k] += positions[w as usize * 3 + k];
+                    }
+                }
+                let count = neighbours[v].len() as f32;
+                for k in 0..3 {
+                    let p = positions[v * 3 + k];
+                    next[v * 3 + k] = p + factor * (mean[k] / count - p);
+                }
+            }
+            std::mem::swap(&mut positions, &mut next);
+            next.copy_from_slice(&positions);
+        }
+
+        Mesh {
+            positions,
+            ..self.clone()
+        }
+    }
+}
+
+/// Sorted edge neighbours of every vertex, and which vertices lie on an open boundary.
+fn vertex_neighbours(mesh: &Mesh) -> (Vec<Vec<u32>>, Vec<bool>) {
+    let n = mesh.vertex_count();
+    let mut edge_count: std::collections::BTreeMap<(u32, u32), u32> = Default::default();
+    for t in mesh.indices.chunks_exact(3) {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            if a != b {
+                *edge_count.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut neighbours = vec![Vec::new(); n];
+    let mut boundary = vec![false; n];
+    for (&(a, b), &count) in &edge_count {
+        neighbours[a as usize].push(b);
+        neighbours[b as usize].push(a);
+        if count == 1 {
+            boundary[a as usize] = true;
+            boundary[b as usize] = true;
+        }
+    }
+    for list in neighbours.iter_mut() {
+        list.sort_unstable();
+    }
+
+    (neighbours, boundary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Octahedron with each vertex jittered outward or inward.
+    fn bumpy_octahedron() -> Mesh {
+        #[rustfmt::skip]
+        let positions = vec![
+            1.2, 0.0, 0.0,  -1.0, 0.0, 0.0,
+            0.0, 0.8, 0.0,   0.0, -1.1, 0.0,
+            0.0, 0.0, 1.0,   0.0, 0.0, -0.9,
+        ];
+        #[rustfmt::skip]
+        let indices = vec![
+            0, 2, 4,  2, 1, 4,  1, 3, 4,  3, 0, 4,
+            2, 0, 5,  1, 2, 5,  3, 1, 5,  0, 3, 5,
+        ];
+        Mesh::new(positions, indices)
+    }
+
+    fn radii(mesh: &Mesh) -> Vec<f32> {
+        mesh.positions
+            .chunks(3)
+            .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+            .collect()
+    }
+
+    fn spread(values: &[f32]) -> f32 {
+        let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+        max - min
+    }
+
+    fn mean(values: &[f32]) -> f32 {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+
+    #[test]
+    fn test_taubin_shrinks_less_than_laplacian() {
+        let mesh = bumpy_octahedron();
+        let laplacian = mesh.smooth(&MeshSmoothing::laplacian(10, 0.5));
+        let taubin = mesh.smooth(&MeshSmoothing::taubin(10));
+
+        let original = mean(&radii(&mesh));
+        let lap_shrink = original - mean(&radii(&laplacian));
+        let taubin_shrink = original - mean(&radii(&taubin));
+        assert!(taubin_shrink.abs() < lap_shrink.abs());
+
+        // Both filters even out the bumps
+        assert!(spread(&radii(&taubin)) < spread(&radii(&mesh)));
+        assert_eq!(taubin.indices, mesh.indices);
+    }
+
+    #[test]
+    fn test_boundary_vertices_fixed() {
+        // Fan of four triangles around a raised centre vertex
+        #[rustfmt::skip]
+        let positions = vec![
+            0.0, 0.0, 1.0,
+            1.0, 0.0, 0.0,  0.0, 1.0, 0.0,  -1.0, 0.0, 0.0,  0.0, -1.0, 0.0,
+        ];
+        let indices = vec![0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 1];
+        let mesh = Mesh::new(positions, indices);
+        let smoothed = mesh.smooth(&MeshSmoothing::laplacian(1, 1.0));
+
+        assert_eq!(&smoothed.positions[3..], &mesh.positions[3..]);
+        assert!(smoothed.positions[2].abs() < 1e-6, "Centre should move to the rim average");
+    }
+}
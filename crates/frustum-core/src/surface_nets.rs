@@ -0,0 +1,329 @@
+//! Surface Nets algorithm for isosurface extraction.
+//!
+//! Alternative to Marching Cubes (Extension 2 in 009-extensions.md).
+//! One vertex is placed per surface-crossing cell and neighbouring cells are
+//! joined by quads, which gives more regular triangles with far fewer slivers.
+//! Like Marching Cubes, this is a pure, deterministic geometry generation step.
+
+use crate::geometry::Mesh;
+use crate::marching_cubes::Volume;
+
+/// Corner offsets of a cell, indexed by bit position (x fastest).
+const CORNER_OFFSETS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [0, 1, 0],
+    [1, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+];
+
+/// Cell edges as pairs of corner indices into `CORNER_OFFSETS`.
+const CELL_EDGES: [(usize, usize); 12] = [
+    (0, 1), (2, 3), (4, 5), (6, 7), // Along X
+    (0, 2), (1, 3), (4, 6), (5, 7), // Along Y
+    (0, 4), (1, 5), (2, 6), (3, 7), // Along Z
+];
+
+/// Extract an isosurface from a volume using the Surface Nets algorithm.
+///
+/// Uses the same `Volume` indexing, spacing and origin semantics as
+/// `marching_cubes`. Each cell crossed by the surface contributes one vertex,
+/// placed at the mean of its interpolated edge crossings. Every grid edge
+/// crossed by the surface produces a quad joining the four cells around it,
+/// split along its shorter diagonal into two triangles.
+///
+/// Returns a `Mesh` with positions, indices, and gradient-based normals, using
+/// the same normal, winding and invalid-voxel conventions as `marching_cubes`.
+/// The mesh has no material_id assigned; the caller must set one before rendering.
+pub fn surface_nets(volume: &Volume, iso_value: f32) -> Mesh {
+    let [nx, ny, nz] = volume.dimensions;
+
+    // Validate dimensions
+    if nx < 2 || ny < 2 || nz < 2 {
+        return Mesh::new(vec![], vec![]);
+    }
+
+    let (cx, cy) = (nx - 1, ny - 1);
+    let cell_index = |x: usize, y: usize, z: usize| x + y * cx + z * cx * cy;
+
+    let mut positions: Vec<f32> = Vec::new();
+    let mut normals: Vec<f32> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    // Cell-to-vertex map: one vertex per surface-crossing cell
+    let mut cell_vertices = vec![u32::MAX; cx * cy * (nz - 1)];
+
+    // Pass 1: place one vertex in every cell crossed by the surface
+    for z in 0..(nz - 1) {
+        for y in 0..(ny - 1) {
+            for x in 0..(nx - 1) {
+                let mut v = [0.0f32; 8];
+                let mut mask = 0u8;
+                for (corner, offset) in CORNER_OFFSETS.iter().enumerate() {
+                    v[corner] = volume.valid_value(x + offset[0], y + offset[1], z + offset[2]);
+                    if v[corner] < iso_value {
+                        mask |= 1 << corner;
+                    }
+                }
+
+                // Cells touching invalid voxels produce no vertex
+                if v.iter().any(|value| !value.is_finite()) {
+                    continue;
+                }
+
+                // Skip if entirely inside or outside
+                if mask == 0 || mask == 255 {
+                    continue;
+                }
+
+                // Average the edge crossings in local cell coordinates
+                let mut local = [0.0f32; 3];
+                let mut crossings = 0;
+                for &(c0, c1) in CELL_EDGES.iter() {
+                    let inside0 = (mask >> c0) & 1;
+                    let inside1 = (mask >> c1) & 1;
+                    if inside0 == inside1 {
+                        continue;
+                    }
+
+                    let t = if (v[c1] - v[c0]).abs() > 1e-10 {
+                        (iso_value - v[c0]) / (v[c1] - v[c0])
+                    } else {
+                        0.5
+                    };
+
+                    let (o0, o1) = (CORNER_OFFSETS[c0], CORNER_OFFSETS[c1]);
+                    for axis in 0..3 {
+                        local[axis] += o0[axis] as f32 + t * (o1[axis] as f32 - o0[axis] as f32);
+                    }
+                    crossings += 1;
+                }
+                for value in local.iter_mut() {
+                    *value /= crossings as f32;
+                }
+
+                // Map to world space, linearly within the cell (same as grid_to_world)
+                let axes = volume.axes();
+                let base = volume.grid_to_world(x, y, z);
+                let pos = [
+                    base[0] + local[0] * axes.step(0, x),
+                    base[1] + local[1] * axes.step(1, y),
+                    base[2] + local[2] * axes.step(2, z),
+                ];
+
+                // Trilinearly interpolate corner gradients (negative gradient)
+                let mut normal = [0.0f32; 3];
+                for offset in CORNER_OFFSETS.iter() {
+                    let mut weight = 1.0;
+                    for axis in 0..3 {
+                        weight *= if offset[axis] == 1 { local[axis] } else { 1.0 - local[axis] };
+                    }
+                    let g = volume.gradient(x + offset[0], y + offset[1], z + offset[2]);
+                    normal[0] -= weight * g[0];
+                    normal[1] -= weight * g[1];
+                    normal[2] -= weight * g[2];
+                }
+
+                // Normalize
+                let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+                if len > 1e-10 {
+                    normal[0] /= len;
+                    normal[1] /= len;
+                    normal[2] /= len;
+                }
+
+                cell_vertices[cell_index(x, y, z)] = (positions.len() / 3) as u32;
+                positions.extend_from_slice(&pos);
+                normals.extend_from_slice(&normal);
+            }
+        }
+    }
+
+    // Pass 2: one quad per surface-crossing grid edge with four surrounding cells
+    for z in 0..nz {
+        for y in 0..ny {
+            for x in 0..nx {
+                let p = [x, y, z];
+                let inside = volume.get(x, y, z) < iso_value;
+
+                for axis in 0..3 {
+                    // The other two axes, in cyclic order so that axis = u × w
+                    let (u, w) = ((axis + 1) % 3, (axis + 2) % 3);
+
+                    // Boundary edges lack a full ring of cells; the surface stays open there
+                    if p[axis] + 1 >= volume.dimensions[axis]
+                        || p[u] == 0
+                        || p[w] == 0
+                        || p[u] + 1 >= volume.dimensions[u]
+                        || p[w] + 1 >= volume.dimensions[w]
+                    {
+                        continue;
+                    }
+
+                    let mut q = p;
+                    q[axis] += 1;
+                    let inside_next = volume.get(q[0], q[1], q[2]) < iso_value;
+                    if inside == inside_next {
+                        continue;
+                    }
+
+                    // Cells around the edge, counter-clockwise about +axis
+                    let cell_at = |du: usize, dw: usize| {
+                        let mut c = p;
+                        c[u] -= 1 - du;
+                        c[w] -= 1 - dw;
+                        cell_vertices[cell_index(c[0], c[1], c[2])]
+                    };
+                    let mut quad = [cell_at(0, 0), cell_at(1, 0), cell_at(1, 1), cell_at(0, 1)];
+
+                    // A skipped neighbour (invalid voxels) leaves a hole
+                    if quad.contains(&u32::MAX) {
+                        continue;
+                    }
+
+                    // Face the quad along the negative gradient (toward values below iso)
+                    if inside {
+                        quad.reverse();
+                    }
+
+                    emit_quad(&positions, quad, &mut indices);
+                }
+            }
+        }
+    }
+
+    Mesh::new(positions, indices).with_normals(normals)
+}
+
+/// Split a quad into two triangles along its shorter diagonal.
+///
+/// Ties fall back to the q0–q2 diagonal so the split is deterministic.
+fn emit_quad(positions: &[f32], quad: [u32; 4], indices: &mut Vec<u32>) {
+    let dist_sq = |a: u32, b: u32| {
+        let (a, b) = (a as usize * 3, b as usize * 3);
+        (0..3)
+            .map(|k| (positions[a + k] - positions[b + k]).powi(2))
+            .sum::<f32>()
+    };
+
+    let [q0, q1, q2, q3] = quad;
+    if dist_sq(q1, q3) < dist_sq(q0, q2) {
+        indices.extend_from_slice(&[q1, q2, q3, q1, q3, q0]);
+    } else {
+        indices.extend_from_slice(&[q0, q1, q2, q0, q2, q3]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marching_cubes::marching_cubes;
+    use crate::marching_cubes::test_support::sphere_volume;
+
+    /// Smallest interior angle of each triangle, in degrees.
+    fn min_angles(mesh: &Mesh) -> Vec<f32> {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            glam::Vec3::new(mesh.positions[i], mesh.positions[i + 1], mesh.positions[i + 2])
+        };
+        mesh.indices
+            .chunks(3)
+            .map(|t| {
+                let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+                let angle = |o: glam::Vec3, x: glam::Vec3, y: glam::Vec3| (x - o).angle_between(y - o);
+                angle(a, b, c).min(angle(b, c, a)).min(angle(c, a, b)).to_degrees()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sphere_surface_nets() {
+        let volume = sphere_volume([20; 3], 0.7);
+        let mesh = surface_nets(&volume, 0.0);
+
+        assert!(!mesh.indices.is_empty());
+        assert!(mesh.normals.is_some());
+        assert_eq!(mesh.normals.as_ref().unwrap().len(), mesh.positions.len());
+
+        // Vertices lie on the sphere within voxel-spacing tolerance
+        let spacing = volume.spacing[0];
+        for p in mesh.positions.chunks(3) {
+            let r = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            assert!((r - 0.7).abs() < spacing, "Vertex off surface: r = {}", r);
+        }
+
+        // Closed surface: every edge is shared by exactly two triangles
+        let mut edges = std::collections::HashMap::new();
+        for t in mesh.indices.chunks(3) {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2), "Mesh is not watertight");
+    }
+
+    #[test]
+    fn test_winding_matches_normals() {
+        let volume = sphere_volume([16; 3], 0.6);
+        let mesh = surface_nets(&volume, 0.0);
+        let normals = mesh.normals.as_ref().unwrap();
+
+        for t in mesh.indices.chunks(3) {
+            let p = |i: u32| {
+                let i = i as usize * 3;
+                glam::Vec3::new(mesh.positions[i], mesh.positions[i + 1], mesh.positions[i + 2])
+            };
+            let face = (p(t[1]) - p(t[0])).cross(p(t[2]) - p(t[0]));
+            let i = t[0] as usize * 3;
+            let n = glam::Vec3::new(normals[i], normals[i + 1], normals[i + 2]);
+            assert!(face.dot(n) > 0.0, "Triangle winding disagrees with normal");
+        }
+    }
+
+    #[test]
+    fn test_fewer_slivers_than_marching_cubes() {
+        let volume = sphere_volume([24; 3], 0.75);
+        let slivers = |mesh: &Mesh| min_angles(mesh).iter().filter(|&&a| a < 10.0).count();
+
+        let nets = surface_nets(&volume, 0.0);
+        let cubes = marching_cubes(&volume, 0.0);
+        assert!(
+            slivers(&nets) * 4 < slivers(&cubes),
+            "Expected far fewer slivers: surface nets {}, marching cubes {}",
+            slivers(&nets),
+            slivers(&cubes)
+        );
+    }
+
+    #[test]
+    fn test_empty_volume() {
+        let volume = Volume::new(vec![1.0; 8], [2, 2, 2], [1.0; 3], [0.0; 3]);
+        let mesh = surface_nets(&volume, 0.0);
+        assert!(mesh.positions.is_empty());
+        assert!(mesh.indices.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_voxels_leave_hole() {
+        let mut volume = sphere_volume([16; 3], 0.6);
+        let [nx, ny, _] = volume.dimensions;
+        // A plug of NaN through the sphere wall on the +x side
+        for z in 5..10 {
+            for y in 5..10 {
+                for x in 11..14 {
+                    volume.values[x + y * nx + z * nx * ny] = f32::NAN;
+                }
+            }
+        }
+        let mesh = surface_nets(&volume, 0.0);
+        let full = surface_nets(&sphere_volume([16; 3], 0.6), 0.0);
+
+        assert!(mesh.triangle_count() < full.triangle_count());
+        assert!(mesh.positions.iter().all(|v| v.is_finite()));
+        assert!(mesh.normals.as_ref().unwrap().iter().all(|v| v.is_finite()));
+    }
+}
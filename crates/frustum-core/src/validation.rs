@@ -0,0 +1,448 @@
+//! Structural validation of scenes.
+//!
+//! `Scene::validate` checks everything the renderer relies on but cannot
+//! recover from: array lengths, index bounds, finite coordinates, material
+//! references and a usable camera. Each problem is reported with a
+//! JSON-pointer path into the serialized scene, e.g. `/elements/2/indices/17`.
+//!
+//! Non-finite *scalars* are not errors: they are drawn with the material's
+//! missing color. Long arrays report only their first offending entry.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::camera::{Camera, Projection};
+use crate::geometry::{AxisBundle, Mesh, PointCloud, Polyline};
+use crate::lighting::Light;
+use crate::materials::Material;
+use crate::migration::SCHEMA_VERSION;
+use crate::scene::{Bounds, Scene, SceneElement};
+
+/// What is wrong at a given path.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    #[error("length {len} is not a multiple of {stride}")]
+    LengthNotMultiple { len: usize, stride: usize },
+    #[error("expected {expected} values, found {found}")]
+    LengthMismatch { expected: usize, found: usize },
+    #[error("index {index} is out of range for {vertex_count} vertices")]
+    IndexOutOfRange { index: u32, vertex_count: usize },
+    #[error("value {0} is not finite")]
+    NonFinite(f32),
+    #[error("material '{0}' is not defined in the scene")]
+    UnknownMaterial(String),
+    #[error("material id '{0}' is defined more than once")]
+    DuplicateMaterial(String),
+    #[error("schema version '{0}' is not supported, expected '{SCHEMA_VERSION}'")]
+    UnsupportedVersion(String),
+    #[error("degenerate camera: {0}")]
+    DegenerateCamera(String),
+    #[error("{0}")]
+    InvalidValue(String),
+}
+
+/// A single validation problem and where it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// JSON pointer to the offending value.
+    pub path: String,
+    pub kind: ValidationErrorKind,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// All problems found in a scene, in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationErrors {
+    pub errors: Vec<ValidationError>,
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid scene ({} errors)", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl Scene {
+    /// Check the scene for structural errors before rendering.
+    ///
+    /// Returns every problem found rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut v = Validator { errors: Vec::new() };
+
+        if self.version != SCHEMA_VERSION {
+            v.push("/version".to_string(), ValidationErrorKind::UnsupportedVersion(self.version.clone()));
+        }
+        v.camera(&self.camera);
+        v.bounds("/bounds", &self.bounds);
+        if let Some(light) = &self.light {
+            v.light(light);
+        }
+
+        let mut ids = HashSet::new();
+        for (i, material) in self.materials.iter().enumerate() {
+            let path = format!("/materials/{i}");
+            if !ids.insert(material.id()) {
+                v.push(format!("{path}/id"), ValidationErrorKind::DuplicateMaterial(material.id().to_string()));
+            }
+            v.material(&path, material);
+        }
+
+        for (i, element) in self.elements.iter().enumerate() {
+            let path = format!("/elements/{i}");
+            match element {
+                SceneElement::PointCloud(pc) => v.point_cloud(&path, pc),
+                SceneElement::Polyline(line) => v.polyline(&path, line),
+                SceneElement::Mesh(mesh) => v.mesh(&path, mesh),
+                SceneElement::Axes(axes) => v.axes(&path, axes),
+            }
+            let material_id = match element {
+                SceneElement::PointCloud(pc) => pc.material_id.as_deref(),
+                SceneElement::Polyline(line) => line.material_id.as_deref(),
+                SceneElement::Mesh(mesh) => mesh.material_id.as_deref(),
+                SceneElement::Axes(_) => None,
+            };
+            if let Some(id) = material_id {
+                if !ids.contains(id) {
+                    v.push(format!("{path}/material_id"), ValidationErrorKind::UnknownMaterial(id.to_string()));
+                }
+            }
+        }
+
+        if v.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { errors: v.errors })
+        }
+    }
+}
+
+struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    fn push(&mut self, path: String, kind: ValidationErrorKind) {
+        self.errors.push(ValidationError { path, kind });
+    }
+
+    fn invalid(&mut self, path: String, message: impl Into<String>) {
+        self.push(path, ValidationErrorKind::InvalidValue(message.into()));
+    }
+
+    /// Report the first non-finite entry of `values`. Returns true if all are finite.
+    fn finite(&mut self, path: &str, values: &[f32]) -> bool {
+        match values.iter().position(|v| !v.is_finite()) {
+            Some(i) => {
+                self.push(format!("{path}/{i}"), ValidationErrorKind::NonFinite(values[i]));
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Check a flattened xyz array and return its vertex count.
+    fn positions(&mut self, path: &str, positions: &[f32]) -> usize {
+        if !positions.len().is_multiple_of(3) {
+            self.push(
+                path.to_string(),
+                ValidationErrorKind::LengthNotMultiple { len: positions.len(), stride: 3 },
+            );
+        }
+        self.finite(path, positions);
+        positions.len() / 3
+    }
+
+    /// Check that a per-vertex array has `per_vertex` values for each of `count` vertices.
+    fn per_vertex(&mut self, path: &str, values: &[f32], count: usize, per_vertex: usize) {
+        if values.len() != count * per_vertex {
+            self.push(
+                path.to_string(),
+                ValidationErrorKind::LengthMismatch { expected: count * per_vertex, found: values.len() },
+            );
+        }
+    }
+
+    fn positive(&mut self, path: String, value: f32) {
+        if !(value.is_finite() && value > 0.0) {
+            self.invalid(path, format!("must be a positive number, got {value}"));
+        }
+    }
+
+    fn point_cloud(&mut self, path: &str, pc: &PointCloud) {
+        let count = self.positions(&format!("{path}/positions"), &pc.positions);
+        if let Some(scalars) = &pc.scalars {
+            self.per_vertex(&format!("{path}/scalars"), scalars, count, 1);
+        }
+        self.positive(format!("{path}/point_size"), pc.point_size);
+    }
+
+    fn polyline(&mut self, path: &str, line: &Polyline) {
+        let count = self.positions(&format!("{path}/positions"), &line.positions);
+        if let Some(scalars) = &line.scalars {
+            self.per_vertex(&format!("{path}/scalars"), scalars, count, 1);
+        }
+        self.positive(format!("{path}/line_width"), line.line_width);
+    }
+
+    fn mesh(&mut self, path: &str, mesh: &Mesh) {
+        let count = self.positions(&format!("{path}/positions"), &mesh.positions);
+
+        if !mesh.indices.len().is_multiple_of(3) {
+            self.push(
+                format!("{path}/indices"),
+                ValidationErrorKind::LengthNotMultiple { len: mesh.indices.len(), stride: 3 },
+            );
+        }
+        if let Some(i) = mesh.indices.iter().position(|&index| index as usize >= count) {
+            self.push(
+                format!("{path}/indices/{i}"),
+                ValidationErrorKind::IndexOutOfRange { index: mesh.indices[i], vertex_count: count },
+            );
+        }
+
+        if let Some(normals) = &mesh.normals {
+            let normals_path = format!("{path}/normals");
+            self.per_vertex(&normals_path, normals, count, 3);
+            self.finite(&normals_path, normals);
+        }
+        if let Some(scalars) = &mesh.scalars {
+            self.per_vertex(&format!("{path}/scalars"), scalars, count, 1);
+        }
+    }
+
+    fn axes(&mut self, path: &str, axes: &AxisBundle) {
+        let min_path = format!("{path}/bounds/min");
+        let max_path = format!("{path}/bounds/max");
+        if self.finite(&min_path, &axes.bounds.min) && self.finite(&max_path, &axes.bounds.max) {
+            self.ordered(&max_path, &axes.bounds.min, &axes.bounds.max);
+        }
+        self.positive(format!("{path}/line_width"), axes.line_width);
+    }
+
+    fn bounds(&mut self, path: &str, bounds: &Bounds) {
+        let min_path = format!("{path}/min");
+        let max_path = format!("{path}/max");
+        if self.finite(&min_path, &bounds.min) && self.finite(&max_path, &bounds.max) {
+            self.ordered(&max_path, &bounds.min, &bounds.max);
+        }
+    }
+
+    fn ordered(&mut self, max_path: &str, min: &[f32; 3], max: &[f32; 3]) {
+        if let Some(axis) = (0..3).find(|&a| min[a] > max[a]) {
+            self.invalid(
+                format!("{max_path}/{axis}"),
+                format!("max {} is less than min {}", max[axis], min[axis]),
+            );
+        }
+    }
+
+    fn material(&mut self, path: &str, material: &Material) {
+        match material {
+            Material::Solid(m) => {
+                self.finite(&format!("{path}/color"), &m.color);
+            }
+            Material::ScalarMapped(m) => {
+                self.finite(&format!("{path}/range"), &m.range);
+                self.finite(&format!("{path}/missing_color"), &m.missing_color);
+            }
+        }
+    }
+
+    fn light(&mut self, light: &Light) {
+        let [x, y, z] = light.direction;
+        if self.finite("/light/direction", &light.direction) && (x * x + y * y + z * z).sqrt() <= 1e-6 {
+            self.invalid("/light/direction".to_string(), "direction must be non-zero");
+        }
+        if !(light.intensity.is_finite() && light.intensity >= 0.0) {
+            self.invalid(
+                "/light/intensity".to_string(),
+                format!("must be a non-negative number, got {}", light.intensity),
+            );
+        }
+    }
+
+    fn camera(&mut self, camera: &Camera) {
+        let all_finite = [
+            self.finite("/camera/position", &camera.position),
+            self.finite("/camera/target", &camera.target),
+            self.finite("/camera/up", &camera.up),
+        ]
+        .iter()
+        .all(|&ok| ok);
+
+        if all_finite {
+            let view = sub(camera.target, camera.position);
+            let distance = length(view);
+            if distance < 1e-6 {
+                self.push(
+                    "/camera/target".to_string(),
+                    ValidationErrorKind::DegenerateCamera("position equals target".to_string()),
+                );
+            } else {
+                let up_length = length(camera.up);
+                if up_length < 1e-6 {
+                    self.push(
+                        "/camera/up".to_string(),
+                        ValidationErrorKind::DegenerateCamera("up vector is zero".to_string()),
+                    );
+                } else if length(cross(view, camera.up)) < 1e-6 * distance * up_length {
+                    self.push(
+                        "/camera/up".to_string(),
+                        ValidationErrorKind::DegenerateCamera("up vector is parallel to the view direction".to_string()),
+                    );
+                }
+            }
+        }
+
+        if !(camera.near.is_finite() && camera.near > 0.0) {
+            self.push(
+                "/camera/near".to_string(),
+                ValidationErrorKind::DegenerateCamera(format!("near plane must be positive, got {}", camera.near)),
+            );
+        } else if !(camera.far.is_finite() && camera.far > camera.near) {
+            self.push(
+                "/camera/far".to_string(),
+                ValidationErrorKind::DegenerateCamera(format!(
+                    "far plane ({}) must be beyond near plane ({})",
+                    camera.far, camera.near
+                )),
+            );
+        }
+
+        let size = camera.fov_or_height;
+        let problem = match camera.projection {
+            Projection::Perspective if !(size > 0.0 && size < 180.0) => {
+                Some(format!("field of view must be between 0 and 180 degrees, got {size}"))
+            }
+            Projection::Orthographic if !(size.is_finite() && size > 0.0) => {
+                Some(format!("view height must be positive, got {size}"))
+            }
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            self.push("/camera/fov_or_height".to_string(), ValidationErrorKind::DegenerateCamera(problem));
+        }
+    }
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn length(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::SolidMaterial;
+    use crate::scene::test_support::empty_scene as scene;
+
+    fn triangle() -> Mesh {
+        Mesh::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], vec![0, 1, 2])
+    }
+
+    fn paths(scene: &Scene) -> Vec<String> {
+        scene.validate().unwrap_err().errors.into_iter().map(|e| e.path).collect()
+    }
+
+    #[test]
+    fn test_valid_scene_passes() {
+        let mut mesh = triangle();
+        mesh.scalars = Some(vec![0.0, f32::NAN, 1.0]);
+        let scene = scene()
+            .add_material(Material::Solid(SolidMaterial::new("red", [1.0, 0.0, 0.0])))
+            .add_mesh(mesh.with_material("red"))
+            .add_point_cloud(PointCloud::new(vec![0.0, 0.0, 0.0], 4.0));
+        assert_eq!(scene.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_mesh_errors_have_paths() {
+        let mut mesh = triangle();
+        mesh.indices = vec![0, 1, 7, 2];
+        mesh.positions.push(f32::NAN);
+        mesh.normals = Some(vec![0.0, 0.0, 1.0]);
+        let scene = scene().add_point_cloud(PointCloud::new(vec![0.0; 3], 4.0)).add_mesh(mesh);
+
+        let errors = scene.validate().unwrap_err().errors;
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/elements/1/positions",
+                "/elements/1/positions/9",
+                "/elements/1/indices",
+                "/elements/1/indices/2",
+                "/elements/1/normals",
+            ]
+        );
+        assert_eq!(errors[3].kind, ValidationErrorKind::IndexOutOfRange { index: 7, vertex_count: 3 });
+        assert_eq!(errors[4].kind, ValidationErrorKind::LengthMismatch { expected: 9, found: 3 });
+    }
+
+    #[test]
+    fn test_material_references() {
+        let red = Material::Solid(SolidMaterial::new("red", [1.0, 0.0, 0.0]));
+        let scene = scene()
+            .add_material(red.clone())
+            .add_material(red)
+            .add_mesh(triangle().with_material("blue"));
+        let errors = scene.validate().unwrap_err().errors;
+        assert_eq!(errors[0].path, "/materials/1/id");
+        assert_eq!(errors[0].kind, ValidationErrorKind::DuplicateMaterial("red".to_string()));
+        assert_eq!(errors[1].path, "/elements/0/material_id");
+        assert_eq!(errors[1].kind, ValidationErrorKind::UnknownMaterial("blue".to_string()));
+    }
+
+    #[test]
+    fn test_camera_degeneracy() {
+        let mut s = scene();
+        s.camera.target = s.camera.position;
+        s.camera.near = 0.0;
+        assert_eq!(paths(&s), ["/camera/target", "/camera/near"]);
+
+        let mut s = scene();
+        s.camera.up = [0.0, 0.0, 2.0];
+        s.camera.far = 0.05;
+        s.camera.fov_or_height = 180.0;
+        assert_eq!(paths(&s), ["/camera/up", "/camera/far", "/camera/fov_or_height"]);
+
+        let mut s = scene();
+        s.camera.position[1] = f32::INFINITY;
+        let errors = s.validate().unwrap_err();
+        assert_eq!(errors.errors[0].path, "/camera/position/1");
+        assert!(errors.to_string().contains("/camera/position/1: value inf is not finite"));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        for version in ["frustum/scene/v99", "frustum/scene/v0", "scene-1"] {
+            let mut s = scene();
+            s.version = version.to_string();
+            let errors = s.validate().unwrap_err().errors;
+            assert_eq!(errors[0].path, "/version");
+            assert_eq!(errors[0].kind, ValidationErrorKind::UnsupportedVersion(version.to_string()));
+        }
+    }
+}
@@ -0,0 +1,859 @@
+//! Volume file readers.
+//!
+//! Loads a `Volume` from common scientific formats: NRRD, NIfTI-1, raw binary
+//! with a JSON sidecar, and NumPy `.npy`. Spacing and origin are read from the
+//! headers where the format has them. Supported voxel types are u8, i16, u16,
+//! f32 and f64; samples are converted to f32.
+//!
+//! Grids are treated as axis-aligned: orientation matrices (NRRD space
+//! directions, NIfTI qform/sform) contribute only their per-axis scale and
+//! translation. Compressed encodings are reported as unsupported.
+//!
+//! Malformed headers and data sizes that do not match the declared
+//! dimensions produce a `VolumeIoError`, never a panic.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::marching_cubes::Volume;
+
+/// Errors that can occur while reading a volume file.
+#[derive(Error, Debug)]
+pub enum VolumeIoError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid {format} header: {message}")]
+    InvalidHeader { format: &'static str, message: String },
+    #[error("Unsupported {format} data type: {data_type}")]
+    UnsupportedDataType { format: &'static str, data_type: String },
+    #[error("Unsupported {format} encoding: {encoding}")]
+    UnsupportedEncoding { format: &'static str, encoding: String },
+    #[error("Expected a 3D volume, got dimensions {0:?}")]
+    InvalidDimensions(Vec<usize>),
+    #[error("Data size mismatch: dimensions require {expected} bytes, found {found}")]
+    SizeMismatch { expected: usize, found: usize },
+    #[error("Invalid JSON sidecar: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Voxel storage type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VoxelType {
+    U8,
+    I16,
+    U16,
+    F32,
+    F64,
+}
+
+impl VoxelType {
+    /// Size of one sample in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            VoxelType::U8 => 1,
+            VoxelType::I16 | VoxelType::U16 => 2,
+            VoxelType::F32 => 4,
+            VoxelType::F64 => 8,
+        }
+    }
+}
+
+/// Byte order of multi-byte samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+/// JSON sidecar describing a raw binary volume.
+///
+/// ```json
+/// { "data_file": "ct.raw", "dimensions": [256, 256, 128], "data_type": "i16",
+///   "spacing": [0.5, 0.5, 1.25], "origin": [0, 0, 0], "endian": "little" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawVolumeHeader {
+    /// Path of the raw data, relative to the sidecar.
+    pub data_file: String,
+    /// Dimensions (nx, ny, nz); samples are stored x-fastest.
+    pub dimensions: [usize; 3],
+    /// Voxel storage type.
+    pub data_type: VoxelType,
+    /// Grid spacing (dx, dy, dz).
+    #[serde(default = "unit_spacing")]
+    pub spacing: [f32; 3],
+    /// World-space origin of voxel [0,0,0].
+    #[serde(default)]
+    pub origin: [f32; 3],
+    /// Byte order of the samples.
+    #[serde(default)]
+    pub endian: Endian,
+    /// Bytes to skip at the start of the data file.
+    #[serde(default)]
+    pub header_bytes: usize,
+}
+
+fn unit_spacing() -> [f32; 3] {
+    [1.0; 3]
+}
+
+// ============================================================================
+// Shared helpers
+// ============================================================================
+
+/// Decode exactly `count` samples from `bytes`.
+fn decode_samples(
+    bytes: &[u8],
+    data_type: VoxelType,
+    endian: Endian,
+    count: usize,
+) -> Result<Vec<f32>, VolumeIoError> {
+    let expected = count * data_type.size();
+    if bytes.len() != expected {
+        return Err(VolumeIoError::SizeMismatch {
+            expected,
+            found: bytes.len(),
+        });
+    }
+
+    macro_rules! decode {
+        ($ty:ty) => {
+            bytes
+                .chunks_exact(std::mem::size_of::<$ty>())
+                .map(|b| {
+                    let b = b.try_into().unwrap();
+                    (match endian {
+                        Endian::Little => <$ty>::from_le_bytes(b),
+                        Endian::Big => <$ty>::from_be_bytes(b),
+                    }) as f32
+                })
+                .collect()
+        };
+    }
+
+    Ok(match data_type {
+        VoxelType::U8 => bytes.iter().map(|&b| b as f32).collect(),
+        VoxelType::I16 => decode!(i16),
+        VoxelType::U16 => decode!(u16),
+        VoxelType::F32 => decode!(f32),
+        VoxelType::F64 => decode!(f64),
+    })
+}
+
+/// Check that dimensions describe a non-empty 3D grid of addressable size.
+fn check_dimensions(dimensions: &[usize]) -> Result<[usize; 3], VolumeIoError> {
+    match dimensions {
+        // The byte size of the largest voxel type must fit in memory
+        &[nx, ny, nz]
+            if nx > 0
+                && ny > 0
+                && nz > 0
+                && nx.checked_mul(ny).and_then(|n| n.checked_mul(nz)).and_then(|n| n.checked_mul(8)).is_some() =>
+        {
+            Ok([nx, ny, nz])
+        }
+        _ => Err(VolumeIoError::InvalidDimensions(dimensions.to_vec())),
+    }
+}
+
+fn header_error(format: &'static str, message: impl Into<String>) -> VolumeIoError {
+    VolumeIoError::InvalidHeader {
+        format,
+        message: message.into(),
+    }
+}
+
+fn resolve_relative(base: &Path, file: &str) -> PathBuf {
+    base.parent().unwrap_or(Path::new("")).join(file)
+}
+
+// ============================================================================
+// Raw binary + JSON sidecar
+// ============================================================================
+
+/// Read a raw binary volume described by a JSON sidecar file.
+pub fn read_raw(sidecar_path: impl AsRef<Path>) -> Result<Volume, VolumeIoError> {
+    let sidecar_path = sidecar_path.as_ref();
+    let header: RawVolumeHeader = serde_json::from_str(&fs::read_to_string(sidecar_path)?)?;
+    let data = fs::read(resolve_relative(sidecar_path, &header.data_file))?;
+    parse_raw(&header, &data)
+}
+
+/// Build a volume from raw sample bytes and their header.
+pub fn parse_raw(header: &RawVolumeHeader, data: &[u8]) -> Result<Volume, VolumeIoError> {
+    let dimensions = check_dimensions(&header.dimensions)?;
+    let payload = data.get(header.header_bytes..).ok_or(VolumeIoError::SizeMismatch {
+        expected: header.header_bytes,
+        found: data.len(),
+    })?;
+    let count = dimensions.iter().product();
+    let values = decode_samples(payload, header.data_type, header.endian, count)?;
+    Ok(Volume::new(values, dimensions, header.spacing, header.origin))
+}
+
+// ============================================================================
+// NRRD
+// ============================================================================
+
+/// Read a NRRD file (`.nrrd` with attached data, or `.nhdr` with a detached data file).
+pub fn read_nrrd(path: impl AsRef<Path>) -> Result<Volume, VolumeIoError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    let (header, data_start) = NrrdHeader::parse(&bytes)?;
+    match &header.data_file {
+        Some(file) => {
+            let data = fs::read(resolve_relative(path, file))?;
+            header.into_volume(&data)
+        }
+        None => header.into_volume(&bytes[data_start..]),
+    }
+}
+
+/// Parse a NRRD file with attached data from memory.
+pub fn parse_nrrd(bytes: &[u8]) -> Result<Volume, VolumeIoError> {
+    let (header, data_start) = NrrdHeader::parse(bytes)?;
+    if header.data_file.is_some() {
+        return Err(header_error("NRRD", "detached data file requires read_nrrd"));
+    }
+    header.into_volume(&bytes[data_start..])
+}
+
+/// Fields of a NRRD header needed to build a volume.
+struct NrrdHeader {
+    data_type: VoxelType,
+    sizes: Vec<usize>,
+    encoding: String,
+    endian: Endian,
+    spacing: [f32; 3],
+    origin: [f32; 3],
+    byte_skip: usize,
+    data_file: Option<String>,
+}
+
+impl NrrdHeader {
+    /// Parse the header, returning it and the offset of attached data.
+    fn parse(bytes: &[u8]) -> Result<(Self, usize), VolumeIoError> {
+        if !bytes.starts_with(b"NRRD") {
+            return Err(header_error("NRRD", "missing NRRD magic"));
+        }
+
+        let mut header = NrrdHeader {
+            data_type: VoxelType::U8,
+            sizes: Vec::new(),
+            encoding: "raw".to_string(),
+            endian: Endian::Little,
+            spacing: [1.0; 3],
+            origin: [0.0; 3],
+            byte_skip: 0,
+            data_file: None,
+        };
+        let mut data_type = None;
+        let mut dimension = None;
+
+        // Header lines end at the first blank line (or the end of a detached header)
+        let mut offset = 0;
+        let mut first = true;
+        while offset < bytes.len() {
+            let end = bytes[offset..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(bytes.len(), |p| offset + p);
+            let line = std::str::from_utf8(&bytes[offset..end])
+                .map_err(|_| header_error("NRRD", "header is not valid UTF-8"))?
+                .trim_end_matches('\r');
+            offset = (end + 1).min(bytes.len());
+
+            if first {
+                first = false;
+                continue;
+            }
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with('#') || line.contains(":=") {
+                continue;
+            }
+
+            let (field, value) = line
+                .split_once(": ")
+                .ok_or_else(|| header_error("NRRD", format!("malformed line '{}'", line)))?;
+            let value = value.trim();
+            match field.to_ascii_lowercase().as_str() {
+                "type" => data_type = Some(nrrd_type(value)?),
+                "dimension" => {
+                    dimension = Some(
+                        value
+                            .parse::<usize>()
+                            .map_err(|_| header_error("NRRD", "invalid dimension"))?,
+                    )
+                }
+                "sizes" => {
+                    header.sizes = value
+                        .split_whitespace()
+                        .map(|s| s.parse::<usize>())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| header_error("NRRD", "invalid sizes"))?
+                }
+                "encoding" => header.encoding = value.to_ascii_lowercase(),
+                "endian" => {
+                    header.endian = match value {
+                        "little" => Endian::Little,
+                        "big" => Endian::Big,
+                        other => return Err(header_error("NRRD", format!("unknown endian '{}'", other))),
+                    }
+                }
+                "spacings" => header.spacing = parse_floats3("spacings", value.split_whitespace())?,
+                "space directions" => {
+                    // Axis-aligned: each direction's length is that axis's spacing
+                    let vectors = parse_vectors(value)?;
+                    if vectors.len() != 3 {
+                        return Err(header_error("NRRD", "expected three space directions"));
+                    }
+                    for (axis, v) in vectors.iter().enumerate() {
+                        header.spacing[axis] = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+                    }
+                }
+                "space origin" => {
+                    let vectors = parse_vectors(value)?;
+                    header.origin = *vectors
+                        .first()
+                        .ok_or_else(|| header_error("NRRD", "empty space origin"))?;
+                }
+                "byte skip" => {
+                    header.byte_skip = value
+                        .parse::<usize>()
+                        .map_err(|_| header_error("NRRD", "unsupported byte skip"))?
+                }
+                "data file" | "datafile" => header.data_file = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        header.data_type = data_type.ok_or_else(|| header_error("NRRD", "missing type"))?;
+        if dimension.is_some_and(|d| d != header.sizes.len()) {
+            return Err(header_error("NRRD", "dimension does not match sizes"));
+        }
+
+        Ok((header, offset))
+    }
+
+    fn into_volume(self, data: &[u8]) -> Result<Volume, VolumeIoError> {
+        let dimensions = check_dimensions(&self.sizes)?;
+        let count: usize = dimensions.iter().product();
+
+        let values = match self.encoding.as_str() {
+            "raw" => {
+                let payload = data.get(self.byte_skip..).ok_or(VolumeIoError::SizeMismatch {
+                    expected: self.byte_skip,
+                    found: data.len(),
+                })?;
+                decode_samples(payload, self.data_type, self.endian, count)?
+            }
+            "ascii" | "text" | "txt" => {
+                let text = std::str::from_utf8(data)
+                    .map_err(|_| header_error("NRRD", "ASCII data is not valid UTF-8"))?;
+                let values = text
+                    .split_whitespace()
+                    .map(|s| s.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| header_error("NRRD", "invalid ASCII sample"))?;
+                if values.len() != count {
+                    return Err(VolumeIoError::SizeMismatch {
+                        expected: count,
+                        found: values.len(),
+                    });
+                }
+                values
+            }
+            other => {
+                return Err(VolumeIoError::UnsupportedEncoding {
+                    format: "NRRD",
+                    encoding: other.to_string(),
+                })
+            }
+        };
+
+        Ok(Volume::new(values, dimensions, self.spacing, self.origin))
+    }
+}
+
+fn nrrd_type(name: &str) -> Result<VoxelType, VolumeIoError> {
+    match name {
+        "uchar" | "unsigned char" | "uint8" | "uint8_t" => Ok(VoxelType::U8),
+        "short" | "short int" | "signed short" | "signed short int" | "int16" | "int16_t" => {
+            Ok(VoxelType::I16)
+        }
+        "ushort" | "unsigned short" | "unsigned short int" | "uint16" | "uint16_t" => Ok(VoxelType::U16),
+        "float" => Ok(VoxelType::F32),
+        "double" => Ok(VoxelType::F64),
+        other => Err(VolumeIoError::UnsupportedDataType {
+            format: "NRRD",
+            data_type: other.to_string(),
+        }),
+    }
+}
+
+fn parse_floats3<'a>(
+    field: &str,
+    parts: impl Iterator<Item = &'a str>,
+) -> Result<[f32; 3], VolumeIoError> {
+    let values: Vec<f32> = parts
+        .map(|s| s.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| header_error("NRRD", format!("invalid {}", field)))?;
+    values
+        .try_into()
+        .map_err(|_| header_error("NRRD", format!("expected three values for {}", field)))
+}
+
+/// Parse vectors written as `(x,y,z) (x,y,z) ...`; `none` entries are skipped.
+fn parse_vectors(value: &str) -> Result<Vec<[f32; 3]>, VolumeIoError> {
+    let mut vectors = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('(') {
+        let end = rest[start..]
+            .find(')')
+            .ok_or_else(|| header_error("NRRD", "unterminated vector"))?;
+        vectors.push(parse_floats3("vector", rest[start + 1..start + end].split(','))?);
+        rest = &rest[start + end + 1..];
+    }
+    Ok(vectors)
+}
+
+// ============================================================================
+// NIfTI-1
+// ============================================================================
+
+/// Read a single-file NIfTI-1 volume (`.nii`).
+pub fn read_nifti(path: impl AsRef<Path>) -> Result<Volume, VolumeIoError> {
+    parse_nifti(&fs::read(path)?)
+}
+
+/// Parse a single-file NIfTI-1 volume from memory.
+///
+/// Spacing comes from `pixdim`, the origin from the qform offset (or the sform
+/// translation when only the sform is set). Intensity scaling
+/// (`scl_slope`, `scl_inter`) is applied when the slope is non-zero.
+pub fn parse_nifti(bytes: &[u8]) -> Result<Volume, VolumeIoError> {
+    const HEADER_SIZE: usize = 348;
+    if bytes.len() < HEADER_SIZE {
+        return Err(header_error("NIfTI", "file shorter than the 348-byte header"));
+    }
+
+    // sizeof_hdr is 348 in the file's byte order
+    let endian = if i32::from_le_bytes(bytes[0..4].try_into().unwrap()) == HEADER_SIZE as i32 {
+        Endian::Little
+    } else if i32::from_be_bytes(bytes[0..4].try_into().unwrap()) == HEADER_SIZE as i32 {
+        Endian::Big
+    } else {
+        return Err(header_error("NIfTI", "sizeof_hdr is not 348"));
+    };
+    if &bytes[344..348] != b"n+1\0" {
+        return Err(header_error("NIfTI", "not a single-file NIfTI-1 (magic 'n+1')"));
+    }
+
+    let i16_at = |offset: usize| {
+        let b = bytes[offset..offset + 2].try_into().unwrap();
+        match endian {
+            Endian::Little => i16::from_le_bytes(b),
+            Endian::Big => i16::from_be_bytes(b),
+        }
+    };
+    let f32_at = |offset: usize| {
+        let b = bytes[offset..offset + 4].try_into().unwrap();
+        match endian {
+            Endian::Little => f32::from_le_bytes(b),
+            Endian::Big => f32::from_be_bytes(b),
+        }
+    };
+
+    // dim[0] is the rank; trailing singleton dimensions are allowed
+    let rank = i16_at(40);
+    let dims: Vec<i16> = (1..=rank.clamp(0, 7) as usize).map(|i| i16_at(40 + 2 * i)).collect();
+    if rank < 3 || dims.iter().any(|&d| d < 1) || dims[3..].iter().any(|&d| d != 1) {
+        return Err(VolumeIoError::InvalidDimensions(
+            dims.iter().map(|&d| d.max(0) as usize).collect(),
+        ));
+    }
+    let dimensions = check_dimensions(&[dims[0] as usize, dims[1] as usize, dims[2] as usize])?;
+
+    let data_type = match i16_at(70) {
+        2 => VoxelType::U8,
+        4 => VoxelType::I16,
+        16 => VoxelType::F32,
+        64 => VoxelType::F64,
+        512 => VoxelType::U16,
+        other => {
+            return Err(VolumeIoError::UnsupportedDataType {
+                format: "NIfTI",
+                data_type: format!("datatype code {}", other),
+            })
+        }
+    };
+
+    let spacing = [1, 2, 3].map(|i| {
+        let d = f32_at(76 + 4 * i).abs();
+        if d > 0.0 {
+            d
+        } else {
+            1.0
+        }
+    });
+
+    let (qform_code, sform_code) = (i16_at(252), i16_at(254));
+    let origin = if qform_code > 0 {
+        [f32_at(268), f32_at(272), f32_at(276)]
+    } else if sform_code > 0 {
+        [f32_at(292), f32_at(308), f32_at(324)]
+    } else {
+        [0.0; 3]
+    };
+
+    let vox_offset = f32_at(108);
+    if vox_offset.is_nan() || vox_offset < HEADER_SIZE as f32 || vox_offset as usize > bytes.len() {
+        return Err(header_error("NIfTI", format!("invalid vox_offset {}", vox_offset)));
+    }
+    let count: usize = dimensions.iter().product();
+    let mut values = decode_samples(&bytes[vox_offset as usize..], data_type, endian, count)?;
+
+    let (slope, intercept) = (f32_at(112), f32_at(116));
+    if slope != 0.0 && slope.is_finite() && (slope != 1.0 || intercept != 0.0) {
+        for v in values.iter_mut() {
+            *v = *v * slope + intercept;
+        }
+    }
+
+    Ok(Volume::new(values, dimensions, spacing, origin))
+}
+
+// ============================================================================
+// NumPy .npy
+// ============================================================================
+
+/// Read a 3D NumPy `.npy` array.
+///
+/// Element `a[i, j, k]` becomes voxel (x = i, y = j, z = k) for both C and
+/// Fortran order. `.npy` carries no geometry, so spacing is 1 and the origin is 0.
+pub fn read_npy(path: impl AsRef<Path>) -> Result<Volume, VolumeIoError> {
+    parse_npy(&fs::read(path)?)
+}
+
+/// Parse a 3D NumPy `.npy` array from memory (see `read_npy`).
+pub fn parse_npy(bytes: &[u8]) -> Result<Volume, VolumeIoError> {
+    if bytes.len() < 10 || !bytes.starts_with(b"\x93NUMPY") {
+        return Err(header_error("NPY", "missing NUMPY magic"));
+    }
+
+    // Version 1 uses a u16 header length, versions 2 and 3 a u32
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize, 12),
+        version => return Err(header_error("NPY", format!("unsupported version {}", version))),
+    };
+    let header_end = header_start + header_len;
+    let header = bytes
+        .get(header_start..header_end)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or_else(|| header_error("NPY", "truncated or non-UTF-8 header"))?;
+
+    let descr = npy_field(header, "descr")?.trim_matches(|c| c == '\'' || c == '"');
+    let fortran_order = match npy_field(header, "fortran_order")? {
+        "True" => true,
+        "False" => false,
+        other => return Err(header_error("NPY", format!("invalid fortran_order '{}'", other))),
+    };
+    let shape: Vec<usize> = npy_field(header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| header_error("NPY", "invalid shape"))?;
+    let dimensions = check_dimensions(&shape)?;
+
+    let (endian, type_code) = if let Some(code) = descr.strip_prefix('>') {
+        (Endian::Big, code)
+    } else {
+        (Endian::Little, descr.trim_start_matches(['<', '|', '=']))
+    };
+    let data_type = match type_code {
+        "u1" => VoxelType::U8,
+        "i2" => VoxelType::I16,
+        "u2" => VoxelType::U16,
+        "f4" => VoxelType::F32,
+        "f8" => VoxelType::F64,
+        _ => {
+            return Err(VolumeIoError::UnsupportedDataType {
+                format: "NPY",
+                data_type: descr.to_string(),
+            })
+        }
+    };
+
+    let count: usize = dimensions.iter().product();
+    let samples = decode_samples(&bytes[header_end..], data_type, endian, count)?;
+
+    // Fortran order is already x-fastest; C order has the last index fastest
+    let values = if fortran_order {
+        samples
+    } else {
+        let [nx, ny, nz] = dimensions;
+        let mut values = vec![0.0; count];
+        for (c_index, &v) in samples.iter().enumerate() {
+            let k = c_index % nz;
+            let j = (c_index / nz) % ny;
+            let i = c_index / (ny * nz);
+            values[i + j * nx + k * nx * ny] = v;
+        }
+        values
+    };
+
+    Ok(Volume::new(values, dimensions, [1.0; 3], [0.0; 3]))
+}
+
+/// Raw text of one value in the `.npy` header dictionary.
+fn npy_field<'a>(header: &'a str, key: &str) -> Result<&'a str, VolumeIoError> {
+    let missing = || header_error("NPY", format!("missing '{}'", key));
+    let key_start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))
+        .ok_or_else(missing)?;
+    let rest = &header[key_start + key.len() + 2..];
+    let rest = rest.trim_start().strip_prefix(':').ok_or_else(missing)?.trim_start();
+
+    // Tuples contain commas, so they end at the closing parenthesis
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|p| p + 1)
+    } else {
+        rest.find([',', '}'])
+    }
+    .ok_or_else(missing)?;
+    Ok(rest[..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values of a small 3x4x2 test volume at (x, y, z), x-fastest.
+    fn sample(x: usize, y: usize, z: usize) -> f32 {
+        (x + 10 * y + 100 * z) as f32
+    }
+
+    fn expected_values() -> Vec<f32> {
+        let mut values = Vec::new();
+        for z in 0..2 {
+            for y in 0..4 {
+                for x in 0..3 {
+                    values.push(sample(x, y, z));
+                }
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn test_raw_all_types() {
+        let values = expected_values();
+        let cases: [(VoxelType, Endian, Vec<u8>); 5] = [
+            (VoxelType::U8, Endian::Little, values.iter().map(|&v| v as u8).collect()),
+            (VoxelType::I16, Endian::Big, values.iter().flat_map(|&v| (v as i16).to_be_bytes()).collect()),
+            (VoxelType::U16, Endian::Little, values.iter().flat_map(|&v| (v as u16).to_le_bytes()).collect()),
+            (VoxelType::F32, Endian::Little, values.iter().flat_map(|&v| v.to_le_bytes()).collect()),
+            (VoxelType::F64, Endian::Big, values.iter().flat_map(|&v| (v as f64).to_be_bytes()).collect()),
+        ];
+
+        for (data_type, endian, bytes) in cases {
+            let header = RawVolumeHeader {
+                data_file: "unused.raw".to_string(),
+                dimensions: [3, 4, 2],
+                data_type,
+                spacing: [0.5, 1.0, 2.0],
+                origin: [1.0, 2.0, 3.0],
+                endian,
+                header_bytes: 0,
+            };
+            let volume = parse_raw(&header, &bytes).unwrap();
+            assert_eq!(volume.values, values, "{:?}", data_type);
+            assert_eq!(volume.spacing, [0.5, 1.0, 2.0]);
+            assert_eq!(volume.origin, [1.0, 2.0, 3.0]);
+        }
+    }
+
+    #[test]
+    fn test_raw_sidecar_file() {
+        let dir = std::env::temp_dir().join(format!("frustum-volume-io-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let bytes: Vec<u8> = expected_values().iter().flat_map(|&v| (v as u16).to_le_bytes()).collect();
+        fs::write(dir.join("volume.raw"), &bytes).unwrap();
+        fs::write(
+            dir.join("volume.json"),
+            r#"{ "data_file": "volume.raw", "dimensions": [3, 4, 2], "data_type": "u16", "spacing": [1, 1, 0.5] }"#,
+        )
+        .unwrap();
+
+        let volume = read_raw(dir.join("volume.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(volume.values, expected_values());
+        assert_eq!(volume.spacing, [1.0, 1.0, 0.5]);
+        assert_eq!(volume.origin, [0.0; 3]);
+    }
+
+    #[test]
+    fn test_size_mismatch_is_error() {
+        let header = RawVolumeHeader {
+            data_file: "unused.raw".to_string(),
+            dimensions: [3, 4, 2],
+            data_type: VoxelType::F32,
+            spacing: [1.0; 3],
+            origin: [0.0; 3],
+            endian: Endian::Little,
+            header_bytes: 0,
+        };
+        let result = parse_raw(&header, &[0u8; 4 * 23]);
+        assert!(matches!(result, Err(VolumeIoError::SizeMismatch { expected: 96, found: 92 })));
+
+        let header = RawVolumeHeader {
+            dimensions: [3, 0, 2],
+            ..header
+        };
+        assert!(matches!(parse_raw(&header, &[]), Err(VolumeIoError::InvalidDimensions(_))));
+    }
+
+    #[test]
+    fn test_nrrd_attached() {
+        let mut bytes = b"NRRD0004\n# comment\ntype: short\ndimension: 3\nsizes: 3 4 2\n\
+            space: left-posterior-superior\nspace directions: (0.5,0,0) (0,0.75,0) (0,0,2)\n\
+            space origin: (-1,2.5,10)\nendian: big\nencoding: raw\n\n"
+            .to_vec();
+        bytes.extend(expected_values().iter().flat_map(|&v| (v as i16).to_be_bytes()));
+
+        let volume = parse_nrrd(&bytes).unwrap();
+        assert_eq!(volume.dimensions, [3, 4, 2]);
+        assert_eq!(volume.values, expected_values());
+        assert_eq!(volume.spacing, [0.5, 0.75, 2.0]);
+        assert_eq!(volume.origin, [-1.0, 2.5, 10.0]);
+    }
+
+    #[test]
+    fn test_nrrd_errors() {
+        let header = b"NRRD0004\ntype: float\ndimension: 3\nsizes: 3 4 2\nencoding: raw\n\n";
+        let mut truncated = header.to_vec();
+        truncated.extend([0u8; 10]);
+        assert!(matches!(parse_nrrd(&truncated), Err(VolumeIoError::SizeMismatch { .. })));
+
+        let gzip = b"NRRD0004\ntype: float\ndimension: 3\nsizes: 3 4 2\nencoding: gzip\n\n";
+        assert!(matches!(parse_nrrd(gzip), Err(VolumeIoError::UnsupportedEncoding { .. })));
+
+        let four_d = b"NRRD0004\ntype: float\ndimension: 4\nsizes: 3 4 2 2\nencoding: raw\n\n";
+        assert!(matches!(parse_nrrd(four_d), Err(VolumeIoError::InvalidDimensions(_))));
+
+        let long = b"NRRD0004\ntype: long\ndimension: 3\nsizes: 3 4 2\n\n";
+        assert!(matches!(parse_nrrd(long), Err(VolumeIoError::UnsupportedDataType { .. })));
+    }
+
+    /// Minimal single-file NIfTI-1 header with the given datatype code and data.
+    fn nifti_bytes(datatype: i16, bitpix: i16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 352];
+        bytes[0..4].copy_from_slice(&348i32.to_le_bytes());
+        for (i, d) in [3i16, 3, 4, 2, 1, 1, 1, 1].iter().enumerate() {
+            bytes[40 + 2 * i..42 + 2 * i].copy_from_slice(&d.to_le_bytes());
+        }
+        bytes[70..72].copy_from_slice(&datatype.to_le_bytes());
+        bytes[72..74].copy_from_slice(&bitpix.to_le_bytes());
+        for (i, p) in [1.0f32, 0.8, 0.9, 2.5].iter().enumerate() {
+            bytes[76 + 4 * i..80 + 4 * i].copy_from_slice(&p.to_le_bytes());
+        }
+        bytes[108..112].copy_from_slice(&352.0f32.to_le_bytes());
+        bytes[252..254].copy_from_slice(&1i16.to_le_bytes());
+        for (i, o) in [-10.0f32, -20.0, 5.0].iter().enumerate() {
+            bytes[268 + 4 * i..272 + 4 * i].copy_from_slice(&o.to_le_bytes());
+        }
+        bytes[344..348].copy_from_slice(b"n+1\0");
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_nifti() {
+        let values = expected_values();
+        let data: Vec<u8> = values.iter().flat_map(|&v| (v as u16).to_le_bytes()).collect();
+        let volume = parse_nifti(&nifti_bytes(512, 16, &data)).unwrap();
+        assert_eq!(volume.dimensions, [3, 4, 2]);
+        assert_eq!(volume.values, values);
+        assert_eq!(volume.spacing, [0.8, 0.9, 2.5]);
+        assert_eq!(volume.origin, [-10.0, -20.0, 5.0]);
+
+        // Intensity scaling
+        let data: Vec<u8> = values.iter().map(|&v| v as u8).collect();
+        let mut bytes = nifti_bytes(2, 8, &data);
+        bytes[112..116].copy_from_slice(&2.0f32.to_le_bytes());
+        bytes[116..120].copy_from_slice(&(-1.0f32).to_le_bytes());
+        let scaled = parse_nifti(&bytes).unwrap();
+        assert_eq!(scaled.values[5], values[5] * 2.0 - 1.0);
+
+        // Missing samples
+        let result = parse_nifti(&nifti_bytes(16, 32, &[0u8; 40]));
+        assert!(matches!(result, Err(VolumeIoError::SizeMismatch { expected: 96, found: 40 })));
+    }
+
+    fn npy_bytes(descr: &str, fortran_order: bool, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+            descr,
+            if fortran_order { "True" } else { "False" },
+            shape
+        );
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn test_npy_c_and_fortran_order() {
+        // Fortran order: a[i, j, k] with i fastest, the same layout as Volume
+        let fortran: Vec<u8> = expected_values().iter().flat_map(|&v| v.to_le_bytes()).collect();
+        let volume = parse_npy(&npy_bytes("<f4", true, "(3, 4, 2)", &fortran)).unwrap();
+        assert_eq!(volume.dimensions, [3, 4, 2]);
+        assert_eq!(volume.values, expected_values());
+
+        // C order: a[i, j, k] with k fastest
+        let mut c_order = Vec::new();
+        for i in 0..3 {
+            for j in 0..4 {
+                for k in 0..2 {
+                    c_order.extend((sample(i, j, k) as f64).to_be_bytes());
+                }
+            }
+        }
+        let volume = parse_npy(&npy_bytes(">f8", false, "(3, 4, 2)", &c_order)).unwrap();
+        assert_eq!(volume.values, expected_values());
+        assert_eq!(volume.spacing, [1.0; 3]);
+    }
+
+    #[test]
+    fn test_npy_errors() {
+        let two_d = npy_bytes("|u1", false, "(3, 4)", &[0u8; 12]);
+        assert!(matches!(parse_npy(&two_d), Err(VolumeIoError::InvalidDimensions(_))));
+
+        let short = npy_bytes("<i2", false, "(3, 4, 2)", &[0u8; 10]);
+        assert!(matches!(parse_npy(&short), Err(VolumeIoError::SizeMismatch { expected: 48, found: 10 })));
+
+        let complex = npy_bytes("<c8", false, "(3, 4, 2)", &[0u8; 192]);
+        assert!(matches!(parse_npy(&complex), Err(VolumeIoError::UnsupportedDataType { .. })));
+    }
+}
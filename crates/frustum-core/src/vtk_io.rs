@@ -0,0 +1,1551 @@
+//! VTK file readers.
+//!
+//! Loads ParaView/VTK data as Frustum objects: legacy `.vtk` files (ASCII and
+//! binary) and the XML formats `.vti` (image data), `.vtr` (rectilinear grid),
+//! `.vtp` (poly data) and `.vtu` (unstructured grid).
+//!
+//! Image data and rectilinear grids become a `Volume`. Poly data and
+//! unstructured grids become a `VtkPolyData`: vertex cells give a point cloud,
+//! line cells give polylines, and polygons, strips and the boundary faces of
+//! linear 3D cells give a triangle mesh.
+//!
+//! A point-data array supplies the scalars: the one named in
+//! `VtkReadOptions`, else the active scalars, else the first array.
+//! Multi-component arrays are reduced to their magnitude unless a component
+//! is selected. Cell data, structured grids, quadratic cells and compressed
+//! XML data are not supported. Malformed input produces a `VtkIoError`,
+//! never a panic.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::geometry::{Mesh, PointCloud, Polyline};
+use crate::marching_cubes::Volume;
+
+/// Errors that can occur while reading a VTK file.
+#[derive(Error, Debug)]
+pub enum VtkIoError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid {format} data: {message}")]
+    Parse { format: &'static str, message: String },
+    #[error("Unsupported {format} feature: {feature}")]
+    Unsupported { format: &'static str, feature: String },
+    #[error("No point-data array named {0:?}")]
+    MissingArray(String),
+    #[error("Unknown VTK file extension: {0:?}")]
+    UnknownFormat(String),
+}
+
+/// Options for reading VTK files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VtkReadOptions {
+    /// Point-data array used as scalars. `None` uses the active scalars, or
+    /// the first array if none is marked active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scalars: Option<String>,
+    /// Component of a multi-component array. `None` uses the magnitude.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<usize>,
+}
+
+impl VtkReadOptions {
+    /// Read the default scalars.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select the point-data array used as scalars.
+    pub fn with_scalars(mut self, name: impl Into<String>) -> Self {
+        self.scalars = Some(name.into());
+        self
+    }
+
+    /// Select one component of a multi-component array.
+    pub fn with_component(mut self, component: usize) -> Self {
+        self.component = Some(component);
+        self
+    }
+}
+
+/// Geometry read from VTK poly data or an unstructured grid.
+#[derive(Debug, Clone, Default)]
+pub struct VtkPolyData {
+    /// Triangulated surface cells, with all dataset points as vertices.
+    pub mesh: Option<Mesh>,
+    /// Points of vertex cells, or every point if the dataset has no cells.
+    pub points: Option<PointCloud>,
+    /// One polyline per line cell.
+    pub polylines: Vec<Polyline>,
+}
+
+/// Contents of a VTK file.
+#[derive(Debug, Clone)]
+pub enum VtkDataset {
+    Volume(Volume),
+    PolyData(VtkPolyData),
+}
+
+impl VtkDataset {
+    /// The volume, if the file holds image data or a rectilinear grid.
+    pub fn into_volume(self) -> Option<Volume> {
+        match self {
+            VtkDataset::Volume(volume) => Some(volume),
+            VtkDataset::PolyData(_) => None,
+        }
+    }
+
+    /// The geometry, if the file holds poly data or an unstructured grid.
+    pub fn into_poly_data(self) -> Option<VtkPolyData> {
+        match self {
+            VtkDataset::PolyData(data) => Some(data),
+            VtkDataset::Volume(_) => None,
+        }
+    }
+}
+
+/// Read a VTK file, choosing legacy or XML parsing from the extension.
+pub fn read_vtk(path: impl AsRef<Path>, options: &VtkReadOptions) -> Result<VtkDataset, VtkIoError> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "vtk" => parse_vtk_legacy(&fs::read(path)?, options),
+        "vti" | "vtr" | "vtp" | "vtu" => parse_vtk_xml(&fs::read(path)?, options),
+        _ => Err(VtkIoError::UnknownFormat(extension)),
+    }
+}
+
+// ============================================================================
+// Shared dataset model
+// ============================================================================
+
+const LEGACY: &str = "VTK";
+const XML: &str = "VTK XML";
+
+// Mesh point sizes and line widths for the created primitives
+const POINT_SIZE: f32 = 4.0;
+const LINE_WIDTH: f32 = 1.0;
+
+fn parse_error(format: &'static str, message: impl Into<String>) -> VtkIoError {
+    VtkIoError::Parse {
+        format,
+        message: message.into(),
+    }
+}
+
+/// `a * b` for sizes declared in the file; overflow is a parse error.
+fn product(format: &'static str, a: usize, b: usize) -> Result<usize, VtkIoError> {
+    a.checked_mul(b)
+        .ok_or_else(|| parse_error(format, format!("declared size {} x {} is too large", a, b)))
+}
+
+fn unsupported(format: &'static str, feature: impl Into<String>) -> VtkIoError {
+    VtkIoError::Unsupported {
+        format,
+        feature: feature.into(),
+    }
+}
+
+/// Scalar type of a data array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VtkType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+impl VtkType {
+    fn from_legacy(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "char" => VtkType::I8,
+            "unsigned_char" => VtkType::U8,
+            "short" => VtkType::I16,
+            "unsigned_short" => VtkType::U16,
+            "int" => VtkType::I32,
+            "unsigned_int" => VtkType::U32,
+            "long" | "vtktypeint64" | "vtkidtype" => VtkType::I64,
+            "unsigned_long" | "vtktypeuint64" => VtkType::U64,
+            "float" => VtkType::F32,
+            "double" => VtkType::F64,
+            _ => return None,
+        })
+    }
+
+    fn from_xml(name: &str) -> Option<Self> {
+        Some(match name {
+            "Int8" => VtkType::I8,
+            "UInt8" => VtkType::U8,
+            "Int16" => VtkType::I16,
+            "UInt16" => VtkType::U16,
+            "Int32" => VtkType::I32,
+            "UInt32" => VtkType::U32,
+            "Int64" => VtkType::I64,
+            "UInt64" => VtkType::U64,
+            "Float32" => VtkType::F32,
+            "Float64" => VtkType::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            VtkType::I8 | VtkType::U8 => 1,
+            VtkType::I16 | VtkType::U16 => 2,
+            VtkType::I32 | VtkType::U32 | VtkType::F32 => 4,
+            VtkType::I64 | VtkType::U64 | VtkType::F64 => 8,
+        }
+    }
+}
+
+/// Decode `count` packed samples.
+fn decode(format: &'static str, bytes: &[u8], ty: VtkType, big_endian: bool, count: usize) -> Result<Vec<f64>, VtkIoError> {
+    let size = ty.size();
+    let needed = count.checked_mul(size).filter(|&n| n <= bytes.len()).ok_or_else(|| {
+        parse_error(format, format!("expected {} values, found {} bytes", count, bytes.len()))
+    })?;
+
+    macro_rules! decode_as {
+        ($t:ty) => {
+            bytes[..needed]
+                .chunks_exact(size)
+                .map(|b| {
+                    let b = b.try_into().unwrap();
+                    (if big_endian { <$t>::from_be_bytes(b) } else { <$t>::from_le_bytes(b) }) as f64
+                })
+                .collect()
+        };
+    }
+    Ok(match ty {
+        VtkType::I8 => bytes[..needed].iter().map(|&b| b as i8 as f64).collect(),
+        VtkType::U8 => bytes[..needed].iter().map(|&b| b as f64).collect(),
+        VtkType::I16 => decode_as!(i16),
+        VtkType::U16 => decode_as!(u16),
+        VtkType::I32 => decode_as!(i32),
+        VtkType::U32 => decode_as!(u32),
+        VtkType::I64 => decode_as!(i64),
+        VtkType::U64 => decode_as!(u64),
+        VtkType::F32 => decode_as!(f32),
+        VtkType::F64 => decode_as!(f64),
+    })
+}
+
+/// A named point-data array with interleaved components.
+struct PointArray {
+    name: String,
+    components: usize,
+    values: Vec<f64>,
+}
+
+enum Geometry {
+    Image {
+        dimensions: [usize; 3],
+        origin: [f32; 3],
+        spacing: [f32; 3],
+    },
+    Rectilinear([Vec<f32>; 3]),
+    /// Points and cells as (VTK cell type, point ids).
+    Cells { points: Vec<f32>, cells: Vec<(u8, Vec<u32>)> },
+}
+
+/// A parsed file before conversion to Frustum objects.
+struct RawDataset {
+    geometry: Geometry,
+    arrays: Vec<PointArray>,
+    active_scalars: Option<String>,
+    active_normals: Option<String>,
+}
+
+// VTK cell types
+const VTK_VERTEX: u8 = 1;
+const VTK_POLY_VERTEX: u8 = 2;
+const VTK_LINE: u8 = 3;
+const VTK_POLY_LINE: u8 = 4;
+const VTK_TRIANGLE: u8 = 5;
+const VTK_TRIANGLE_STRIP: u8 = 6;
+const VTK_POLYGON: u8 = 7;
+const VTK_PIXEL: u8 = 8;
+const VTK_QUAD: u8 = 9;
+const VTK_TETRA: u8 = 10;
+const VTK_VOXEL: u8 = 11;
+const VTK_HEXAHEDRON: u8 = 12;
+const VTK_WEDGE: u8 = 13;
+const VTK_PYRAMID: u8 = 14;
+
+/// Outward faces of the linear 3D cells, in VTK point order.
+fn cell_faces(cell_type: u8) -> &'static [&'static [usize]] {
+    match cell_type {
+        VTK_TETRA => &[&[0, 1, 3], &[1, 2, 3], &[2, 0, 3], &[0, 2, 1]],
+        VTK_VOXEL => &[&[0, 4, 6, 2], &[1, 3, 7, 5], &[0, 1, 5, 4], &[2, 6, 7, 3], &[0, 2, 3, 1], &[4, 5, 7, 6]],
+        VTK_HEXAHEDRON => &[&[0, 4, 7, 3], &[1, 2, 6, 5], &[0, 1, 5, 4], &[3, 7, 6, 2], &[0, 3, 2, 1], &[4, 5, 6, 7]],
+        VTK_WEDGE => &[&[0, 1, 2], &[3, 5, 4], &[0, 3, 4, 1], &[1, 4, 5, 2], &[2, 5, 3, 0]],
+        VTK_PYRAMID => &[&[0, 3, 2, 1], &[0, 1, 4], &[1, 2, 4], &[2, 3, 4], &[3, 0, 4]],
+        _ => &[],
+    }
+}
+
+/// Number of points of fixed-size cell types.
+fn cell_size(cell_type: u8) -> Option<usize> {
+    match cell_type {
+        VTK_VERTEX => Some(1),
+        VTK_LINE => Some(2),
+        VTK_TRIANGLE => Some(3),
+        VTK_PIXEL | VTK_QUAD | VTK_TETRA => Some(4),
+        VTK_PYRAMID => Some(5),
+        VTK_WEDGE => Some(6),
+        VTK_VOXEL | VTK_HEXAHEDRON => Some(8),
+        _ => None,
+    }
+}
+
+fn fan(face: &[u32], indices: &mut Vec<u32>) {
+    for k in 1..face.len().saturating_sub(1) {
+        indices.extend_from_slice(&[face[0], face[k], face[k + 1]]);
+    }
+}
+
+impl RawDataset {
+    fn point_count(&self, format: &'static str) -> Result<usize, VtkIoError> {
+        let dimensions = match &self.geometry {
+            Geometry::Image { dimensions, .. } => *dimensions,
+            Geometry::Rectilinear(coordinates) => [0, 1, 2].map(|a| coordinates[a].len()),
+            Geometry::Cells { points, .. } => return Ok(points.len() / 3),
+        };
+        product(format, product(format, dimensions[0], dimensions[1])?, dimensions[2])
+    }
+
+    fn array(&self, name: &str) -> Option<&PointArray> {
+        self.arrays.iter().find(|a| a.name == name)
+    }
+
+    /// The selected scalar array, reduced to one value per point.
+    fn scalars(&self, format: &'static str, options: &VtkReadOptions) -> Result<Option<Vec<f32>>, VtkIoError> {
+        let array = match &options.scalars {
+            Some(name) => Some(self.array(name).ok_or_else(|| VtkIoError::MissingArray(name.clone()))?),
+            None => self.active_scalars.as_deref().and_then(|name| self.array(name)).or_else(|| {
+                self.arrays
+                    .iter()
+                    .find(|a| Some(a.name.as_str()) != self.active_normals.as_deref())
+            }),
+        };
+        let Some(array) = array else {
+            return Ok(None);
+        };
+
+        let values = match options.component {
+            Some(c) if c >= array.components => {
+                return Err(parse_error(
+                    format,
+                    format!("array {:?} has {} components, component {} requested", array.name, array.components, c),
+                ));
+            }
+            Some(c) => array.values.iter().skip(c).step_by(array.components).map(|&v| v as f32).collect(),
+            None if array.components == 1 => array.values.iter().map(|&v| v as f32).collect(),
+            None => array
+                .values
+                .chunks_exact(array.components)
+                .map(|t| t.iter().map(|v| v * v).sum::<f64>().sqrt() as f32)
+                .collect(),
+        };
+        Ok(Some(values))
+    }
+
+    fn into_dataset(self, format: &'static str, options: &VtkReadOptions) -> Result<VtkDataset, VtkIoError> {
+        let count = self.point_count(format)?;
+        for array in &self.arrays {
+            if array.components == 0 || array.values.len() != product(format, count, array.components)? {
+                return Err(parse_error(
+                    format,
+                    format!("array {:?} has {} values for {} points", array.name, array.values.len(), count),
+                ));
+            }
+        }
+        let scalars = self.scalars(format, options)?;
+        let normals = self
+            .active_normals
+            .as_deref()
+            .and_then(|name| self.array(name))
+            .filter(|a| a.components == 3)
+            .map(|a| a.values.iter().map(|&v| v as f32).collect::<Vec<f32>>());
+
+        match self.geometry {
+            Geometry::Image {
+                dimensions,
+                origin,
+                spacing,
+            } => {
+                let values = scalars.ok_or_else(|| parse_error(format, "image data without point-data arrays"))?;
+                Ok(VtkDataset::Volume(Volume::new(values, dimensions, spacing, origin)))
+            }
+            Geometry::Rectilinear([x, y, z]) => {
+                let values = scalars.ok_or_else(|| parse_error(format, "rectilinear grid without point-data arrays"))?;
+                for axis in [&x, &y, &z] {
+                    if axis.is_empty() || !axis.windows(2).all(|w| w[0] < w[1]) || !axis.iter().all(|c| c.is_finite()) {
+                        return Err(parse_error(format, "grid coordinates must be finite and strictly increasing"));
+                    }
+                }
+                Ok(VtkDataset::Volume(Volume::rectilinear(values, x, y, z)))
+            }
+            Geometry::Cells { points, cells } => build_poly_data(format, points, &cells, scalars, normals),
+        }
+    }
+}
+
+fn build_poly_data(
+    format: &'static str,
+    points: Vec<f32>,
+    cells: &[(u8, Vec<u32>)],
+    scalars: Option<Vec<f32>>,
+    normals: Option<Vec<f32>>,
+) -> Result<VtkDataset, VtkIoError> {
+    let count = points.len() / 3;
+    let mut indices = Vec::new();
+    let mut point_ids = Vec::new();
+    let mut seen = vec![false; count];
+    let mut lines: Vec<&[u32]> = Vec::new();
+    let mut solid_faces: Vec<Vec<u32>> = Vec::new();
+
+    for (cell_type, ids) in cells {
+        if let Some(&bad) = ids.iter().find(|&&i| i as usize >= count) {
+            return Err(parse_error(format, format!("cell references point {} of {}", bad, count)));
+        }
+        if cell_size(*cell_type).is_some_and(|n| n != ids.len()) {
+            return Err(parse_error(format, format!("cell type {} with {} points", cell_type, ids.len())));
+        }
+        match *cell_type {
+            VTK_VERTEX | VTK_POLY_VERTEX => {
+                for &id in ids {
+                    if !seen[id as usize] {
+                        seen[id as usize] = true;
+                        point_ids.push(id);
+                    }
+                }
+            }
+            VTK_LINE | VTK_POLY_LINE if ids.len() >= 2 => lines.push(ids),
+            VTK_LINE | VTK_POLY_LINE => {}
+            VTK_TRIANGLE | VTK_POLYGON | VTK_QUAD => fan(ids, &mut indices),
+            VTK_PIXEL => fan(&[ids[0], ids[1], ids[3], ids[2]], &mut indices),
+            VTK_TRIANGLE_STRIP => {
+                for k in 0..ids.len().saturating_sub(2) {
+                    if k % 2 == 0 {
+                        indices.extend_from_slice(&[ids[k], ids[k + 1], ids[k + 2]]);
+                    } else {
+                        indices.extend_from_slice(&[ids[k + 1], ids[k], ids[k + 2]]);
+                    }
+                }
+            }
+            VTK_TETRA | VTK_VOXEL | VTK_HEXAHEDRON | VTK_WEDGE | VTK_PYRAMID => {
+                for face in cell_faces(*cell_type) {
+                    solid_faces.push(face.iter().map(|&k| ids[k]).collect());
+                }
+            }
+            other => return Err(unsupported(format, format!("cell type {}", other))),
+        }
+    }
+
+    // Faces of 3D cells used once lie on the boundary
+    let mut face_count: HashMap<Vec<u32>, usize> = HashMap::new();
+    for face in &solid_faces {
+        let mut key = face.clone();
+        key.sort_unstable();
+        *face_count.entry(key).or_insert(0) += 1;
+    }
+    for face in &solid_faces {
+        let mut key = face.clone();
+        key.sort_unstable();
+        if face_count[&key] == 1 {
+            fan(face, &mut indices);
+        }
+    }
+
+    // A dataset without cells is a plain point set
+    if cells.is_empty() {
+        point_ids = (0..count as u32).collect();
+    }
+
+    let gather = |ids: &[u32], values: &[f32], n: usize| -> Vec<f32> {
+        ids.iter().flat_map(|&i| values[i as usize * n..i as usize * n + n].to_vec()).collect()
+    };
+    let mut data = VtkPolyData::default();
+    for ids in lines {
+        let mut line = Polyline::new(gather(ids, &points, 3), LINE_WIDTH);
+        if let Some(scalars) = &scalars {
+            line = line.with_scalars(gather(ids, scalars, 1));
+        }
+        data.polylines.push(line);
+    }
+    if !point_ids.is_empty() {
+        let mut pc = PointCloud::new(gather(&point_ids, &points, 3), POINT_SIZE);
+        if let Some(scalars) = &scalars {
+            pc = pc.with_scalars(gather(&point_ids, scalars, 1));
+        }
+        data.points = Some(pc);
+    }
+    if !indices.is_empty() {
+        let mut mesh = Mesh::new(points, indices);
+        if let Some(normals) = normals {
+            mesh = mesh.with_normals(normals);
+        }
+        if let Some(scalars) = scalars {
+            mesh = mesh.with_scalars(scalars);
+        }
+        data.mesh = Some(mesh);
+    }
+    Ok(VtkDataset::PolyData(data))
+}
+
+// ============================================================================
+// Legacy format
+// ============================================================================
+
+/// Parse a legacy `.vtk` file (ASCII or big-endian binary).
+///
+/// Supports `STRUCTURED_POINTS`, `RECTILINEAR_GRID`, `POLYDATA` and
+/// `UNSTRUCTURED_GRID` datasets, with cells in both the classic and the
+/// version 5 `OFFSETS`/`CONNECTIVITY` layout.
+pub fn parse_vtk_legacy(bytes: &[u8], options: &VtkReadOptions) -> Result<VtkDataset, VtkIoError> {
+    LegacyReader { bytes, pos: 0, binary: false }
+        .read()?
+        .into_dataset(LEGACY, options)
+}
+
+struct LegacyReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    binary: bool,
+}
+
+/// Attribute section the reader is in.
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Geometry,
+    PointData(usize),
+    CellData(usize),
+}
+
+impl<'a> LegacyReader<'a> {
+    /// The next line, without its line break.
+    fn raw_line(&mut self) -> Option<&'a str> {
+        if self.pos >= self.bytes.len() {
+            return None;
+        }
+        let rest = &self.bytes[self.pos..];
+        let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        self.pos += (end + 1).min(rest.len());
+        Some(std::str::from_utf8(&rest[..end]).unwrap_or("").trim_end_matches('\r'))
+    }
+
+    /// Tokens of the next non-blank line.
+    fn line(&mut self) -> Option<Vec<&'a str>> {
+        loop {
+            let tokens: Vec<&str> = self.raw_line()?.split_whitespace().collect();
+            if !tokens.is_empty() {
+                return Some(tokens);
+            }
+        }
+    }
+
+    fn expect_line(&mut self) -> Result<Vec<&'a str>, VtkIoError> {
+        self.line().ok_or_else(|| parse_error(LEGACY, "unexpected end of file"))
+    }
+
+    /// Read `count` values: whitespace-separated text or big-endian binary.
+    fn values(&mut self, count: usize, ty: VtkType) -> Result<Vec<f64>, VtkIoError> {
+        if self.binary {
+            let values = decode(LEGACY, &self.bytes[self.pos..], ty, true, count)?;
+            self.pos += count * ty.size();
+            return Ok(values);
+        }
+        let mut values = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            while self.bytes.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+                self.pos += 1;
+            }
+            let start = self.pos;
+            while self.bytes.get(self.pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            values.push(number(std::str::from_utf8(&self.bytes[start..self.pos]).ok())?);
+        }
+        Ok(values)
+    }
+
+    fn typed_values(&mut self, count: usize, type_name: Option<&str>) -> Result<Vec<f64>, VtkIoError> {
+        let name = type_name.ok_or_else(|| parse_error(LEGACY, "missing data type"))?;
+        let ty = VtkType::from_legacy(name).ok_or_else(|| unsupported(LEGACY, format!("data type {:?}", name)))?;
+        self.values(count, ty)
+    }
+
+    /// Skip a `METADATA` block, which ends at a blank line.
+    fn skip_metadata(&mut self) {
+        while let Some(line) = self.raw_line() {
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// Read cells after a `POLYGONS n size`-style header line.
+    fn cells(&mut self, header: &[&str]) -> Result<Vec<Vec<u32>>, VtkIoError> {
+        let n: usize = parse_token(header.get(1).copied())?;
+        let size: usize = parse_token(header.get(2).copied())?;
+
+        // Version 5 layout: n offsets and `size` connectivity entries
+        let before = self.pos;
+        if let Some(line) = self.line().filter(|t| t[0].eq_ignore_ascii_case("OFFSETS")) {
+            let offsets = self.typed_values(n, line.get(1).copied())?;
+            let line = self.expect_line()?;
+            if !line[0].eq_ignore_ascii_case("CONNECTIVITY") {
+                return Err(parse_error(LEGACY, "OFFSETS without CONNECTIVITY"));
+            }
+            let connectivity = self.typed_values(size, line.get(1).copied())?;
+            return offsets
+                .windows(2)
+                .map(|w| {
+                    let (a, b) = (w[0] as usize, w[1] as usize);
+                    if a > b || b > connectivity.len() {
+                        return Err(parse_error(LEGACY, "cell offsets out of range"));
+                    }
+                    Ok(connectivity[a..b].iter().map(|&i| i as u32).collect())
+                })
+                .collect();
+        }
+        self.pos = before;
+
+        // Classic layout: each cell is its point count followed by the ids
+        let data = self.values(size, VtkType::I32)?;
+        let mut cells = Vec::with_capacity(n.min(1 << 20));
+        let mut k = 0;
+        for _ in 0..n {
+            let len = *data.get(k).ok_or_else(|| parse_error(LEGACY, "cell list shorter than declared"))? as usize;
+            let ids = (k + 1)
+                .checked_add(len)
+                .and_then(|end| data.get(k + 1..end))
+                .ok_or_else(|| parse_error(LEGACY, "cell list shorter than declared"))?;
+            if ids.iter().any(|&i| i < 0.0) {
+                return Err(parse_error(LEGACY, "negative point id"));
+            }
+            cells.push(ids.iter().map(|&i| i as u32).collect());
+            k += 1 + len;
+        }
+        Ok(cells)
+    }
+
+    fn read(mut self) -> Result<RawDataset, VtkIoError> {
+        let magic = self.raw_line().unwrap_or("");
+        if !magic.trim_start().to_lowercase().starts_with("# vtk datafile") {
+            return Err(parse_error(LEGACY, "missing '# vtk DataFile' header"));
+        }
+        self.raw_line(); // title
+        self.binary = match self.expect_line()?[0].to_uppercase().as_str() {
+            "ASCII" => false,
+            "BINARY" => true,
+            other => return Err(parse_error(LEGACY, format!("expected ASCII or BINARY, found {:?}", other))),
+        };
+        let line = self.expect_line()?;
+        if !line[0].eq_ignore_ascii_case("DATASET") || line.len() < 2 {
+            return Err(parse_error(LEGACY, "missing DATASET line"));
+        }
+        let dataset = line[1].to_uppercase();
+        if !matches!(dataset.as_str(), "STRUCTURED_POINTS" | "RECTILINEAR_GRID" | "POLYDATA" | "UNSTRUCTURED_GRID") {
+            return Err(unsupported(LEGACY, format!("dataset {}", dataset)));
+        }
+
+        let mut dimensions = [1usize; 3];
+        let mut origin = [0.0f32; 3];
+        let mut spacing = [1.0f32; 3];
+        let mut coordinates: [Vec<f32>; 3] = Default::default();
+        let mut points = Vec::new();
+        let mut cells: Vec<(u8, Vec<u32>)> = Vec::new();
+        let mut grid_cells = Vec::new();
+        let mut cell_types = Vec::new();
+        let mut arrays = Vec::new();
+        let mut active_scalars = None;
+        let mut active_normals = None;
+        let mut section = Section::Geometry;
+
+        while let Some(line) = self.line() {
+            let keyword = line[0].to_uppercase();
+            let count = match section {
+                Section::PointData(n) | Section::CellData(n) => n,
+                Section::Geometry => 0,
+            };
+            // Arrays read in the current section: (name, components, values)
+            let array = match keyword.as_str() {
+                "DIMENSIONS" => {
+                    for (axis, d) in dimensions.iter_mut().enumerate() {
+                        *d = parse_token(line.get(axis + 1).copied())?;
+                    }
+                    None
+                }
+                "SPACING" | "ASPECT_RATIO" | "ORIGIN" => {
+                    let target = if keyword == "ORIGIN" { &mut origin } else { &mut spacing };
+                    for (axis, v) in target.iter_mut().enumerate() {
+                        *v = parse_token(line.get(axis + 1).copied())?;
+                    }
+                    None
+                }
+                "X_COORDINATES" | "Y_COORDINATES" | "Z_COORDINATES" => {
+                    let axis = (keyword.as_bytes()[0] - b'X') as usize;
+                    let n = parse_token(line.get(1).copied())?;
+                    coordinates[axis] = self.typed_values(n, line.get(2).copied())?.into_iter().map(|v| v as f32).collect();
+                    None
+                }
+                "POINTS" => {
+                    let n: usize = parse_token(line.get(1).copied())?;
+                    points = self.typed_values(product(LEGACY, n, 3)?, line.get(2).copied())?.into_iter().map(|v| v as f32).collect();
+                    None
+                }
+                "VERTICES" | "LINES" | "POLYGONS" | "TRIANGLE_STRIPS" => {
+                    let cell_type = match keyword.as_str() {
+                        "VERTICES" => VTK_POLY_VERTEX,
+                        "LINES" => VTK_POLY_LINE,
+                        "POLYGONS" => VTK_POLYGON,
+                        _ => VTK_TRIANGLE_STRIP,
+                    };
+                    cells.extend(self.cells(&line)?.into_iter().map(|ids| (cell_type, ids)));
+                    None
+                }
+                "CELLS" => {
+                    grid_cells = self.cells(&line)?;
+                    None
+                }
+                "CELL_TYPES" => {
+                    let n = parse_token(line.get(1).copied())?;
+                    cell_types = self.values(n, VtkType::I32)?;
+                    None
+                }
+                "METADATA" => {
+                    self.skip_metadata();
+                    None
+                }
+                "POINT_DATA" => {
+                    section = Section::PointData(parse_token(line.get(1).copied())?);
+                    None
+                }
+                "CELL_DATA" => {
+                    section = Section::CellData(parse_token(line.get(1).copied())?);
+                    None
+                }
+                "FIELD" => {
+                    let n: usize = parse_token(line.get(2).copied())?;
+                    let mut field = Vec::new();
+                    while field.len() < n {
+                        let line = self.expect_line()?;
+                        match line[0] {
+                            "METADATA" => self.skip_metadata(),
+                            "NULL_ARRAY" => field.push(None),
+                            name => {
+                                let components: usize = parse_token(line.get(1).copied())?;
+                                let tuples: usize = parse_token(line.get(2).copied())?;
+                                let values = self.typed_values(product(LEGACY, components, tuples)?, line.get(3).copied())?;
+                                field.push(Some((name.to_string(), components, values)));
+                            }
+                        }
+                    }
+                    if let Section::PointData(_) = section {
+                        arrays.extend(field.into_iter().flatten().map(|(name, components, values)| PointArray {
+                            name,
+                            components,
+                            values,
+                        }));
+                    }
+                    None
+                }
+                _ if section == Section::Geometry => {
+                    return Err(parse_error(LEGACY, format!("unexpected keyword {:?}", line[0])));
+                }
+                "SCALARS" => {
+                    let components = match line.get(3) {
+                        Some(token) => parse_token(Some(token))?,
+                        None => 1,
+                    };
+                    // LOOKUP_TABLE is required by the format but often omitted
+                    let before = self.pos;
+                    if !self.line().is_some_and(|t| t[0].eq_ignore_ascii_case("LOOKUP_TABLE")) {
+                        self.pos = before;
+                    }
+                    let values = self.typed_values(product(LEGACY, count, components)?, line.get(2).copied())?;
+                    Some((components, values))
+                }
+                "COLOR_SCALARS" => {
+                    let components: usize = parse_token(line.get(2).copied())?;
+                    let total = product(LEGACY, count, components)?;
+                    let values = if self.binary {
+                        self.values(total, VtkType::U8)?.into_iter().map(|v| v / 255.0).collect()
+                    } else {
+                        self.values(total, VtkType::F32)?
+                    };
+                    Some((components, values))
+                }
+                "LOOKUP_TABLE" => {
+                    let size: usize = parse_token(line.get(2).copied())?;
+                    self.values(product(LEGACY, size, 4)?, if self.binary { VtkType::U8 } else { VtkType::F32 })?;
+                    None
+                }
+                "VECTORS" | "NORMALS" => Some((3, self.typed_values(product(LEGACY, count, 3)?, line.get(2).copied())?)),
+                "TEXTURE_COORDINATES" => {
+                    let components: usize = parse_token(line.get(2).copied())?;
+                    Some((components, self.typed_values(product(LEGACY, count, components)?, line.get(3).copied())?))
+                }
+                "TENSORS" => Some((9, self.typed_values(product(LEGACY, count, 9)?, line.get(2).copied())?)),
+                "TENSORS6" => Some((6, self.typed_values(product(LEGACY, count, 6)?, line.get(2).copied())?)),
+                "GLOBAL_IDS" | "PEDIGREE_IDS" => Some((1, self.typed_values(count, line.get(2).copied())?)),
+                _ => return Err(parse_error(LEGACY, format!("unexpected keyword {:?}", line[0]))),
+            };
+
+            if let (Some((components, values)), Section::PointData(_)) = (array, section) {
+                let name = line.get(1).unwrap_or(&"").to_string();
+                match keyword.as_str() {
+                    "SCALARS" if active_scalars.is_none() => active_scalars = Some(name.clone()),
+                    "NORMALS" if active_normals.is_none() => active_normals = Some(name.clone()),
+                    _ => {}
+                }
+                arrays.push(PointArray { name, components, values });
+            }
+        }
+
+        let geometry = match dataset.as_str() {
+            "STRUCTURED_POINTS" => Geometry::Image {
+                dimensions,
+                origin,
+                spacing,
+            },
+            "RECTILINEAR_GRID" => Geometry::Rectilinear(coordinates),
+            "POLYDATA" => Geometry::Cells { points, cells },
+            _ => {
+                if grid_cells.len() != cell_types.len() {
+                    return Err(parse_error(LEGACY, "CELLS and CELL_TYPES counts differ"));
+                }
+                let cells = cell_types.iter().map(|&t| t as u8).zip(grid_cells).collect();
+                Geometry::Cells { points, cells }
+            }
+        };
+        Ok(RawDataset {
+            geometry,
+            arrays,
+            active_scalars,
+            active_normals,
+        })
+    }
+}
+
+fn number(token: Option<&str>) -> Result<f64, VtkIoError> {
+    let token = token.filter(|t| !t.is_empty()).ok_or_else(|| parse_error(LEGACY, "unexpected end of data"))?;
+    token
+        .parse()
+        .map_err(|_| parse_error(LEGACY, format!("invalid number {:?}", token)))
+}
+
+fn parse_token<T: std::str::FromStr>(token: Option<&str>) -> Result<T, VtkIoError> {
+    let token = token.ok_or_else(|| parse_error(LEGACY, "missing value"))?;
+    token
+        .parse()
+        .map_err(|_| parse_error(LEGACY, format!("invalid value {:?}", token)))
+}
+
+// ============================================================================
+// XML formats
+// ============================================================================
+
+/// Parse an XML VTK file: `ImageData`, `RectilinearGrid`, `PolyData` or
+/// `UnstructuredGrid`.
+///
+/// Data arrays may be `ascii`, inline `binary` (base64) or `appended` (raw
+/// or base64), in either byte order and with 32- or 64-bit headers.
+pub fn parse_vtk_xml(bytes: &[u8], options: &VtkReadOptions) -> Result<VtkDataset, VtkIoError> {
+    // Appended data is raw bytes after an underscore, so split it off before parsing XML
+    let (document, appended) = match find(bytes, b"<AppendedData") {
+        Some(start) => {
+            let tag_end = find(&bytes[start..], b">").map(|e| start + e + 1);
+            let data = tag_end.and_then(|end| find(&bytes[end..], b"_").map(|u| end + u + 1));
+            let data = data.ok_or_else(|| parse_error(XML, "malformed AppendedData"))?;
+            let tag = std::str::from_utf8(&bytes[start..tag_end.unwrap()]).unwrap_or("");
+            let base64 = !tag.contains("\"raw\"");
+            (&bytes[..start], Some((base64, &bytes[data..])))
+        }
+        None => (bytes, None),
+    };
+    let text = std::str::from_utf8(document).map_err(|_| parse_error(XML, "document is not valid UTF-8"))?;
+    let root = parse_xml(text)?;
+    if root.name != "VTKFile" {
+        return Err(parse_error(XML, format!("root element is {:?}, not VTKFile", root.name)));
+    }
+
+    let context = XmlContext {
+        big_endian: root.attr("byte_order") == Some("BigEndian"),
+        header: match root.attr("header_type") {
+            None | Some("UInt32") => VtkType::U32,
+            Some("UInt64") => VtkType::U64,
+            Some(other) => return Err(unsupported(XML, format!("header type {}", other))),
+        },
+        compressor: root.attr("compressor").map(str::to_string),
+        appended,
+    };
+
+    let kind = root.attr("type").unwrap_or("");
+    let dataset = root
+        .child(kind)
+        .ok_or_else(|| parse_error(XML, format!("missing {} element", kind)))?;
+    let pieces: Vec<&XmlElement> = dataset.children_named("Piece").collect();
+    if pieces.is_empty() {
+        return Err(parse_error(XML, "no Piece elements"));
+    }
+
+    let mut raw = match kind {
+        "ImageData" | "RectilinearGrid" => {
+            if pieces.len() > 1 {
+                return Err(unsupported(XML, "multiple pieces in a grid"));
+            }
+            let extent = dataset
+                .attr("WholeExtent")
+                .or_else(|| pieces[0].attr("Extent"))
+                .ok_or_else(|| parse_error(XML, "missing WholeExtent"))?;
+            let extent: Vec<i64> = floats::<6>(extent)?.into_iter().map(|v| v as i64).collect();
+            let mut dimensions = [0; 3];
+            for (a, d) in dimensions.iter_mut().enumerate() {
+                let span = extent[2 * a + 1].checked_sub(extent[2 * a]).and_then(|s| s.checked_add(1));
+                let span = span.ok_or_else(|| parse_error(XML, "WholeExtent is too large"))?;
+                *d = usize::try_from(span.max(0)).map_err(|_| parse_error(XML, "WholeExtent is too large"))?;
+            }
+            let geometry = if kind == "ImageData" {
+                let origin = floats::<3>(dataset.attr("Origin").unwrap_or("0 0 0"))?;
+                let spacing = floats::<3>(dataset.attr("Spacing").unwrap_or("1 1 1"))?;
+                Geometry::Image {
+                    dimensions,
+                    origin: [0, 1, 2].map(|a| (origin[a] + extent[2 * a] as f64 * spacing[a]) as f32),
+                    spacing: spacing.map(|s| s as f32),
+                }
+            } else {
+                let arrays: Vec<&XmlElement> = pieces[0]
+                    .child("Coordinates")
+                    .ok_or_else(|| parse_error(XML, "missing Coordinates"))?
+                    .children_named("DataArray")
+                    .collect();
+                if arrays.len() != 3 {
+                    return Err(parse_error(XML, "Coordinates must hold three arrays"));
+                }
+                let mut coordinates: [Vec<f32>; 3] = Default::default();
+                for (axis, array) in arrays.iter().enumerate() {
+                    coordinates[axis] = context.array(array)?.values.into_iter().map(|v| v as f32).collect();
+                }
+                Geometry::Rectilinear(coordinates)
+            };
+            RawDataset {
+                geometry,
+                arrays: Vec::new(),
+                active_scalars: None,
+                active_normals: None,
+            }
+        }
+        "PolyData" | "UnstructuredGrid" => {
+            let mut points = Vec::new();
+            let mut cells = Vec::new();
+            for piece in &pieces {
+                let offset = u32::try_from(points.len() / 3).map_err(|_| parse_error(XML, "too many points"))?;
+                let array = piece
+                    .child("Points")
+                    .and_then(|p| p.child("DataArray"))
+                    .ok_or_else(|| parse_error(XML, "missing Points"))?;
+                points.extend(context.array(array)?.values.into_iter().map(|v| v as f32));
+
+                let sections: &[(&str, Option<u8>)] = if kind == "PolyData" {
+                    &[
+                        ("Verts", Some(VTK_POLY_VERTEX)),
+                        ("Lines", Some(VTK_POLY_LINE)),
+                        ("Strips", Some(VTK_TRIANGLE_STRIP)),
+                        ("Polys", Some(VTK_POLYGON)),
+                    ]
+                } else {
+                    &[("Cells", None)]
+                };
+                for &(section, cell_type) in sections {
+                    if let Some(element) = piece.child(section) {
+                        cells.extend(context.cells(element, cell_type, offset)?);
+                    }
+                }
+            }
+            RawDataset {
+                geometry: Geometry::Cells { points, cells },
+                arrays: Vec::new(),
+                active_scalars: None,
+                active_normals: None,
+            }
+        }
+        other => return Err(unsupported(XML, format!("dataset {}", other))),
+    };
+
+    // Point data, concatenated across pieces by array position
+    for (p, piece) in pieces.iter().enumerate() {
+        let Some(point_data) = piece.child("PointData") else {
+            continue;
+        };
+        if p == 0 {
+            raw.active_scalars = point_data.attr("Scalars").map(str::to_string);
+            raw.active_normals = point_data.attr("Normals").map(str::to_string);
+        }
+        for (k, element) in point_data.children_named("DataArray").enumerate() {
+            let array = context.array(element)?;
+            match raw.arrays.get_mut(k) {
+                Some(existing) if p > 0 => existing.values.extend(array.values),
+                _ => raw.arrays.push(array),
+            }
+        }
+    }
+
+    raw.into_dataset(XML, options)
+}
+
+struct XmlContext<'a> {
+    big_endian: bool,
+    header: VtkType,
+    compressor: Option<String>,
+    /// Appended data after the underscore, and whether it is base64 encoded.
+    appended: Option<(bool, &'a [u8])>,
+}
+
+impl XmlContext<'_> {
+    fn array(&self, element: &XmlElement) -> Result<PointArray, VtkIoError> {
+        let type_name = element.attr("type").unwrap_or("");
+        let ty = VtkType::from_xml(type_name).ok_or_else(|| unsupported(XML, format!("array type {:?}", type_name)))?;
+        let components = match element.attr("NumberOfComponents") {
+            Some(n) => n.parse().map_err(|_| parse_error(XML, format!("invalid NumberOfComponents {:?}", n)))?,
+            None => 1,
+        };
+        let name = element.attr("Name").unwrap_or("").to_string();
+
+        let format = element.attr("format").unwrap_or("ascii");
+        if format != "ascii" && self.compressor.is_some() {
+            return Err(unsupported(XML, format!("compressor {}", self.compressor.as_deref().unwrap_or(""))));
+        }
+        let header_size = self.header.size();
+        let values = match format {
+            "ascii" => element
+                .text
+                .split_ascii_whitespace()
+                .map(|t| t.parse::<f64>().map_err(|_| parse_error(XML, format!("invalid number {:?}", t))))
+                .collect::<Result<_, _>>()?,
+            "binary" => {
+                let bytes = decode_base64(element.text.as_bytes())?;
+                self.block(&bytes, ty)?
+            }
+            "appended" => {
+                let (base64, data) = self.appended.ok_or_else(|| parse_error(XML, "appended array without AppendedData"))?;
+                let offset: usize = element
+                    .attr("offset")
+                    .and_then(|o| o.parse().ok())
+                    .ok_or_else(|| parse_error(XML, "appended array without offset"))?;
+                let data = data.get(offset..).ok_or_else(|| parse_error(XML, "appended offset out of range"))?;
+                if base64 {
+                    // Header and data are encoded separately
+                    let header_chars = header_size.div_ceil(3) * 4;
+                    let header = decode_base64(data.get(..header_chars).unwrap_or(data))?;
+                    let length = self.length(&header)?;
+                    let rest = &data[header_chars.min(data.len())..];
+                    let bytes = decode_base64(rest.get(..length.div_ceil(3) * 4).unwrap_or(rest))?;
+                    decode(XML, &bytes, ty, self.big_endian, length / ty.size())?
+                } else {
+                    self.block(data, ty)?
+                }
+            }
+            other => return Err(unsupported(XML, format!("array format {}", other))),
+        };
+        Ok(PointArray { name, components, values })
+    }
+
+    /// Byte count from a block header.
+    fn length(&self, header: &[u8]) -> Result<usize, VtkIoError> {
+        let value = decode(XML, header, self.header, self.big_endian, 1)?;
+        Ok(value[0] as usize)
+    }
+
+    /// Decode a header-prefixed block of samples.
+    fn block(&self, bytes: &[u8], ty: VtkType) -> Result<Vec<f64>, VtkIoError> {
+        let length = self.length(bytes)?;
+        let data = &bytes[self.header.size()..];
+        if length > data.len() {
+            return Err(parse_error(XML, format!("block of {} bytes, found {}", length, data.len())));
+        }
+        decode(XML, data, ty, self.big_endian, length / ty.size())
+    }
+
+    /// Cells from a `connectivity`/`offsets`(/`types`) section.
+    fn cells(&self, element: &XmlElement, cell_type: Option<u8>, point_offset: u32) -> Result<Vec<(u8, Vec<u32>)>, VtkIoError> {
+        let named = |name: &str| element.children_named("DataArray").find(|a| a.attr("Name") == Some(name));
+        let (Some(connectivity), Some(offsets)) = (named("connectivity"), named("offsets")) else {
+            return Ok(Vec::new());
+        };
+        let connectivity = self.array(connectivity)?.values;
+        let offsets = self.array(offsets)?.values;
+        let types = match cell_type {
+            Some(t) => vec![t as f64; offsets.len()],
+            None => self.array(named("types").ok_or_else(|| parse_error(XML, "Cells without types"))?)?.values,
+        };
+        if types.len() != offsets.len() {
+            return Err(parse_error(XML, "cell offsets and types differ in length"));
+        }
+
+        let mut start = 0;
+        let mut cells = Vec::with_capacity(offsets.len());
+        for (&end, &ty) in offsets.iter().zip(&types) {
+            let end = end as usize;
+            let ids = connectivity
+                .get(start..end)
+                .ok_or_else(|| parse_error(XML, "cell offsets out of range"))?;
+            let ids = ids
+                .iter()
+                .map(|&i| {
+                    u32::try_from(i as u64)
+                        .ok()
+                        .and_then(|i| i.checked_add(point_offset))
+                        .ok_or_else(|| parse_error(XML, "point id out of range"))
+                })
+                .collect::<Result<_, _>>()?;
+            cells.push((ty as u8, ids));
+            start = end;
+        }
+        Ok(cells)
+    }
+}
+
+fn floats<const N: usize>(text: &str) -> Result<[f64; N], VtkIoError> {
+    let values: Vec<f64> = text.split_ascii_whitespace().filter_map(|t| t.parse().ok()).collect();
+    values
+        .try_into()
+        .map_err(|_| parse_error(XML, format!("expected {} numbers, found {:?}", N, text)))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn decode_base64(text: &[u8]) -> Result<Vec<u8>, VtkIoError> {
+    let digit = |c: u8| -> Result<u32, VtkIoError> {
+        Ok(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(parse_error(XML, "invalid base64 data")),
+        } as u32)
+    };
+    let chars: Vec<u8> = text.iter().copied().filter(|c| !c.is_ascii_whitespace()).collect();
+    let mut out = Vec::with_capacity(chars.len() / 4 * 3);
+    // Groups decode independently, so separately padded streams concatenate
+    for group in chars.chunks(4) {
+        let data: Vec<u8> = group.iter().copied().take_while(|&c| c != b'=').collect();
+        if data.len() < 2 {
+            return Err(parse_error(XML, "truncated base64 data"));
+        }
+        let mut n = 0u32;
+        for (k, &c) in data.iter().enumerate() {
+            n |= digit(c)? << (18 - 6 * k);
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..data.len()]);
+    }
+    Ok(out)
+}
+
+/// Minimal XML element tree: enough for VTK files.
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+}
+
+/// Parse XML into an element tree. Elements still open at the end of the
+/// text are closed, since the appended data section is cut off beforehand.
+fn parse_xml(text: &str) -> Result<XmlElement, VtkIoError> {
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root = None;
+    let mut rest = text;
+
+    let skip_past = |rest: &str, end: &str| -> Result<usize, VtkIoError> {
+        rest.find(end)
+            .map(|i| i + end.len())
+            .ok_or_else(|| parse_error(XML, format!("unterminated markup, expected {:?}", end)))
+    };
+    let close = |stack: &mut Vec<XmlElement>, root: &mut Option<XmlElement>| {
+        let element = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => *root = root.take().or(Some(element)),
+        }
+    };
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix("<?") {
+            rest = &stripped[skip_past(stripped, "?>")?..];
+        } else if let Some(stripped) = rest.strip_prefix("<!--") {
+            rest = &stripped[skip_past(stripped, "-->")?..];
+        } else if let Some(stripped) = rest.strip_prefix("<!") {
+            rest = &stripped[skip_past(stripped, ">")?..];
+        } else if let Some(stripped) = rest.strip_prefix("</") {
+            let end = skip_past(stripped, ">")?;
+            let name = stripped[..end - 1].trim();
+            if stack.last().map(|e| e.name.as_str()) != Some(name) {
+                return Err(parse_error(XML, format!("unexpected closing tag {:?}", name)));
+            }
+            close(&mut stack, &mut root);
+            rest = &stripped[end..];
+        } else if let Some(stripped) = rest.strip_prefix('<') {
+            let name_end = stripped
+                .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+                .ok_or_else(|| parse_error(XML, "unterminated tag"))?;
+            let mut element = XmlElement {
+                name: stripped[..name_end].to_string(),
+                attributes: Vec::new(),
+                children: Vec::new(),
+                text: String::new(),
+            };
+            rest = &stripped[name_end..];
+            loop {
+                rest = rest.trim_start();
+                if let Some(after) = rest.strip_prefix("/>") {
+                    rest = after;
+                    stack.push(element);
+                    close(&mut stack, &mut root);
+                    break;
+                }
+                if let Some(after) = rest.strip_prefix('>') {
+                    rest = after;
+                    stack.push(element);
+                    break;
+                }
+                let eq = rest.find('=').ok_or_else(|| parse_error(XML, "malformed attribute"))?;
+                let key = rest[..eq].trim().to_string();
+                let value = rest[eq + 1..].trim_start();
+                let quote = value.chars().next().filter(|&c| c == '"' || c == '\'');
+                let quote = quote.ok_or_else(|| parse_error(XML, format!("unquoted attribute {:?}", key)))?;
+                let end = value[1..].find(quote).ok_or_else(|| parse_error(XML, "unterminated attribute"))?;
+                element.attributes.push((key, unescape(&value[1..1 + end])));
+                rest = &value[end + 2..];
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            if let Some(element) = stack.last_mut() {
+                element.text.push_str(&rest[..end]);
+            }
+            rest = &rest[end..];
+        }
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut root);
+    }
+    root.ok_or_else(|| parse_error(XML, "no root element"))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gltf::base64;
+
+    const IMAGE: &str = "# vtk DataFile Version 3.0
+density on a 2x2x2 grid
+ASCII
+DATASET STRUCTURED_POINTS
+DIMENSIONS 2 2 2
+ORIGIN 1 2 3
+SPACING 0.5 0.5 2
+POINT_DATA 8
+VECTORS velocity float
+3 4 0  0 0 0  0 0 0  0 0 0
+0 0 0  0 0 0  0 0 0  1 2 2
+SCALARS density float
+LOOKUP_TABLE default
+0 1 2 3 4 5 6 7
+";
+
+    #[test]
+    fn test_legacy_structured_points() {
+        let volume = parse_vtk_legacy(IMAGE.as_bytes(), &VtkReadOptions::new())
+            .unwrap()
+            .into_volume()
+            .unwrap();
+        assert_eq!(volume.dimensions, [2, 2, 2]);
+        assert_eq!(volume.origin, [1.0, 2.0, 3.0]);
+        assert_eq!(volume.spacing, [0.5, 0.5, 2.0]);
+        // SCALARS are active even when another array comes first
+        assert_eq!(volume.get(1, 1, 1), 7.0);
+
+        let options = VtkReadOptions::new().with_scalars("velocity");
+        let speed = parse_vtk_legacy(IMAGE.as_bytes(), &options).unwrap().into_volume().unwrap();
+        assert_eq!((speed.get(0, 0, 0), speed.get(1, 1, 1)), (5.0, 3.0));
+        let vy = parse_vtk_legacy(IMAGE.as_bytes(), &options.clone().with_component(1)).unwrap();
+        assert_eq!(vy.into_volume().unwrap().get(0, 0, 0), 4.0);
+
+        let missing = parse_vtk_legacy(IMAGE.as_bytes(), &VtkReadOptions::new().with_scalars("pressure"));
+        assert!(matches!(missing, Err(VtkIoError::MissingArray(name)) if name == "pressure"));
+        assert!(parse_vtk_legacy(&IMAGE.as_bytes()[..IMAGE.len() - 6], &VtkReadOptions::new()).is_err());
+    }
+
+    #[test]
+    fn test_legacy_oversized_counts_are_errors() {
+        let header = "# vtk DataFile Version 3.0\nhuge\nASCII\nDATASET POLYDATA\n";
+        for body in [
+            "POINTS 9223372036854775807 float\n0 0 0\n",
+            "POINTS 1 float\n0 0 0\nPOLYGONS 1 2\n18446744073709551615 0\n",
+            "POINTS 1 float\n0 0 0\nPOINT_DATA 9223372036854775807\nTENSORS t float\n",
+            "POINTS 1 float\n0 0 0\nPOINT_DATA 9223372036854775807\nNORMALS n float\n",
+        ] {
+            let bytes = format!("{header}{body}").into_bytes();
+            let result = parse_vtk_legacy(&bytes, &VtkReadOptions::new());
+            assert!(matches!(result, Err(VtkIoError::Parse { .. })), "{body:?}");
+        }
+
+        let grid = "# vtk DataFile Version 3.0\nhuge\nASCII\nDATASET STRUCTURED_POINTS\nDIMENSIONS 4294967296 4294967296 2\n";
+        let result = parse_vtk_legacy(grid.as_bytes(), &VtkReadOptions::new());
+        assert!(matches!(result, Err(VtkIoError::Parse { .. })), "{result:?}");
+
+        let image = |extent: &str, components: &str| {
+            format!(
+                r#"<VTKFile type="ImageData" version="1.0"><ImageData WholeExtent="{extent}"><Piece>
+<PointData><DataArray type="Float32" Name="p" NumberOfComponents="{components}" format="ascii">1 2</DataArray></PointData>
+</Piece></ImageData></VTKFile>"#
+            )
+        };
+        for text in [
+            image("-9223372036854775808 9223372036854775807 0 0 0 0", "1"),
+            image("0 1 0 0 0 0", "18446744073709551615"),
+        ] {
+            let result = parse_vtk_xml(text.as_bytes(), &VtkReadOptions::new());
+            assert!(matches!(result, Err(VtkIoError::Parse { .. })), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_legacy_binary_poly_data() {
+        let mut bytes = b"# vtk DataFile Version 3.0\nsquare\nBINARY\nDATASET POLYDATA\nPOINTS 4 float\n".to_vec();
+        for v in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0] {
+            bytes.extend_from_slice(&v.to_be_bytes());
+        }
+        let mut section = |header: &str, ints: &[i32]| {
+            bytes.extend_from_slice(format!("\n{}\n", header).as_bytes());
+            ints.iter().for_each(|i| bytes.extend_from_slice(&i.to_be_bytes()));
+        };
+        section("VERTICES 1 3", &[2, 1, 3]);
+        section("LINES 1 4", &[3, 0, 1, 2]);
+        section("POLYGONS 1 5", &[4, 0, 1, 2, 3]);
+        bytes.extend_from_slice(b"\nPOINT_DATA 4\nSCALARS temperature double 1\nLOOKUP_TABLE default\n");
+        for v in [10.0f64, 20.0, 30.0, 40.0] {
+            bytes.extend_from_slice(&v.to_be_bytes());
+        }
+        bytes.push(b'\n');
+
+        let data = parse_vtk_legacy(&bytes, &VtkReadOptions::new()).unwrap().into_poly_data().unwrap();
+        let mesh = data.mesh.unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.scalars, Some(vec![10.0, 20.0, 30.0, 40.0]));
+        let points = data.points.unwrap();
+        assert_eq!(points.positions, vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        assert_eq!(points.scalars, Some(vec![20.0, 40.0]));
+        assert_eq!(data.polylines.len(), 1);
+        assert_eq!(data.polylines[0].scalars, Some(vec![10.0, 20.0, 30.0]));
+    }
+
+    #[test]
+    fn test_legacy_unstructured_grid_boundary() {
+        // Two tetrahedra sharing the face (1, 2, 3)
+        let text = "# vtk DataFile Version 2.0
+two tets
+ASCII
+
+DATASET UNSTRUCTURED_GRID
+POINTS 5 double
+0 0 0  1 0 0  0 1 0  0 0 1  1 1 1
+CELLS 2 10
+4 0 1 2 3
+4 1 2 3 4
+CELL_TYPES 2
+10
+10
+CELL_DATA 2
+SCALARS id int
+LOOKUP_TABLE default
+0 1
+";
+        let mesh = parse_vtk_legacy(text.as_bytes(), &VtkReadOptions::new())
+            .unwrap()
+            .into_poly_data()
+            .unwrap()
+            .mesh
+            .unwrap();
+        // Cell data is ignored; the shared face is interior
+        assert!(mesh.scalars.is_none());
+        assert_eq!(mesh.triangle_count(), 6);
+        assert!(mesh.indices.chunks(3).all(|t| {
+            let mut t = t.to_vec();
+            t.sort();
+            t != [1, 2, 3]
+        }));
+
+        // Version 5 cell layout
+        let text = "# vtk DataFile Version 5.1\nv5\nASCII\nDATASET POLYDATA\nPOINTS 3 float\n0 0 0 1 0 0 0 1 0\n\
+                    METADATA\nINFORMATION 0\n\nPOLYGONS 2 3\nOFFSETS vtktypeint64\n0 3\nCONNECTIVITY vtktypeint64\n0 1 2\n";
+        let data = parse_vtk_legacy(text.as_bytes(), &VtkReadOptions::new()).unwrap().into_poly_data().unwrap();
+        assert_eq!(data.mesh.unwrap().indices, vec![0, 1, 2]);
+        assert!(data.points.is_none());
+    }
+
+    #[test]
+    fn test_xml_image_data_ascii() {
+        let text = r#"<?xml version="1.0"?>
+<!-- written by hand -->
+<VTKFile type="ImageData" version="1.0" byte_order="LittleEndian">
+  <ImageData WholeExtent="0 1 0 1 1 2" Origin="0 0 0" Spacing="1 2 3">
+    <Piece Extent="0 1 0 1 1 2">
+      <PointData Scalars="rho">
+        <DataArray type="Float32" Name="p" format="ascii">8 7 6 5 4 3 2 1</DataArray>
+        <DataArray type="Float64" Name="rho" format="ascii">
+          0 1 2 3
+          4 5 6 7
+        </DataArray>
+      </PointData>
+      <CellData/>
+    </Piece>
+  </ImageData>
+</VTKFile>
+"#;
+        let volume = parse_vtk_xml(text.as_bytes(), &VtkReadOptions::new()).unwrap().into_volume().unwrap();
+        assert_eq!(volume.dimensions, [2, 2, 2]);
+        // The extent starts at z = 1
+        assert_eq!(volume.origin, [0.0, 0.0, 3.0]);
+        assert_eq!(volume.get(1, 0, 1), 5.0);
+
+        let dir = std::env::temp_dir().join(format!("frustum_vtk_io_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("grid.vti"), text).unwrap();
+        let p = read_vtk(dir.join("grid.vti"), &VtkReadOptions::new().with_scalars("p")).unwrap();
+        assert_eq!(p.into_volume().unwrap().get(0, 0, 0), 8.0);
+        assert!(matches!(read_vtk(dir.join("grid.xyz"), &VtkReadOptions::new()), Err(VtkIoError::UnknownFormat(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_xml_poly_data_inline_binary() {
+        let block = |bytes: Vec<u8>| {
+            let mut data = (bytes.len() as u32).to_le_bytes().to_vec();
+            data.extend(bytes);
+            base64(&data)
+        };
+        let f32s = |v: &[f32]| block(v.iter().flat_map(|x| x.to_le_bytes()).collect());
+        let i32s = |v: &[i32]| block(v.iter().flat_map(|x| x.to_le_bytes()).collect());
+        let text = format!(
+            r#"<VTKFile type="PolyData" byte_order="LittleEndian" header_type="UInt32">
+<PolyData><Piece NumberOfPoints="3" NumberOfPolys="1">
+<Points><DataArray type="Float32" NumberOfComponents="3" format="binary">{}</DataArray></Points>
+<Polys>
+<DataArray type="Int32" Name="connectivity" format="binary">{}</DataArray>
+<DataArray type="Int32" Name="offsets" format="binary">{}</DataArray>
+</Polys>
+<PointData Normals="n"><DataArray type="Float32" Name="n" NumberOfComponents="3" format="binary">{}</DataArray>
+<DataArray type="Float32" Name="s" format="binary">{}</DataArray></PointData>
+</Piece></PolyData></VTKFile>"#,
+            f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]),
+            i32s(&[0, 1, 2]),
+            i32s(&[3]),
+            f32s(&[0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]),
+            f32s(&[0.5, 1.5, 2.5]),
+        );
+        let mesh = parse_vtk_xml(text.as_bytes(), &VtkReadOptions::new())
+            .unwrap()
+            .into_poly_data()
+            .unwrap()
+            .mesh
+            .unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert_eq!(mesh.normals.as_ref().unwrap()[2], 1.0);
+        // The normals array is not picked as scalars
+        assert_eq!(mesh.scalars, Some(vec![0.5, 1.5, 2.5]));
+
+        let compressed = text.replace("header_type=", "compressor=\"vtkZLibDataCompressor\" header_type=");
+        let error = parse_vtk_xml(compressed.as_bytes(), &VtkReadOptions::new());
+        assert!(matches!(error, Err(VtkIoError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn test_xml_unstructured_grid_appended_raw() {
+        // Unit cube as one hexahedron; big-endian with 64-bit headers
+        let mut appended = Vec::new();
+        let mut offsets = Vec::new();
+        let mut push = |bytes: Vec<u8>| {
+            offsets.push(appended.len());
+            appended.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+            appended.extend(bytes);
+        };
+        let cube: Vec<f64> = vec![
+            0., 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0., 0., 0., 1., 1., 0., 1., 1., 1., 1., 0., 1., 1.,
+        ];
+        push(cube.iter().flat_map(|x| x.to_be_bytes()).collect());
+        push((0i64..8).flat_map(|x| x.to_be_bytes()).collect());
+        push(8i64.to_be_bytes().to_vec());
+        push(vec![VTK_HEXAHEDRON]);
+
+        let mut bytes = format!(
+            r#"<VTKFile type="UnstructuredGrid" byte_order="BigEndian" header_type="UInt64">
+<UnstructuredGrid><Piece NumberOfPoints="8" NumberOfCells="1">
+<Points><DataArray type="Float64" NumberOfComponents="3" format="appended" offset="{}"/></Points>
+<Cells>
+<DataArray type="Int64" Name="connectivity" format="appended" offset="{}"/>
+<DataArray type="Int64" Name="offsets" format="appended" offset="{}"/>
+<DataArray type="UInt8" Name="types" format="appended" offset="{}"/>
+</Cells>
+</Piece></UnstructuredGrid>
+<AppendedData encoding="raw">
+_"#,
+            offsets[0], offsets[1], offsets[2], offsets[3]
+        )
+        .into_bytes();
+        bytes.extend(appended);
+        bytes.extend_from_slice(b"\n</AppendedData>\n</VTKFile>\n");
+
+        let data = parse_vtk_xml(&bytes, &VtkReadOptions::new()).unwrap().into_poly_data().unwrap();
+        let mesh = data.mesh.unwrap();
+        assert_eq!(mesh.vertex_count(), 8);
+        assert_eq!(mesh.triangle_count(), 12);
+        assert!(mesh.scalars.is_none());
+
+        // Faces point outward: every triangle normal points away from the centre
+        for t in mesh.indices.chunks(3) {
+            let p = |v: u32| glam::Vec3::from_slice(&mesh.positions[v as usize * 3..v as usize * 3 + 3]);
+            let normal = (p(t[1]) - p(t[0])).cross(p(t[2]) - p(t[0]));
+            let centre = (p(t[0]) + p(t[1]) + p(t[2])) / 3.0 - glam::Vec3::splat(0.5);
+            assert!(normal.dot(centre) > 0.0);
+        }
+    }
+}
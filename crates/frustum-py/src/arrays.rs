@@ -0,0 +1,152 @@
+//! Conversion of NumPy arrays and array-likes into scene buffers.
+//!
+//! Arguments are received as `PyArrayLike`: an array of the right dtype is
+//! borrowed in place, with no intermediate conversion, and copied once into
+//! the scene element (a single `memcpy` when it is C-contiguous). Other
+//! dtypes and plain sequences are converted by NumPy first.
+
+use numpy::ndarray::{Array2, Array3, ArrayViewD};
+use numpy::{AllowTypeChange, IntoPyArray, PyArray2, PyArray3, PyArrayLikeDyn, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Floating-point input, cast to `f32` by NumPy when needed.
+pub type FloatArray<'py> = PyArrayLikeDyn<'py, f32, AllowTypeChange>;
+/// Index input, cast to `u32` by NumPy when needed.
+pub type IndexArray<'py> = PyArrayLikeDyn<'py, u32, AllowTypeChange>;
+
+/// Flatten an array of `width`-component rows (shape `(n, width)` or `(n * width,)`).
+pub fn rows<T: Copy + numpy::Element>(
+    array: &PyArrayLikeDyn<'_, T, AllowTypeChange>,
+    name: &str,
+    width: usize,
+) -> PyResult<Vec<T>> {
+    check_rows(array.shape(), name, width).map_err(PyValueError::new_err)?;
+    Ok(flatten(array.as_array()))
+}
+
+/// Flatten a 1-D array.
+pub fn values<T: Copy + numpy::Element>(array: &PyArrayLikeDyn<'_, T, AllowTypeChange>, name: &str) -> PyResult<Vec<T>> {
+    if array.ndim() != 1 {
+        return Err(PyValueError::new_err(format!(
+            "{name} must be one-dimensional, got shape {:?}",
+            array.shape()
+        )));
+    }
+    Ok(flatten(array.as_array()))
+}
+
+/// Values of a 3-D array indexed `[x, y, z]`, in `Volume` order (x fastest).
+pub fn volume_values(array: &FloatArray<'_>) -> PyResult<(Vec<f32>, [usize; 3])> {
+    volume_order(array.as_array()).map_err(PyValueError::new_err)
+}
+
+/// A flat buffer as an `(n, width)` array.
+pub fn to_rows<'py>(py: Python<'py>, data: &[f32], name: &str, width: usize) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    Ok(row_array(data, name, width).map_err(PyValueError::new_err)?.into_pyarray(py))
+}
+
+/// An `(n, 3)` array of triangle indices.
+pub fn to_triangles<'py>(py: Python<'py>, indices: &[u32]) -> PyResult<Bound<'py, PyArray2<u32>>> {
+    Ok(row_array(indices, "indices", 3).map_err(PyValueError::new_err)?.into_pyarray(py))
+}
+
+/// RGBA pixels as a `(height, width, 4)` `uint8` array, without copying.
+pub fn to_image<'py>(py: Python<'py>, pixels: Vec<u8>, width: u32, height: u32) -> Bound<'py, PyArray3<u8>> {
+    Array3::from_shape_vec((height as usize, width as usize, 4), pixels)
+        .expect("pixel buffer matches the image size")
+        .into_pyarray(py)
+}
+
+fn check_rows(shape: &[usize], name: &str, width: usize) -> Result<(), String> {
+    let shaped = match shape {
+        [len] => len.is_multiple_of(width),
+        [_, columns] => *columns == width,
+        _ => false,
+    };
+    if !shaped {
+        return Err(format!("{name} must have shape (n, {width}) or (n * {width},), got {shape:?}"));
+    }
+    Ok(())
+}
+
+fn volume_order(view: ArrayViewD<'_, f32>) -> Result<(Vec<f32>, [usize; 3]), String> {
+    let &[nx, ny, nz] = view.shape() else {
+        return Err(format!("volume values must be three-dimensional, got shape {:?}", view.shape()));
+    };
+    Ok((flatten(view.reversed_axes()), [nx, ny, nz]))
+}
+
+fn flatten<T: Copy>(view: ArrayViewD<'_, T>) -> Vec<T> {
+    match view.as_slice() {
+        Some(slice) => slice.to_vec(),
+        None => view.iter().copied().collect(),
+    }
+}
+
+/// Scene buffers loaded from JSON or binary are not validated, so a ragged
+/// buffer is an error here rather than a panic.
+fn row_array<T: Clone>(data: &[T], name: &str, width: usize) -> Result<Array2<T>, String> {
+    if !data.len().is_multiple_of(width) {
+        return Err(format!("{name} length {} is not a multiple of {width}", data.len()));
+    }
+    Array2::from_shape_vec((data.len() / width, width), data.to_vec()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::{ArrayD, IxDyn, ShapeBuilder};
+
+    #[test]
+    fn test_check_rows() {
+        assert!(check_rows(&[4, 3], "positions", 3).is_ok());
+        assert!(check_rows(&[12], "positions", 3).is_ok());
+        let err = check_rows(&[4, 2], "positions", 3).unwrap_err();
+        assert_eq!(err, "positions must have shape (n, 3) or (n * 3,), got [4, 2]");
+        assert!(check_rows(&[7], "indices", 3).is_err());
+        assert!(check_rows(&[2, 3, 1], "normals", 3).is_err());
+    }
+
+    #[test]
+    fn test_flatten_strided_views() {
+        let c_order = ArrayD::from_shape_vec(IxDyn(&[2, 3]), (0..6).collect()).unwrap();
+        assert_eq!(flatten(c_order.view()), [0, 1, 2, 3, 4, 5]);
+
+        let fortran = ArrayD::from_shape_vec(IxDyn(&[2, 3]).f(), vec![0, 3, 1, 4, 2, 5]).unwrap();
+        assert_eq!(flatten(fortran.view()), [0, 1, 2, 3, 4, 5], "logical row-major order");
+        assert_eq!(flatten(c_order.view().reversed_axes()), [0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn test_volume_order_is_x_fastest() {
+        // values[x, y, z] = 100 x + 10 y + z
+        let (nx, ny, nz) = (4, 3, 2);
+        let values =
+            ArrayD::from_shape_fn(IxDyn(&[nx, ny, nz]), |i| (100 * i[0] + 10 * i[1] + i[2]) as f32);
+        let (flat, dimensions) = volume_order(values.view()).unwrap();
+        assert_eq!(dimensions, [4, 3, 2]);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    assert_eq!(flat[x + nx * (y + ny * z)], (100 * x + 10 * y + z) as f32);
+                }
+            }
+        }
+
+        let flat_input = ArrayD::<f32>::zeros(IxDyn(&[4, 4]));
+        let err = volume_order(flat_input.view()).unwrap_err();
+        assert!(err.contains("three-dimensional"), "{err}");
+    }
+
+    #[test]
+    fn test_ragged_buffers_are_errors() {
+        let rows = row_array(&[0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0], "positions", 3).unwrap();
+        assert_eq!(rows.shape(), [2, 3]);
+        assert_eq!(rows[[1, 0]], 3.0);
+        assert!(row_array::<u32>(&[], "indices", 3).unwrap().is_empty());
+
+        let err = row_array(&[0u32, 1, 2, 3], "indices", 3).unwrap_err();
+        assert_eq!(err, "indices length 4 is not a multiple of 3");
+    }
+}
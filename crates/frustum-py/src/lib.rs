@@ -0,0 +1,126 @@
+//! Frustum Python bindings
+//!
+//! Exposes the scene model of `frustum-core` and the headless renderer of
+//! `frustum-render` as the `frustum` Python module. Geometry is passed as
+//! NumPy arrays (or anything NumPy can convert); rendered images come back as
+//! PNG bytes or `(height, width, 4)` `uint8` arrays.
+//!
+//! Build the extension with `maturin develop` from this directory. Rendering
+//! uses a hardware adapter when one exists and a software adapter otherwise.
+
+mod arrays;
+mod scene;
+mod types;
+
+use frustum_render::{RenderConfig, RenderError};
+use numpy::PyArray3;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+pub use scene::PyScene;
+pub use types::{
+    PyAxisBundle, PyCamera, PyLight, PyMesh, PyPointCloud, PyPolyline, PyScalarMappedMaterial, PySolidMaterial,
+    PyVolume,
+};
+
+/// Invalid scenes are the caller's mistake; everything else is a renderer failure.
+fn render_error(error: RenderError) -> PyErr {
+    match error {
+        RenderError::InvalidScene(errors) => PyValueError::new_err(errors.to_string()),
+        other => PyRuntimeError::new_err(other.to_string()),
+    }
+}
+
+fn render_config(width: u32, height: u32, background: [f32; 4]) -> PyResult<RenderConfig> {
+    if width == 0 || height == 0 {
+        return Err(PyValueError::new_err("width and height must be positive"));
+    }
+    Ok(RenderConfig { width, height, background })
+}
+
+/// Extract an isosurface from a volume.
+#[pyfunction]
+fn marching_cubes(py: Python<'_>, volume: PyRef<'_, PyVolume>, iso_value: f32) -> PyMesh {
+    let volume = &volume.inner;
+    let mesh = py.detach(|| frustum_core::marching_cubes(volume, iso_value));
+    PyMesh { inner: mesh }
+}
+
+/// Render a scene to PNG bytes.
+#[pyfunction]
+#[pyo3(signature = (scene, width = 800, height = 600, background = [1.0, 1.0, 1.0, 1.0]))]
+fn render_to_png<'py>(
+    py: Python<'py>,
+    scene: PyRef<'_, PyScene>,
+    width: u32,
+    height: u32,
+    background: [f32; 4],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let config = render_config(width, height, background)?;
+    let scene = &scene.inner;
+    let png = py
+        .detach(|| frustum_render::render_to_png(scene, &config))
+        .map_err(render_error)?;
+    Ok(PyBytes::new(py, &png))
+}
+
+/// Render a scene to a `(height, width, 4)` `uint8` RGBA array.
+#[pyfunction]
+#[pyo3(signature = (scene, width = 800, height = 600, background = [1.0, 1.0, 1.0, 1.0]))]
+fn render_to_array<'py>(
+    py: Python<'py>,
+    scene: PyRef<'_, PyScene>,
+    width: u32,
+    height: u32,
+    background: [f32; 4],
+) -> PyResult<Bound<'py, PyArray3<u8>>> {
+    let config = render_config(width, height, background)?;
+    let scene = &scene.inner;
+    let pixels = py.detach(|| -> Result<Vec<u8>, RenderError> {
+        let png = frustum_render::render_to_png(scene, &config)?;
+        Ok(image::load_from_memory(&png)?.to_rgba8().into_raw())
+    });
+    Ok(arrays::to_image(py, pixels.map_err(render_error)?, width, height))
+}
+
+/// Render a scene and return `(png_bytes, audit)`, with the audit bundle as a dict.
+#[pyfunction]
+#[pyo3(signature = (scene, width = 800, height = 600, background = [1.0, 1.0, 1.0, 1.0]))]
+fn render_with_audit<'py>(
+    py: Python<'py>,
+    scene: PyRef<'_, PyScene>,
+    width: u32,
+    height: u32,
+    background: [f32; 4],
+) -> PyResult<(Bound<'py, PyBytes>, Bound<'py, PyAny>)> {
+    let config = render_config(width, height, background)?;
+    let scene = &scene.inner;
+    let (png, audit) = py
+        .detach(|| frustum_render::render_with_audit(scene, &config))
+        .map_err(render_error)?;
+    let audit = audit.to_json().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let audit = py.import("json")?.call_method1("loads", (audit,))?;
+    Ok((PyBytes::new(py, &png), audit))
+}
+
+#[pymodule]
+fn frustum(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("SCHEMA_VERSION", frustum_core::SCHEMA_VERSION)?;
+    m.add_class::<PyScene>()?;
+    m.add_class::<PyCamera>()?;
+    m.add_class::<PyMesh>()?;
+    m.add_class::<PyPointCloud>()?;
+    m.add_class::<PyPolyline>()?;
+    m.add_class::<PyAxisBundle>()?;
+    m.add_class::<PySolidMaterial>()?;
+    m.add_class::<PyScalarMappedMaterial>()?;
+    m.add_class::<PyLight>()?;
+    m.add_class::<PyVolume>()?;
+    m.add_function(wrap_pyfunction!(marching_cubes, m)?)?;
+    m.add_function(wrap_pyfunction!(render_to_png, m)?)?;
+    m.add_function(wrap_pyfunction!(render_to_array, m)?)?;
+    m.add_function(wrap_pyfunction!(render_with_audit, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! The Python `Scene` class.
+
+use frustum_core::scene::{Bounds, SceneElement};
+use frustum_core::{Material, Scene};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::types::{
+    PyAxisBundle, PyCamera, PyLight, PyMesh, PyPointCloud, PyPolyline, PyScalarMappedMaterial, PySolidMaterial,
+};
+
+/// A complete scene: camera, elements, materials, light and bounds.
+///
+/// The `add_*` methods copy the element into the scene and return the scene,
+/// so calls can be chained.
+#[pyclass(name = "Scene", module = "frustum")]
+#[derive(Clone)]
+pub struct PyScene {
+    pub inner: Scene,
+}
+
+#[pymethods]
+impl PyScene {
+    #[new]
+    fn new(camera: PyRef<'_, PyCamera>, min: [f32; 3], max: [f32; 3]) -> Self {
+        Self { inner: Scene::new(camera.inner.clone(), Bounds { min, max }) }
+    }
+
+    fn add_mesh<'py>(mut slf: PyRefMut<'py, Self>, mesh: PyRef<'_, PyMesh>) -> PyRefMut<'py, Self> {
+        slf.inner.elements.push(SceneElement::Mesh(mesh.inner.clone()));
+        slf
+    }
+
+    fn add_point_cloud<'py>(mut slf: PyRefMut<'py, Self>, cloud: PyRef<'_, PyPointCloud>) -> PyRefMut<'py, Self> {
+        slf.inner.elements.push(SceneElement::PointCloud(cloud.inner.clone()));
+        slf
+    }
+
+    fn add_polyline<'py>(mut slf: PyRefMut<'py, Self>, line: PyRef<'_, PyPolyline>) -> PyRefMut<'py, Self> {
+        slf.inner.elements.push(SceneElement::Polyline(line.inner.clone()));
+        slf
+    }
+
+    fn add_axes<'py>(mut slf: PyRefMut<'py, Self>, axes: PyRef<'_, PyAxisBundle>) -> PyRefMut<'py, Self> {
+        slf.inner.elements.push(SceneElement::Axes(axes.inner.clone()));
+        slf
+    }
+
+    /// Add a `SolidMaterial` or `ScalarMappedMaterial`.
+    fn add_material<'py>(mut slf: PyRefMut<'py, Self>, material: &Bound<'_, PyAny>) -> PyResult<PyRefMut<'py, Self>> {
+        let material = if let Ok(solid) = material.cast::<PySolidMaterial>() {
+            Material::Solid(solid.borrow().inner.clone())
+        } else if let Ok(mapped) = material.cast::<PyScalarMappedMaterial>() {
+            Material::ScalarMapped(mapped.borrow().inner.clone())
+        } else {
+            return Err(PyTypeError::new_err(
+                "material must be a SolidMaterial or ScalarMappedMaterial",
+            ));
+        };
+        slf.inner.materials.push(material);
+        Ok(slf)
+    }
+
+    fn with_light<'py>(mut slf: PyRefMut<'py, Self>, light: PyRef<'_, PyLight>) -> PyRefMut<'py, Self> {
+        slf.inner.light = Some(light.inner);
+        slf
+    }
+
+    #[getter]
+    fn camera(&self) -> PyCamera {
+        PyCamera { inner: self.inner.camera.clone() }
+    }
+
+    #[setter]
+    fn set_camera(&mut self, camera: PyRef<'_, PyCamera>) {
+        self.inner.camera = camera.inner.clone();
+    }
+
+    #[getter]
+    fn light(&self) -> Option<PyLight> {
+        self.inner.light.map(|inner| PyLight { inner })
+    }
+
+    #[getter]
+    fn version(&self) -> String {
+        self.inner.version.clone()
+    }
+
+    #[getter]
+    fn bounds(&self) -> ([f32; 3], [f32; 3]) {
+        (self.inner.bounds.min, self.inner.bounds.max)
+    }
+
+    #[getter]
+    fn material_ids(&self) -> Vec<String> {
+        self.inner.materials.iter().map(|m| m.id().to_string()).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.elements.len()
+    }
+
+    /// Structural problems as `(json_pointer, message)` pairs; empty if valid.
+    fn validate(&self) -> Vec<(String, String)> {
+        match self.inner.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .errors
+                .into_iter()
+                .map(|error| (error.path, error.kind.to_string()))
+                .collect(),
+        }
+    }
+
+    /// SHA-256 of the canonical scene encoding, as lowercase hex.
+    fn content_hash(&self) -> String {
+        self.inner.content_hash()
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        self.inner.to_json().map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Load scene JSON, upgrading older schema versions.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner = Scene::from_json(json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    fn to_binary<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner.to_binary())
+    }
+
+    #[staticmethod]
+    fn from_binary(data: &[u8]) -> PyResult<Self> {
+        let inner = Scene::from_binary(data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Scene(elements={}, materials={})",
+            self.inner.elements.len(),
+            self.inner.materials.len()
+        )
+    }
+}
@@ -0,0 +1,530 @@
+//! Python classes wrapping the scene element types of `frustum-core`.
+
+use frustum_core::{
+    Axis, AxisBounds, AxisBundle, Camera, Colormap, Light, Mesh, PointCloud, Polyline, Projection,
+    ScalarMappedMaterial, Shading, SolidMaterial, TickSpec, Volume,
+};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::arrays::{self, FloatArray, IndexArray};
+
+/// A perspective or orthographic camera.
+#[pyclass(name = "Camera", module = "frustum")]
+#[derive(Clone)]
+pub struct PyCamera {
+    pub inner: Camera,
+}
+
+#[pymethods]
+impl PyCamera {
+    /// Perspective camera; `fov` is the vertical field of view in degrees.
+    #[new]
+    #[pyo3(signature = (position, target, fov = 45.0))]
+    fn new(position: [f32; 3], target: [f32; 3], fov: f32) -> Self {
+        Self { inner: Camera::perspective(position, target, fov) }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (position, target, fov = 45.0))]
+    fn perspective(position: [f32; 3], target: [f32; 3], fov: f32) -> Self {
+        Self { inner: Camera::perspective(position, target, fov) }
+    }
+
+    /// Orthographic camera showing `view_height` world units vertically.
+    #[staticmethod]
+    fn orthographic(position: [f32; 3], target: [f32; 3], view_height: f32) -> Self {
+        Self { inner: Camera::orthographic(position, target, view_height) }
+    }
+
+    #[getter]
+    fn projection(&self) -> &'static str {
+        match self.inner.projection {
+            Projection::Perspective => "perspective",
+            Projection::Orthographic => "orthographic",
+        }
+    }
+
+    #[getter]
+    fn position(&self) -> [f32; 3] {
+        self.inner.position
+    }
+
+    #[setter]
+    fn set_position(&mut self, position: [f32; 3]) {
+        self.inner.position = position;
+    }
+
+    #[getter]
+    fn target(&self) -> [f32; 3] {
+        self.inner.target
+    }
+
+    #[setter]
+    fn set_target(&mut self, target: [f32; 3]) {
+        self.inner.target = target;
+    }
+
+    #[getter]
+    fn up(&self) -> [f32; 3] {
+        self.inner.up
+    }
+
+    #[setter]
+    fn set_up(&mut self, up: [f32; 3]) {
+        self.inner.up = up;
+    }
+
+    #[getter]
+    fn near(&self) -> f32 {
+        self.inner.near
+    }
+
+    #[setter]
+    fn set_near(&mut self, near: f32) {
+        self.inner.near = near;
+    }
+
+    #[getter]
+    fn far(&self) -> f32 {
+        self.inner.far
+    }
+
+    #[setter]
+    fn set_far(&mut self, far: f32) {
+        self.inner.far = far;
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Camera({}, position={:?}, target={:?})",
+            self.projection(),
+            self.inner.position,
+            self.inner.target
+        )
+    }
+}
+
+fn parse_shading(name: &str) -> PyResult<Shading> {
+    match name {
+        "flat" => Ok(Shading::Flat),
+        "smooth" => Ok(Shading::Smooth),
+        other => Err(PyValueError::new_err(format!(
+            "unknown shading '{other}', expected 'flat' or 'smooth'"
+        ))),
+    }
+}
+
+/// A triangle mesh.
+#[pyclass(name = "Mesh", module = "frustum")]
+#[derive(Clone)]
+pub struct PyMesh {
+    pub inner: Mesh,
+}
+
+#[pymethods]
+impl PyMesh {
+    /// `positions` is `(n, 3)` and `indices` is `(m, 3)`; flat arrays are accepted too.
+    #[new]
+    #[pyo3(signature = (positions, indices, normals = None, scalars = None, material = None, shading = None))]
+    fn new(
+        positions: FloatArray<'_>,
+        indices: IndexArray<'_>,
+        normals: Option<FloatArray<'_>>,
+        scalars: Option<FloatArray<'_>>,
+        material: Option<String>,
+        shading: Option<&str>,
+    ) -> PyResult<Self> {
+        let mut mesh = Mesh::new(arrays::rows(&positions, "positions", 3)?, arrays::rows(&indices, "indices", 3)?);
+        if let Some(normals) = normals {
+            mesh = mesh.with_normals(arrays::rows(&normals, "normals", 3)?);
+        }
+        if let Some(scalars) = scalars {
+            mesh = mesh.with_scalars(arrays::values(&scalars, "scalars")?);
+        }
+        if let Some(material) = material {
+            mesh = mesh.with_material(material);
+        }
+        if let Some(shading) = shading {
+            mesh = mesh.with_shading(parse_shading(shading)?);
+        }
+        Ok(Self { inner: mesh })
+    }
+
+    #[getter]
+    fn positions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        arrays::to_rows(py, &self.inner.positions, "positions", 3)
+    }
+
+    #[getter]
+    fn indices<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<u32>>> {
+        arrays::to_triangles(py, &self.inner.indices)
+    }
+
+    #[getter]
+    fn normals<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyArray2<f32>>>> {
+        self.inner.normals.as_deref().map(|normals| arrays::to_rows(py, normals, "normals", 3)).transpose()
+    }
+
+    #[getter]
+    fn scalars<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyArray1<f32>>> {
+        self.inner.scalars.clone().map(|scalars| scalars.into_pyarray(py))
+    }
+
+    #[getter]
+    fn material(&self) -> Option<String> {
+        self.inner.material_id.clone()
+    }
+
+    #[getter]
+    fn vertex_count(&self) -> usize {
+        self.inner.vertex_count()
+    }
+
+    #[getter]
+    fn triangle_count(&self) -> usize {
+        self.inner.triangle_count()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Mesh(vertices={}, triangles={})",
+            self.inner.vertex_count(),
+            self.inner.triangle_count()
+        )
+    }
+}
+
+/// A set of points drawn as screen-space sprites.
+#[pyclass(name = "PointCloud", module = "frustum")]
+#[derive(Clone)]
+pub struct PyPointCloud {
+    pub inner: PointCloud,
+}
+
+#[pymethods]
+impl PyPointCloud {
+    #[new]
+    #[pyo3(signature = (positions, point_size = 1.0, scalars = None, material = None))]
+    fn new(
+        positions: FloatArray<'_>,
+        point_size: f32,
+        scalars: Option<FloatArray<'_>>,
+        material: Option<String>,
+    ) -> PyResult<Self> {
+        let mut cloud = PointCloud::new(arrays::rows(&positions, "positions", 3)?, point_size);
+        if let Some(scalars) = scalars {
+            cloud = cloud.with_scalars(arrays::values(&scalars, "scalars")?);
+        }
+        if let Some(material) = material {
+            cloud = cloud.with_material(material);
+        }
+        Ok(Self { inner: cloud })
+    }
+
+    #[getter]
+    fn positions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        arrays::to_rows(py, &self.inner.positions, "positions", 3)
+    }
+
+    #[getter]
+    fn point_size(&self) -> f32 {
+        self.inner.point_size
+    }
+
+    #[getter]
+    fn material(&self) -> Option<String> {
+        self.inner.material_id.clone()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PointCloud(points={})", self.inner.len())
+    }
+}
+
+/// A connected line strip.
+#[pyclass(name = "Polyline", module = "frustum")]
+#[derive(Clone)]
+pub struct PyPolyline {
+    pub inner: Polyline,
+}
+
+#[pymethods]
+impl PyPolyline {
+    #[new]
+    #[pyo3(signature = (positions, line_width = 1.0, scalars = None, material = None))]
+    fn new(
+        positions: FloatArray<'_>,
+        line_width: f32,
+        scalars: Option<FloatArray<'_>>,
+        material: Option<String>,
+    ) -> PyResult<Self> {
+        let mut line = Polyline::new(arrays::rows(&positions, "positions", 3)?, line_width);
+        if let Some(scalars) = scalars {
+            line = line.with_scalars(arrays::values(&scalars, "scalars")?);
+        }
+        if let Some(material) = material {
+            line = line.with_material(material);
+        }
+        Ok(Self { inner: line })
+    }
+
+    #[getter]
+    fn positions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        arrays::to_rows(py, &self.inner.positions, "positions", 3)
+    }
+
+    #[getter]
+    fn line_width(&self) -> f32 {
+        self.inner.line_width
+    }
+
+    #[getter]
+    fn material(&self) -> Option<String> {
+        self.inner.material_id.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Polyline(vertices={})", self.inner.positions.len() / 3)
+    }
+}
+
+/// Tick placement: an approximate count, or explicit world positions.
+#[derive(FromPyObject)]
+pub enum Ticks {
+    Count(u32),
+    Values(Vec<f32>),
+}
+
+/// Axis lines with ticks and labels over a bounding box.
+#[pyclass(name = "AxisBundle", module = "frustum")]
+#[derive(Clone)]
+pub struct PyAxisBundle {
+    pub inner: AxisBundle,
+}
+
+#[pymethods]
+impl PyAxisBundle {
+    /// `axes` is a string of axis names such as `"xy"`; `ticks=None` hides ticks.
+    #[new]
+    #[pyo3(signature = (id, min, max, axes = "xyz", line_width = 1.0, ticks = Some(Ticks::Count(5)), labels = true))]
+    fn new(
+        id: String,
+        min: [f32; 3],
+        max: [f32; 3],
+        axes: &str,
+        line_width: f32,
+        ticks: Option<Ticks>,
+        labels: bool,
+    ) -> PyResult<Self> {
+        let axes = axes
+            .chars()
+            .map(|c| match c.to_ascii_lowercase() {
+                'x' => Ok(Axis::X),
+                'y' => Ok(Axis::Y),
+                'z' => Ok(Axis::Z),
+                other => Err(PyValueError::new_err(format!("unknown axis '{other}'"))),
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let ticks = match ticks {
+            Some(Ticks::Count(count)) => TickSpec::Auto { count },
+            Some(Ticks::Values(values)) => TickSpec::Fixed { values },
+            None => TickSpec::None,
+        };
+        let mut bundle = AxisBundle::new(id, AxisBounds { min, max })
+            .with_axes(axes)
+            .with_line_width(line_width)
+            .with_ticks(ticks);
+        bundle.labels.show = labels;
+        Ok(Self { inner: bundle })
+    }
+
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AxisBundle('{}')", self.inner.id)
+    }
+}
+
+/// A single flat color.
+#[pyclass(name = "SolidMaterial", module = "frustum")]
+#[derive(Clone)]
+pub struct PySolidMaterial {
+    pub inner: SolidMaterial,
+}
+
+#[pymethods]
+impl PySolidMaterial {
+    /// `color` is RGB or RGBA with components in `[0, 1]`.
+    #[new]
+    fn new(id: String, color: Vec<f32>) -> PyResult<Self> {
+        let inner = match color[..] {
+            [r, g, b] => SolidMaterial::new(id, [r, g, b]),
+            [r, g, b, a] => SolidMaterial::with_alpha(id, [r, g, b, a]),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "color must have 3 or 4 components, got {}",
+                    color.len()
+                )))
+            }
+        };
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id.clone()
+    }
+
+    #[getter]
+    fn color(&self) -> [f32; 4] {
+        self.inner.color
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SolidMaterial('{}', {:?})", self.inner.id, self.inner.color)
+    }
+}
+
+/// Colors elements by their per-vertex scalars through a colormap.
+#[pyclass(name = "ScalarMappedMaterial", module = "frustum")]
+#[derive(Clone)]
+pub struct PyScalarMappedMaterial {
+    pub inner: ScalarMappedMaterial,
+}
+
+#[pymethods]
+impl PyScalarMappedMaterial {
+    #[new]
+    #[pyo3(signature = (id, colormap = "viridis", range = [0.0, 1.0], clamp = true, missing_color = None))]
+    fn new(
+        id: String,
+        colormap: &str,
+        range: [f32; 2],
+        clamp: bool,
+        missing_color: Option<[f32; 4]>,
+    ) -> PyResult<Self> {
+        if Colormap::from_name(colormap).is_none() {
+            return Err(PyValueError::new_err(format!("unknown colormap '{colormap}'")));
+        }
+        let mut inner = ScalarMappedMaterial::new(id, colormap, range).with_clamp(clamp);
+        if let Some(color) = missing_color {
+            inner = inner.with_missing_color(color);
+        }
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id.clone()
+    }
+
+    #[getter]
+    fn colormap(&self) -> String {
+        self.inner.colormap.clone()
+    }
+
+    #[getter]
+    fn range(&self) -> [f32; 2] {
+        self.inner.range
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ScalarMappedMaterial('{}', '{}', {:?})",
+            self.inner.id, self.inner.colormap, self.inner.range
+        )
+    }
+}
+
+/// A directional light.
+#[pyclass(name = "Light", module = "frustum")]
+#[derive(Clone)]
+pub struct PyLight {
+    pub inner: Light,
+}
+
+#[pymethods]
+impl PyLight {
+    /// `direction` points from the light toward the scene and is normalized.
+    #[new]
+    #[pyo3(signature = (direction, intensity = 1.0, enabled = true))]
+    fn new(direction: [f32; 3], intensity: f32, enabled: bool) -> PyResult<Self> {
+        // `Light::new` panics on these; report them as Python errors instead.
+        let length = direction.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if !(length > 1e-6 && length.is_finite()) {
+            return Err(PyValueError::new_err("light direction must be finite and non-zero"));
+        }
+        if !(intensity >= 0.0 && intensity.is_finite()) {
+            return Err(PyValueError::new_err("light intensity must be finite and non-negative"));
+        }
+        Ok(Self { inner: Light::new(direction, intensity).with_enabled(enabled) })
+    }
+
+    #[getter]
+    fn direction(&self) -> [f32; 3] {
+        self.inner.direction
+    }
+
+    #[getter]
+    fn intensity(&self) -> f32 {
+        self.inner.intensity
+    }
+
+    #[getter]
+    fn enabled(&self) -> bool {
+        self.inner.enabled
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Light(direction={:?}, intensity={})",
+            self.inner.direction, self.inner.intensity
+        )
+    }
+}
+
+/// A scalar field on a regular grid.
+#[pyclass(name = "Volume", module = "frustum")]
+#[derive(Clone)]
+pub struct PyVolume {
+    pub inner: Volume,
+}
+
+#[pymethods]
+impl PyVolume {
+    /// `values` is a 3-D array indexed `[x, y, z]`.
+    #[new]
+    #[pyo3(signature = (values, spacing = [1.0, 1.0, 1.0], origin = [0.0, 0.0, 0.0]))]
+    fn new(values: FloatArray<'_>, spacing: [f32; 3], origin: [f32; 3]) -> PyResult<Self> {
+        let (values, dimensions) = arrays::volume_values(&values)?;
+        Ok(Self { inner: Volume::new(values, dimensions, spacing, origin) })
+    }
+
+    #[getter]
+    fn dimensions(&self) -> [usize; 3] {
+        self.inner.dimensions
+    }
+
+    #[getter]
+    fn spacing(&self) -> [f32; 3] {
+        self.inner.spacing
+    }
+
+    #[getter]
+    fn origin(&self) -> [f32; 3] {
+        self.inner.origin
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Volume(dimensions={:?})", self.inner.dimensions)
+    }
+}
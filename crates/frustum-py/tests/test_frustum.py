@@ -0,0 +1,146 @@
+"""Tests for the frustum Python bindings.
+
+Run with `pytest` after `maturin develop`. Rendering tests use whatever
+adapter wgpu finds, including software adapters such as lavapipe.
+"""
+
+import json
+import struct
+
+import numpy as np
+import pytest
+
+import frustum
+
+TRIANGLE_POSITIONS = np.array(
+    [[-0.5, -0.5, 0.0], [0.5, -0.5, 0.0], [0.0, 0.5, 0.0]],
+    dtype=np.float32,
+)
+
+
+def triangle_scene():
+    camera = frustum.Camera.perspective([0.0, 0.0, 2.0], [0.0, 0.0, 0.0], 45.0)
+    mesh = frustum.Mesh(TRIANGLE_POSITIONS, [[0, 1, 2]], material="red")
+    return (
+        frustum.Scene(camera, [-1.0, -1.0, -1.0], [1.0, 1.0, 1.0])
+        .add_material(frustum.SolidMaterial("red", (1.0, 0.0, 0.0)))
+        .add_mesh(mesh)
+    )
+
+
+def png_size(png):
+    assert png[:8] == b"\x89PNG\r\n\x1a\n"
+    return struct.unpack(">II", png[16:24])
+
+
+def sphere_volume(n=16):
+    axis = np.linspace(-1.0, 1.0, n, dtype=np.float32)
+    x, y, z = np.meshgrid(axis, axis, axis, indexing="ij")
+    return np.sqrt(x * x + y * y + z * z)
+
+
+class TestGeometryInput:
+    def test_mesh_accepts_arrays_and_flat_buffers(self):
+        mesh = frustum.Mesh(TRIANGLE_POSITIONS, np.array([0, 1, 2], dtype=np.int64))
+        assert mesh.vertex_count == 3
+        assert mesh.triangle_count == 1
+        np.testing.assert_array_equal(mesh.positions, TRIANGLE_POSITIONS)
+        assert mesh.indices.shape == (1, 3)
+
+    def test_float64_and_strided_input_is_converted(self):
+        positions = np.asfortranarray(TRIANGLE_POSITIONS.astype(np.float64))
+        mesh = frustum.Mesh(positions, [[0, 1, 2]])
+        np.testing.assert_array_equal(mesh.positions, TRIANGLE_POSITIONS)
+
+        wide = np.zeros((3, 6), dtype=np.float32)
+        wide[:, ::2] = TRIANGLE_POSITIONS
+        cloud = frustum.PointCloud(wide[:, ::2], point_size=4.0)
+        assert len(cloud) == 3
+        np.testing.assert_array_equal(cloud.positions, TRIANGLE_POSITIONS)
+
+    def test_bad_shapes_raise_value_error(self):
+        with pytest.raises(ValueError, match="positions"):
+            frustum.Mesh(np.zeros((3, 2), dtype=np.float32), [[0, 1, 2]])
+        with pytest.raises(ValueError, match="scalars"):
+            frustum.PointCloud(TRIANGLE_POSITIONS, scalars=np.zeros((3, 1)))
+        with pytest.raises(ValueError, match="three-dimensional"):
+            frustum.Volume(np.zeros((4, 4)))
+
+    def test_volume_is_indexed_xyz(self):
+        values = np.zeros((4, 3, 2), dtype=np.float32)
+        volume = frustum.Volume(values, spacing=(0.5, 0.5, 0.5))
+        assert volume.dimensions == [4, 3, 2]
+
+    def test_marching_cubes(self):
+        volume = frustum.Volume(sphere_volume(), spacing=(2 / 15,) * 3, origin=(-1.0, -1.0, -1.0))
+        mesh = frustum.marching_cubes(volume, 0.6)
+        assert mesh.triangle_count > 0
+        radii = np.linalg.norm(mesh.positions, axis=1)
+        assert np.all(np.abs(radii - 0.6) < 0.05)
+        assert mesh.normals.shape == mesh.positions.shape
+
+
+class TestScene:
+    def test_invalid_arguments_raise_instead_of_panicking(self):
+        with pytest.raises(ValueError):
+            frustum.Light((0.0, 0.0, 0.0))
+        with pytest.raises(ValueError):
+            frustum.SolidMaterial("x", (1.0, 0.0))
+        with pytest.raises(ValueError):
+            frustum.ScalarMappedMaterial("x", "not-a-colormap")
+        with pytest.raises(ValueError):
+            frustum.AxisBundle("axes", [0, 0, 0], [1, 1, 1], axes="xw")
+        with pytest.raises(TypeError):
+            triangle_scene().add_material("red")
+
+    def test_validate_reports_json_pointer_paths(self):
+        camera = frustum.Camera([0.0, 0.0, 2.0], [0.0, 0.0, 0.0])
+        scene = frustum.Scene(camera, [-1.0] * 3, [1.0] * 3)
+        scene.add_mesh(frustum.Mesh(TRIANGLE_POSITIONS, [[0, 1, 7]], material="missing"))
+        paths = [path for path, _ in scene.validate()]
+        assert "/elements/0/indices/2" in paths
+        assert "/elements/0/material_id" in paths
+        assert triangle_scene().validate() == []
+
+    def test_serialization_round_trips(self):
+        scene = triangle_scene().with_light(frustum.Light((0.0, 0.0, -1.0)))
+        scene.add_axes(frustum.AxisBundle("axes", [-1, -1, -1], [1, 1, 1], ticks=None))
+        scene.add_polyline(frustum.Polyline(TRIANGLE_POSITIONS, line_width=2.0))
+
+        from_json = frustum.Scene.from_json(scene.to_json())
+        from_binary = frustum.Scene.from_binary(scene.to_binary())
+        assert from_json.content_hash() == scene.content_hash()
+        assert from_binary.content_hash() == scene.content_hash()
+        assert scene.version == frustum.SCHEMA_VERSION
+        assert len(scene) == 3
+
+    def test_from_json_errors_are_value_errors(self):
+        with pytest.raises(ValueError, match="newer"):
+            frustum.Scene.from_json(json.dumps({"version": "frustum/scene/v99"}))
+
+
+class TestRendering:
+    def test_render_to_png(self):
+        png = frustum.render_to_png(triangle_scene(), width=64, height=48)
+        assert png_size(png) == (64, 48)
+
+    def test_render_to_array(self):
+        pixels = frustum.render_to_array(triangle_scene(), width=64, height=48, background=(0, 0, 1, 1))
+        assert pixels.shape == (48, 64, 4)
+        assert pixels.dtype == np.uint8
+        # Background in the corner, the red triangle in the middle.
+        np.testing.assert_array_equal(pixels[0, 0], [0, 0, 255, 255])
+        assert pixels[24, 32, 0] > 200 and pixels[24, 32, 2] < 50
+
+    def test_render_with_audit(self):
+        scene = triangle_scene()
+        png, audit = frustum.render_with_audit(scene, width=64, height=48)
+        assert png_size(png) == (64, 48)
+        assert audit["metadata"]["scene_hash"] == scene.content_hash()
+        assert audit["metadata"]["resolution"] == [64, 48]
+
+    def test_invalid_scene_is_rejected_before_rendering(self):
+        scene = triangle_scene()
+        scene.add_mesh(frustum.Mesh(TRIANGLE_POSITIONS, [[0, 1, 9]]))
+        with pytest.raises(ValueError, match="/elements/1/indices/2"):
+            frustum.render_to_png(scene)
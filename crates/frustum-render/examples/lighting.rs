@@ -35,6 +35,8 @@ fn create_sphere_mesh() -> frustum_core::Mesh {
         dimensions: [resolution, resolution, resolution],
         spacing: [spacing, spacing, spacing],
         origin,
+        mask: None,
+        coordinates: None,
     };
 
     marching_cubes(&volume, 0.0)
@@ -35,6 +35,8 @@ fn main() {
         dimensions: [resolution, resolution, resolution],
         spacing: [spacing, spacing, spacing],
         origin,
+        mask: None,
+        coordinates: None,
     };
 
     // Extract isosurface at distance = 0
@@ -4,6 +4,7 @@
 //! The audit bundle contains metadata, geometry probes, and image-derived
 //! metrics that allow checking invariants without evaluating raw pixels.
 
+use frustum_core::ExtractionReport;
 use serde::{Deserialize, Serialize};
 
 /// Complete audit bundle emitted alongside a render.
@@ -22,7 +23,7 @@ pub struct AuditBundle {
 /// Structural metadata about the render.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenderMetadata {
-    /// Hash of the scene JSON for identity checking.
+    /// `Scene::content_hash` of the rendered scene, for identity checks and caching.
     pub scene_hash: String,
     /// Frustum schema version.
     pub schema_version: String,
@@ -188,6 +189,28 @@ impl AuditBundle {
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    /// Record the outcome of an isosurface extraction that produced scene geometry.
+    ///
+    /// Cells skipped because of invalid voxels (NaN or masked out) become a
+    /// geometry warning, since the surface has holes there. Use
+    /// `ExtractionReport::for_volume` for extractions that return only a mesh.
+    pub fn record_extraction(&mut self, report: &ExtractionReport) {
+        if report.skipped_cells > 0 {
+            self.invariants.warning(
+                InvariantCategory::Geometry,
+                format!(
+                    "Isosurface extraction skipped {} of {} cells touching invalid voxels",
+                    report.skipped_cells, report.total_cells
+                ),
+            );
+        } else {
+            self.invariants.note(format!(
+                "Isosurface extraction used all {} cells",
+                report.total_cells
+            ));
+        }
+    }
 }
 
 impl InvariantResults {
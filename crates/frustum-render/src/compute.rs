@@ -0,0 +1,468 @@
+//! GPU compute path for geometry generation.
+//!
+//! Implements Extension 6 (GPU-accelerated geometry generation, from
+//! 009-extensions.md) for Marching Cubes. This is an implementation
+//! optimization only: the output `Mesh` has exactly the topology, vertex order
+//! and index order of `frustum_core::marching_cubes`, with positions and
+//! normals equal up to floating-point rounding.
+//!
+//! The compute shader classifies cells and interpolates every edge crossing in
+//! parallel. Vertex welding and triangle emission run on the CPU in the serial
+//! scan order, using the shared lookup tables, which keeps the output
+//! deterministic regardless of GPU scheduling.
+
+use bytemuck::{Pod, Zeroable};
+use frustum_core::marching_cubes::{EDGE_TABLE, TRI_TABLE};
+use frustum_core::{Mesh, Volume};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use wgpu::util::DeviceExt;
+
+use crate::{request_adapter, RenderError, RenderMetadata};
+
+/// Invocations per workgroup (must match `@workgroup_size` in the shader).
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Cube index written for cells touching an invalid voxel (matches the shader).
+const SKIPPED_CELL: u32 = u32::MAX;
+
+/// Bytes of edge output per grid point: three edges of two `vec4<f32>` each.
+const EDGE_BYTES_PER_POINT: u64 = 3 * 2 * 16;
+
+/// Cell edge to grid edge: (dx, dy, plane offset, axis) relative to the cell's lowest corner.
+///
+/// Matches the corner numbering of `EDGE_CORNERS` in `frustum_core::marching_cubes`.
+const EDGE_TO_GRID: [(usize, usize, usize, usize); 12] = [
+    (0, 0, 0, 0), (1, 0, 0, 1), (0, 1, 0, 0), (0, 0, 0, 1), // Bottom face
+    (0, 0, 1, 0), (1, 0, 1, 1), (0, 1, 1, 0), (0, 0, 1, 1), // Top face
+    (0, 0, 0, 2), (1, 0, 0, 2), (1, 1, 0, 2), (0, 1, 0, 2), // Vertical edges
+];
+
+/// Uniform parameters for one chunk dispatch.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ComputeParams {
+    dims: [u32; 4],
+    chunk: [u32; 4],
+    spacing: [f32; 4],
+    origin: [f32; 4],
+}
+
+/// GPU results for one plane of grid points.
+struct PlaneEdges {
+    /// Per point and axis: position (w = crossing flag), then normal.
+    edges: Vec<[f32; 4]>,
+    /// Cube index of the cell whose lowest corner is each point.
+    cubes: Vec<u32>,
+    /// Vertex ids assigned to edges leaving each point, u32::MAX if none yet.
+    ids: Vec<u32>,
+}
+
+/// Compute pipeline for Marching Cubes.
+struct MarchingCubesPipeline {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    metadata: RenderMetadata,
+}
+
+impl MarchingCubesPipeline {
+    async fn new() -> Result<Self, RenderError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+
+        let adapter = request_adapter(&instance).await?;
+
+        let adapter_info = adapter.get_info();
+        let metadata = RenderMetadata {
+            backend: format!("{:?}", adapter_info.backend),
+            adapter_name: adapter_info.name.clone(),
+        };
+
+        log::info!(
+            "Using compute adapter: {} (backend: {:?})",
+            adapter_info.name,
+            adapter_info.backend
+        );
+
+        // Large volumes benefit from the adapter's full buffer limits
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Marching Cubes Device"),
+                    required_limits: adapter.limits(),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Marching Cubes Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!(
+                "shaders/marching_cubes.wgsl"
+            ))),
+        });
+
+        let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Marching Cubes Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(1, true),
+                storage_entry(2, false),
+                storage_entry(3, false),
+                storage_entry(4, true),
+                storage_entry(5, true),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Marching Cubes Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Marching Cubes Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            bind_group_layout,
+            metadata,
+        })
+    }
+
+    /// Number of point planes processed per dispatch, bounded by the device's buffer limits.
+    fn planes_per_chunk(&self, plane_points: usize) -> Result<usize, RenderError> {
+        let limits = self.device.limits();
+        let max_bytes = (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size);
+        let plane_bytes = plane_points as u64 * EDGE_BYTES_PER_POINT;
+        if plane_bytes > max_bytes {
+            return Err(RenderError::VolumeTooLarge);
+        }
+        Ok((max_bytes / plane_bytes) as usize)
+    }
+
+    /// Run the shader over point planes `z_start..z_start + z_count`.
+    fn dispatch_chunk(
+        &self,
+        volume: &Volume,
+        iso_value: f32,
+        z_start: usize,
+        z_count: usize,
+    ) -> Result<Vec<PlaneEdges>, RenderError> {
+        let [nx, ny, nz] = volume.dimensions;
+        let plane = nx * ny;
+
+        // Values for the chunk plus the neighbouring planes needed by gradients and top corners
+        let first_plane = z_start.saturating_sub(1);
+        let last_plane = (z_start + z_count + 1).min(nz - 1);
+        let uploaded = first_plane * plane..(last_plane + 1) * plane;
+
+        // Invalid voxels go up as a cleared flag with a zero value, so the shader never sees NaN
+        let valid: Vec<u32> = uploaded
+            .clone()
+            .map(|i| {
+                let (x, y, z) = (i % nx, (i / nx) % ny, i / plane);
+                volume.is_valid(x, y, z) as u32
+            })
+            .collect();
+        let values: Vec<f32> = uploaded
+            .zip(&valid)
+            .map(|(i, &ok)| if ok != 0 { volume.values[i] } else { 0.0 })
+            .collect();
+
+        let invocations = (plane * z_count) as u32;
+        let groups = invocations.div_ceil(WORKGROUP_SIZE);
+        let max_groups = self.device.limits().max_compute_workgroups_per_dimension;
+        let groups_x = groups.min(max_groups);
+        let groups_y = groups.div_ceil(groups_x);
+
+        let params = ComputeParams {
+            dims: [nx as u32, ny as u32, nz as u32, volume.is_rectilinear() as u32],
+            chunk: [
+                z_start as u32,
+                z_count as u32,
+                first_plane as u32,
+                groups_x * WORKGROUP_SIZE,
+            ],
+            spacing: [volume.spacing[0], volume.spacing[1], volume.spacing[2], iso_value],
+            origin: [volume.origin[0], volume.origin[1], volume.origin[2], 0.0],
+        };
+
+        let params_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Cubes Params"),
+            contents: bytemuck::cast_slice(&[params]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let values_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Cubes Values"),
+            contents: bytemuck::cast_slice(&values),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let coords: Vec<f32> = (0..3)
+            .flat_map(|axis| (0..volume.dimensions[axis]).map(move |i| volume.axis_coordinate(axis, i)))
+            .collect();
+        let coords_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Cubes Coordinates"),
+            contents: bytemuck::cast_slice(&coords),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let valid_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marching Cubes Validity"),
+            contents: bytemuck::cast_slice(&valid),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let edges_size = plane as u64 * z_count as u64 * EDGE_BYTES_PER_POINT;
+        let cubes_size = (plane * z_count * 4) as u64;
+        let edges_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Marching Cubes Edges"),
+            size: edges_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let cubes_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Marching Cubes Cube Indices"),
+            size: cubes_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Marching Cubes Readback"),
+            size: edges_size + cubes_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marching Cubes Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: values_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: edges_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: cubes_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: valid_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: coords_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Marching Cubes Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Marching Cubes Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(groups_x, groups_y, 1);
+        }
+        encoder.copy_buffer_to_buffer(&edges_buffer, 0, &readback_buffer, 0, edges_size);
+        encoder.copy_buffer_to_buffer(&cubes_buffer, 0, &readback_buffer, edges_size, cubes_size);
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        // Map buffer and read data
+        let buffer_slice = readback_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()
+            .unwrap()
+            .map_err(|_| RenderError::BufferMapping)?;
+
+        let data = buffer_slice.get_mapped_range();
+        let (edge_bytes, cube_bytes) = data.split_at(edges_size as usize);
+        let edges: &[[f32; 4]] = bytemuck::cast_slice(edge_bytes);
+        let cubes: &[u32] = bytemuck::cast_slice(cube_bytes);
+
+        let planes = (0..z_count)
+            .map(|k| PlaneEdges {
+                edges: edges[k * plane * 6..(k + 1) * plane * 6].to_vec(),
+                cubes: cubes[k * plane..(k + 1) * plane].to_vec(),
+                ids: vec![u32::MAX; plane * 3],
+            })
+            .collect();
+
+        drop(data);
+        readback_buffer.unmap();
+
+        Ok(planes)
+    }
+
+    fn extract(&self, volume: &Volume, iso_value: f32) -> Result<Mesh, RenderError> {
+        let [nx, ny, nz] = volume.dimensions;
+
+        // Validate dimensions
+        if nx < 2 || ny < 2 || nz < 2 {
+            return Ok(Mesh::new(vec![], vec![]));
+        }
+
+        let planes_per_chunk = self.planes_per_chunk(nx * ny)?;
+        let mut positions: Vec<f32> = Vec::new();
+        let mut normals: Vec<f32> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        // Planes z and z+1 must both be resident to assemble the cell layer between them
+        let mut window: VecDeque<PlaneEdges> = VecDeque::new();
+        let mut layer = 0;
+        let mut z_start = 0;
+        while z_start < nz {
+            let z_count = planes_per_chunk.min(nz - z_start);
+            window.extend(self.dispatch_chunk(volume, iso_value, z_start, z_count)?);
+            z_start += z_count;
+
+            while window.len() >= 2 && layer < nz - 1 {
+                let (bottom, top) = window.make_contiguous().split_at_mut(1);
+                assemble_layer(
+                    nx,
+                    ny,
+                    &mut bottom[0],
+                    &mut top[0],
+                    &mut positions,
+                    &mut normals,
+                    &mut indices,
+                );
+                window.pop_front();
+                layer += 1;
+            }
+        }
+
+        log::debug!(
+            "GPU marching cubes on {}: {} vertices, {} triangles",
+            self.metadata.adapter_name,
+            positions.len() / 3,
+            indices.len() / 3
+        );
+
+        Ok(Mesh::new(positions, indices).with_normals(normals))
+    }
+}
+
+/// Weld vertices and emit triangles for the cell layer between two planes.
+///
+/// Visits cells and edges in the same order as the CPU extractor, so vertex
+/// and index order match it exactly.
+fn assemble_layer(
+    nx: usize,
+    ny: usize,
+    bottom: &mut PlaneEdges,
+    top: &mut PlaneEdges,
+    positions: &mut Vec<f32>,
+    normals: &mut Vec<f32>,
+    indices: &mut Vec<u32>,
+) {
+    for y in 0..(ny - 1) {
+        for x in 0..(nx - 1) {
+            let cube_index = bottom.cubes[x + y * nx];
+
+            // Skip cells touching invalid voxels, and those entirely inside or outside
+            if cube_index == SKIPPED_CELL || cube_index == 0 || cube_index == 255 {
+                continue;
+            }
+
+            let cube_index = cube_index as usize;
+            let edge_flags = EDGE_TABLE[cube_index];
+            let mut edge_vertices = [u32::MAX; 12];
+            for (edge, &(dx, dy, dz, axis)) in EDGE_TO_GRID.iter().enumerate() {
+                if edge_flags & (1 << edge) == 0 {
+                    continue;
+                }
+
+                let plane = if dz == 0 { &mut *bottom } else { &mut *top };
+                let point = (x + dx) + (y + dy) * nx;
+                let slot = point * 3 + axis;
+                if plane.ids[slot] == u32::MAX {
+                    let position = plane.edges[slot * 2];
+                    let normal = plane.edges[slot * 2 + 1];
+                    debug_assert_eq!(position[3], 1.0, "GPU and table disagree on edge crossing");
+
+                    plane.ids[slot] = (positions.len() / 3) as u32;
+                    positions.extend_from_slice(&position[..3]);
+                    normals.extend_from_slice(&normal[..3]);
+                }
+                edge_vertices[edge] = plane.ids[slot];
+            }
+
+            // Generate triangles
+            let tri_table = &TRI_TABLE[cube_index];
+            let mut i = 0;
+            while tri_table[i] != -1 {
+                indices.push(edge_vertices[tri_table[i] as usize]);
+                indices.push(edge_vertices[tri_table[i + 1] as usize]);
+                indices.push(edge_vertices[tri_table[i + 2] as usize]);
+                i += 3;
+            }
+        }
+    }
+}
+
+/// Extract an isosurface from a volume with a wgpu compute shader.
+///
+/// Produces the same `Mesh` as `frustum_core::marching_cubes` (identical
+/// topology and vertex order; positions and normals within floating-point
+/// rounding), including skipped cells around invalid voxels and rectilinear
+/// grids. Uses a hardware adapter when available and falls back to a software
+/// adapter otherwise. Large volumes are processed in z-chunks sized to the
+/// device's storage buffer limits.
+pub fn marching_cubes_gpu(volume: &Volume, iso_value: f32) -> Result<Mesh, RenderError> {
+    pollster::block_on(marching_cubes_gpu_async(volume, iso_value))
+}
+
+async fn marching_cubes_gpu_async(volume: &Volume, iso_value: f32) -> Result<Mesh, RenderError> {
+    let pipeline = MarchingCubesPipeline::new().await?;
+    pipeline.extract(volume, iso_value)
+}
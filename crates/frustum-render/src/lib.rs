@@ -3,21 +3,21 @@
 //! GPU rendering backend for Frustum using wgpu.
 
 pub mod audit;
+pub mod compute;
 pub mod font;
 pub mod invariants;
 pub mod metrics;
 pub mod primitives;
 
 use bytemuck::{Pod, Zeroable};
-use frustum_core::Scene;
+use frustum_core::{Scene, ValidationErrors};
 use glam::Mat4;
 use std::borrow::Cow;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 use thiserror::Error;
 use wgpu::util::DeviceExt;
 
 pub use audit::AuditBundle;
+pub use compute::marching_cubes_gpu;
 pub use invariants::{compare_for_regression, RegressionResult, RegressionTolerance};
 pub use primitives::{ExpandedLabel, SimpleVertex, TextVertex};
 
@@ -32,6 +32,10 @@ pub enum RenderError {
     PngEncoding(#[from] image::ImageError),
     #[error("Buffer mapping failed")]
     BufferMapping,
+    #[error("Volume plane exceeds GPU buffer limits")]
+    VolumeTooLarge,
+    #[error("{0}")]
+    InvalidScene(#[from] ValidationErrors),
 }
 
 /// Render configuration.
@@ -107,6 +111,28 @@ struct Renderer {
     metadata: RenderMetadata,
 }
 
+/// Request a hardware adapter, falling back to a software one (e.g. lavapipe).
+pub(crate) async fn request_adapter(instance: &wgpu::Instance) -> Result<wgpu::Adapter, RenderError> {
+    let hardware = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })
+        .await;
+    match hardware {
+        Some(adapter) => Ok(adapter),
+        None => instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::None,
+                compatible_surface: None,
+                force_fallback_adapter: true,
+            })
+            .await
+            .ok_or(RenderError::AdapterCreation),
+    }
+}
+
 impl Renderer {
     async fn new() -> Result<Self, RenderError> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
@@ -114,14 +140,7 @@ impl Renderer {
             ..Default::default()
         });
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: None,
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or(RenderError::AdapterCreation)?;
+        let adapter = request_adapter(&instance).await?;
 
         let adapter_info = adapter.get_info();
         let metadata = RenderMetadata {
@@ -588,12 +607,14 @@ impl Renderer {
 
 /// Render a scene to a PNG image.
 ///
-/// This is the primary entry point for headless rendering.
+/// This is the primary entry point for headless rendering. The scene is
+/// validated first; structural errors are returned as `RenderError::InvalidScene`.
 pub fn render_to_png(scene: &Scene, config: &RenderConfig) -> Result<Vec<u8>, RenderError> {
     pollster::block_on(render_to_png_async(scene, config))
 }
 
 async fn render_to_png_async(scene: &Scene, config: &RenderConfig) -> Result<Vec<u8>, RenderError> {
+    scene.validate()?;
     let renderer = Renderer::new().await?;
 
     let aspect_ratio = config.width as f32 / config.height as f32;
@@ -615,6 +636,7 @@ async fn render_to_png_async(scene: &Scene, config: &RenderConfig) -> Result<Vec
 ///
 /// Returns both the PNG data and a structured audit bundle containing
 /// metadata, geometry probes, image metrics, and invariant check results.
+/// Scenes that fail `Scene::validate` are rejected before rendering.
 pub fn render_with_audit(
     scene: &Scene,
     config: &RenderConfig,
@@ -629,6 +651,7 @@ async fn render_with_audit_async(
     use crate::audit::*;
     use frustum_core::scene::SceneElement;
 
+    scene.validate()?;
     let renderer = Renderer::new().await?;
 
     let aspect_ratio = config.width as f32 / config.height as f32;
@@ -674,16 +697,12 @@ async fn render_with_audit_async(
         }
     }
 
-    // Compute scene hash
-    let scene_json = scene.to_json().unwrap_or_default();
-    let mut hasher = DefaultHasher::new();
-    scene_json.hash(&mut hasher);
-    let scene_hash = format!("{:016x}", hasher.finish());
+    let scene_hash = scene.content_hash();
 
     // Build metadata
     let metadata = RenderMetadata {
         scene_hash,
-        schema_version: "frustum/scene/v1".to_string(),
+        schema_version: scene.version.clone(),
         renderer_version: env!("CARGO_PKG_VERSION").to_string(),
         backend: renderer.metadata.backend.clone(),
         adapter: renderer.metadata.adapter_name.clone(),
@@ -816,42 +835,13 @@ fn compute_camera_basis(camera: &frustum_core::Camera) -> (glam::Vec3, glam::Vec
     (right, up)
 }
 
-/// Get color for a scalar value using a material's colormap.
-fn scalar_to_color(
-    scalar: f32,
-    material: &frustum_core::ScalarMappedMaterial,
-) -> [f32; 3] {
-    use frustum_core::Colormap;
-
-    if !scalar.is_finite() {
-        return [material.missing_color[0], material.missing_color[1], material.missing_color[2]];
-    }
-
-    let [min, max] = material.range;
-    let t = if max > min {
-        (scalar - min) / (max - min)
-    } else {
-        0.5
-    };
-
-    let t = if material.clamp { t.clamp(0.0, 1.0) } else { t };
-
-    if let Some(cmap) = Colormap::from_name(&material.colormap) {
-        cmap.sample(t)
-    } else {
-        // Unknown colormap, use grayscale
-        let v = t.clamp(0.0, 1.0);
-        [v, v, v]
-    }
-}
-
 /// Get solid color from a material (RGB).
 fn get_solid_color(material: &frustum_core::Material) -> [f32; 3] {
     match material {
         frustum_core::Material::Solid(m) => [m.color[0], m.color[1], m.color[2]],
         frustum_core::Material::ScalarMapped(m) => {
             // For scalar-mapped without scalars, use middle of range
-            scalar_to_color((m.range[0] + m.range[1]) / 2.0, m)
+            m.color((m.range[0] + m.range[1]) / 2.0)
         }
     }
 }
@@ -859,6 +849,7 @@ fn get_solid_color(material: &frustum_core::Material) -> [f32; 3] {
 /// Convert scene elements to separate vertex arrays for meshes, points, lines, and labels.
 fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<SimpleVertex>, Vec<primitives::ExpandedLabel>, f32) {
     use frustum_core::scene::SceneElement;
+    use frustum_core::{NormalOptions, Shading};
     use glam::Vec3;
 
     let mut mesh_vertices = Vec::new();
@@ -877,6 +868,20 @@ fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<Simp
     for element in &scene.elements {
         match element {
             SceneElement::Mesh(mesh) => {
+                // Smooth shading without stored normals generates them; flat ignores them
+                let generated;
+                let mesh = match mesh.shading {
+                    Some(Shading::Smooth) if mesh.normals.is_none() => {
+                        generated = mesh.compute_normals(&NormalOptions::new());
+                        &generated
+                    }
+                    _ => mesh,
+                };
+                let use_mesh_normals = match mesh.shading {
+                    Some(Shading::Flat) => false,
+                    Some(Shading::Smooth) | None => mesh.normals.is_some(),
+                };
+
                 // Get material color or use scalar mapping
                 let material = mesh.material_id.as_ref().and_then(|id| scene.get_material(id));
                 let has_scalars = mesh.scalars.is_some();
@@ -894,7 +899,6 @@ fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<Simp
                     let edge1 = p1 - p0;
                     let edge2 = p2 - p0;
                     let face_normal = edge1.cross(edge2).normalize_or_zero();
-                    let use_mesh_normals = mesh.normals.is_some();
 
                     for &index in chunk {
                         let i = index as usize;
@@ -910,7 +914,7 @@ fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<Simp
                             let scalars = mesh.scalars.as_ref().unwrap();
                             let scalar = scalars.get(i).copied().unwrap_or(0.0);
                             if let Some(frustum_core::Material::ScalarMapped(sm)) = material {
-                                scalar_to_color(scalar, sm)
+                                sm.color(scalar)
                             } else {
                                 default_mesh_color
                             }
@@ -937,7 +941,7 @@ fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<Simp
                         let scalars = pc.scalars.as_ref().unwrap();
                         let scalar = scalars.get(i).copied().unwrap_or(0.0);
                         if let Some(frustum_core::Material::ScalarMapped(sm)) = material {
-                            scalar_to_color(scalar, sm)
+                            sm.color(scalar)
                         } else {
                             default_point_color
                         }
@@ -958,15 +962,21 @@ fn scene_to_vertices(scene: &Scene) -> (Vec<Vertex>, Vec<SimpleVertex>, Vec<Simp
 
                 let material = line.material_id.as_ref().and_then(|id| scene.get_material(id));
                 let color = material.map(|m| get_solid_color(m)).unwrap_or(default_line_color);
+                let vertex_color = |i: usize| match (material, line.scalars.as_ref()) {
+                    (Some(frustum_core::Material::ScalarMapped(sm)), Some(scalars)) => {
+                        sm.color(scalars.get(i).copied().unwrap_or(0.0))
+                    }
+                    _ => color,
+                };
 
                 for i in 0..(vertex_count.saturating_sub(1)) {
                     line_vertices.push(SimpleVertex {
                         position: [line.positions[i * 3], line.positions[i * 3 + 1], line.positions[i * 3 + 2]],
-                        color,
+                        color: vertex_color(i),
                     });
                     line_vertices.push(SimpleVertex {
                         position: [line.positions[(i + 1) * 3], line.positions[(i + 1) * 3 + 1], line.positions[(i + 1) * 3 + 2]],
-                        color,
+                        color: vertex_color(i + 1),
                     });
                 }
             }
@@ -0,0 +1,245 @@
+// Marching Cubes compute shader for Frustum
+// One invocation per grid point of a z-chunk. Each invocation:
+//   - classifies the cell whose lowest corner is this point (cube index)
+//   - interpolates the vertex on each of the three grid edges leaving this point
+// Topology is assembled on the CPU from the shared lookup tables.
+// Invalid voxels arrive as 0.0 with a cleared validity flag, never as NaN.
+
+struct Params {
+    dims: vec4<u32>,      // nx, ny, nz, 1 if rectilinear
+    chunk: vec4<u32>,     // first z, z count, first uploaded plane, invocations per dispatch row
+    spacing: vec4<f32>,   // dx, dy, dz, iso value
+    origin: vec4<f32>,    // origin xyz, unused
+}
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+
+@group(0) @binding(1)
+var<storage, read> values: array<f32>;
+
+// Per point and axis: (position, crossing flag), (normal, unused)
+@group(0) @binding(2)
+var<storage, read_write> edges: array<vec4<f32>>;
+
+// Cube index per cell; SKIPPED_CELL where a corner voxel is invalid
+@group(0) @binding(3)
+var<storage, read_write> cubes: array<u32>;
+
+// Per uploaded point: 1 if the voxel is valid (finite and not masked out)
+@group(0) @binding(4)
+var<storage, read> valid: array<u32>;
+
+// Rectilinear grids: x coordinates, then y, then z
+@group(0) @binding(5)
+var<storage, read> coords: array<f32>;
+
+const SKIPPED_CELL: u32 = 0xffffffffu;
+
+fn rectilinear() -> bool {
+    return params.dims.w != 0u;
+}
+
+fn axis_offset(axis: u32) -> u32 {
+    if (axis == 0u) {
+        return 0u;
+    } else if (axis == 1u) {
+        return params.dims.x;
+    }
+    return params.dims.x + params.dims.y;
+}
+
+// Distance from grid index i to i + 1 along an axis
+fn step(axis: u32, i: u32) -> f32 {
+    if (rectilinear()) {
+        let o = axis_offset(axis);
+        return coords[o + i + 1u] - coords[o + i];
+    }
+    return params.spacing[axis];
+}
+
+fn point_index(x: u32, y: u32, z: u32) -> u32 {
+    let nx = params.dims.x;
+    let ny = params.dims.y;
+    return x + y * nx + (z - params.chunk.z) * nx * ny;
+}
+
+fn value(x: u32, y: u32, z: u32) -> f32 {
+    return values[point_index(x, y, z)];
+}
+
+fn is_valid(x: u32, y: u32, z: u32) -> bool {
+    return valid[point_index(x, y, z)] != 0u;
+}
+
+// Central difference, one-sided when a neighbour is missing or invalid, zero if both are.
+// hm and hp are the steps to the previous and next grid points.
+fn derivative(prev_ok: bool, prev: f32, here: f32, next_ok: bool, next: f32, hm: f32, hp: f32) -> f32 {
+    if (prev_ok && next_ok) {
+        if (rectilinear()) {
+            return (hm * hm * next - hp * hp * prev + (hp * hp - hm * hm) * here) / (hm * hp * (hm + hp));
+        }
+        return (next - prev) / (2.0 * hp);
+    } else if (next_ok) {
+        return (next - here) / hp;
+    } else if (prev_ok) {
+        return (here - prev) / hm;
+    }
+    return 0.0;
+}
+
+// Gradient at a grid point (matches central_gradient in frustum-core)
+fn gradient(x: u32, y: u32, z: u32) -> vec3<f32> {
+    let n = params.dims.xyz;
+    let here = value(x, y, z);
+    var g: vec3<f32>;
+
+    var prev_ok = false;
+    var prev = 0.0;
+    var hm = 1.0;
+    var next_ok = false;
+    var next = 0.0;
+    var hp = 1.0;
+
+    if (x > 0u) {
+        prev_ok = is_valid(x - 1u, y, z);
+        prev = value(x - 1u, y, z);
+        hm = step(0u, x - 1u);
+    }
+    if (x + 1u < n.x) {
+        next_ok = is_valid(x + 1u, y, z);
+        next = value(x + 1u, y, z);
+        hp = step(0u, x);
+    }
+    g.x = derivative(prev_ok, prev, here, next_ok, next, hm, hp);
+
+    prev_ok = false;
+    next_ok = false;
+    if (y > 0u) {
+        prev_ok = is_valid(x, y - 1u, z);
+        prev = value(x, y - 1u, z);
+        hm = step(1u, y - 1u);
+    }
+    if (y + 1u < n.y) {
+        next_ok = is_valid(x, y + 1u, z);
+        next = value(x, y + 1u, z);
+        hp = step(1u, y);
+    }
+    g.y = derivative(prev_ok, prev, here, next_ok, next, hm, hp);
+
+    prev_ok = false;
+    next_ok = false;
+    if (z > 0u) {
+        prev_ok = is_valid(x, y, z - 1u);
+        prev = value(x, y, z - 1u);
+        hm = step(2u, z - 1u);
+    }
+    if (z + 1u < n.z) {
+        next_ok = is_valid(x, y, z + 1u);
+        next = value(x, y, z + 1u);
+        hp = step(2u, z);
+    }
+    g.z = derivative(prev_ok, prev, here, next_ok, next, hm, hp);
+
+    return g;
+}
+
+fn world(p: vec3<u32>) -> vec3<f32> {
+    if (rectilinear()) {
+        return vec3<f32>(coords[p.x], coords[axis_offset(1u) + p.y], coords[axis_offset(2u) + p.z]);
+    }
+    return params.origin.xyz + vec3<f32>(p) * params.spacing.xyz;
+}
+fn write_edge(slot: u32, p0: vec3<u32>, p1: vec3<u32>) {
+    let iso = params.spacing.w;
+    let v0 = value(p0.x, p0.y, p0.z);
+    let v1 = value(p1.x, p1.y, p1.z);
+    let both_valid = is_valid(p0.x, p0.y, p0.z) && is_valid(p1.x, p1.y, p1.z);
+
+    if (!both_valid || (v0 < iso) == (v1 < iso)) {
+        edges[slot * 2u] = vec4<f32>(0.0);
+        edges[slot * 2u + 1u] = vec4<f32>(0.0);
+        return;
+    }
+
+    var t = 0.5;
+    if (abs(v1 - v0) > 1e-10) {
+        t = (iso - v0) / (v1 - v0);
+    }
+
+    let a = world(p0);
+    let b = world(p1);
+    let position = a + t * (b - a);
+
+    // Interpolate normal (negative gradient)
+    let g0 = gradient(p0.x, p0.y, p0.z);
+    let g1 = gradient(p1.x, p1.y, p1.z);
+    var normal = -(g0 + t * (g1 - g0));
+    let len = length(normal);
+    if (len > 1e-10) {
+        normal = normal / len;
+    }
+
+    edges[slot * 2u] = vec4<f32>(position, 1.0);
+    edges[slot * 2u + 1u] = vec4<f32>(normal, 0.0);
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let nx = params.dims.x;
+    let ny = params.dims.y;
+    let nz = params.dims.z;
+
+    let local = id.x + id.y * params.chunk.w;
+    if (local >= nx * ny * params.chunk.y) {
+        return;
+    }
+
+    let x = local % nx;
+    let y = (local / nx) % ny;
+    let z = params.chunk.x + local / (nx * ny);
+    let p = vec3<u32>(x, y, z);
+
+    // Edges along +x, +y, +z from this point
+    let slot = local * 3u;
+    if (x + 1u < nx) {
+        write_edge(slot, p, p + vec3<u32>(1u, 0u, 0u));
+    }
+    if (y + 1u < ny) {
+        write_edge(slot + 1u, p, p + vec3<u32>(0u, 1u, 0u));
+    }
+    if (z + 1u < nz) {
+        write_edge(slot + 2u, p, p + vec3<u32>(0u, 0u, 1u));
+    }
+
+    // Cube index of the cell at this corner, using the CPU corner order
+    if (x + 1u < nx && y + 1u < ny && z + 1u < nz) {
+        let iso = params.spacing.w;
+        var corners = array<f32, 8>(
+            value(x, y, z),
+            value(x + 1u, y, z),
+            value(x + 1u, y + 1u, z),
+            value(x, y + 1u, z),
+            value(x, y, z + 1u),
+            value(x + 1u, y, z + 1u),
+            value(x + 1u, y + 1u, z + 1u),
+            value(x, y + 1u, z + 1u),
+        );
+        let all_valid = is_valid(x, y, z) && is_valid(x + 1u, y, z)
+            && is_valid(x + 1u, y + 1u, z) && is_valid(x, y + 1u, z)
+            && is_valid(x, y, z + 1u) && is_valid(x + 1u, y, z + 1u)
+            && is_valid(x + 1u, y + 1u, z + 1u) && is_valid(x, y + 1u, z + 1u);
+        var cube_index = 0u;
+        for (var i = 0u; i < 8u; i = i + 1u) {
+            if (corners[i] < iso) {
+                cube_index = cube_index | (1u << i);
+            }
+        }
+        if (!all_valid) {
+            cube_index = SKIPPED_CELL;
+        }
+        cubes[local] = cube_index;
+    } else {
+        cubes[local] = 0u;
+    }
+}
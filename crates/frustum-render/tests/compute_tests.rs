@@ -0,0 +1,114 @@
+//! Extension 6 - GPU Geometry Generation Tests
+//!
+//! The compute-shader Marching Cubes must be an implementation optimization
+//! only (009-extensions.md): identical topology to the CPU extractor, with
+//! positions and normals equal within floating-point tolerance.
+
+use frustum_core::marching_cubes::test_support::sphere_volume;
+use frustum_core::{marching_cubes, Mesh, Volume};
+use frustum_render::marching_cubes_gpu;
+
+fn init_logger() {
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+fn assert_meshes_match(gpu: &Mesh, cpu: &Mesh) {
+    assert_eq!(gpu.triangle_count(), cpu.triangle_count(), "Triangle counts differ");
+    assert_eq!(gpu.vertex_count(), cpu.vertex_count(), "Vertex counts differ");
+    assert_eq!(gpu.indices, cpu.indices, "Topology differs");
+
+    for (i, (g, c)) in gpu.positions.iter().zip(&cpu.positions).enumerate() {
+        assert!((g - c).abs() < 1e-4, "Position {} differs: gpu {} vs cpu {}", i, g, c);
+    }
+
+    let gpu_normals = gpu.normals.as_ref().expect("GPU mesh has normals");
+    let cpu_normals = cpu.normals.as_ref().expect("CPU mesh has normals");
+    for (g, c) in gpu_normals.chunks(3).zip(cpu_normals.chunks(3)) {
+        let dot = g[0] * c[0] + g[1] * c[1] + g[2] * c[2];
+        assert!(dot > 0.999, "Normals differ: gpu {:?} vs cpu {:?}", g, c);
+    }
+}
+
+#[test]
+fn test_gpu_matches_cpu_sphere() {
+    init_logger();
+    let volume = sphere_volume([24, 24, 24], 0.7);
+
+    let cpu = marching_cubes(&volume, 0.0);
+    let gpu = marching_cubes_gpu(&volume, 0.0).expect("GPU marching cubes failed");
+
+    assert!(!cpu.indices.is_empty());
+    assert_meshes_match(&gpu, &cpu);
+}
+
+#[test]
+fn test_gpu_matches_cpu_anisotropic() {
+    init_logger();
+    // Non-cubic dimensions exercise the x/y/z indexing and per-axis spacing
+    let volume = sphere_volume([17, 11, 29], 0.55);
+
+    for iso in [-0.2, 0.0, 0.3] {
+        let cpu = marching_cubes(&volume, iso);
+        let gpu = marching_cubes_gpu(&volume, iso).expect("GPU marching cubes failed");
+        assert_meshes_match(&gpu, &cpu);
+    }
+}
+
+#[test]
+fn test_gpu_empty_volume() {
+    init_logger();
+    let volume = Volume::new(vec![1.0; 27], [3, 3, 3], [1.0; 3], [0.0; 3]);
+    let mesh = marching_cubes_gpu(&volume, 0.0).expect("GPU marching cubes failed");
+    assert!(mesh.positions.is_empty());
+    assert!(mesh.indices.is_empty());
+}
+
+#[test]
+fn test_gpu_matches_cpu_with_invalid_voxels() {
+    init_logger();
+    let volume = sphere_volume([20, 20, 20], 0.7);
+    let [nx, ny, _] = volume.dimensions;
+
+    // Masked-out block plus a few NaN voxels, both crossing the surface
+    let mask: Vec<bool> = (0..volume.values.len())
+        .map(|i| {
+            let (x, y, z) = (i % nx, (i / nx) % ny, i / (nx * ny));
+            !((14..20).contains(&x) && (6..14).contains(&y) && (6..14).contains(&z))
+        })
+        .collect();
+    let mut volume = volume.with_mask(mask);
+    for i in [3 + 10 * nx + 10 * nx * ny, 10 + 3 * nx + 9 * nx * ny] {
+        volume.values[i] = f32::NAN;
+    }
+
+    let cpu = marching_cubes(&volume, 0.0);
+    let gpu = marching_cubes_gpu(&volume, 0.0).expect("GPU marching cubes failed");
+    assert_meshes_match(&gpu, &cpu);
+}
+
+#[test]
+fn test_gpu_matches_cpu_rectilinear() {
+    init_logger();
+    // Cells refined towards the centre along x, uniform along y, coarser at the ends along z
+    let axis = |n: usize, f: fn(f32) -> f32| -> Vec<f32> {
+        (0..n).map(|i| f(i as f32 / (n - 1) as f32 * 2.0 - 1.0)).collect()
+    };
+    let xs = axis(21, |t| 0.5 * (t + t * t * t));
+    let ys = axis(15, |t| t);
+    let zs = axis(18, |t| t * (0.6 + 0.4 * t * t));
+
+    let mut values = Vec::with_capacity(xs.len() * ys.len() * zs.len());
+    for &z in &zs {
+        for &y in &ys {
+            for &x in &xs {
+                values.push((x * x + y * y + z * z).sqrt() - 0.65);
+            }
+        }
+    }
+    let volume = Volume::rectilinear(values, xs, ys, zs);
+
+    let cpu = marching_cubes(&volume, 0.0);
+    let gpu = marching_cubes_gpu(&volume, 0.0).expect("GPU marching cubes failed");
+    assert!(!cpu.indices.is_empty());
+    assert_meshes_match(&gpu, &cpu);
+}
@@ -3,11 +3,13 @@
 //! This module implements the non-negotiable renderer tests defined in
 //! 007-renderer-tests.md. A renderer is non-compliant if any of these fail.
 
+use frustum_core::marching_cubes::test_support::sphere_volume;
 use frustum_core::scene::{Bounds, Scene};
 use frustum_core::{
-    Axis, AxisBounds, AxisBundle, Camera, Light, Material, Mesh, PointCloud, Polyline,
-    ScalarMappedMaterial, SolidMaterial, TickSpec,
+    Axis, AxisBounds, AxisBundle, Camera, Light, Material, Mesh, NormalOptions, PointCloud, Polyline,
+    surface_nets, ExtractionReport, ScalarGrid, ScalarMappedMaterial, Shading, SolidMaterial, TickSpec, Volume,
 };
+use frustum_render::audit::{InvariantCategory, OverallStatus};
 use frustum_render::{render_to_png, render_with_audit, RenderConfig};
 
 fn init_logger() {
@@ -302,6 +304,37 @@ fn test_empty_geometry_lists() {
     assert!(result.is_ok(), "Empty geometry lists should render without error");
 }
 
+#[test]
+fn test_invalid_scene_rejected_before_drawing() {
+    init_logger();
+
+    // Index 5 is out of range; this used to panic while building vertices
+    let mesh = Mesh::new(
+        vec![
+            0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+        ],
+        vec![0, 1, 5],
+    )
+    .with_material("missing");
+
+    let scene = Scene::new(
+        test_camera(),
+        Bounds {
+            min: [-1.0, -1.0, -1.0],
+            max: [1.0, 1.0, 1.0],
+        },
+    )
+    .add_mesh(mesh);
+
+    let err = render_to_png(&scene, &test_config()).expect_err("invalid scene should not render");
+    let message = err.to_string();
+    assert!(message.contains("/elements/0/indices/2"), "{message}");
+    assert!(message.contains("/elements/0/material_id"), "{message}");
+    assert!(render_with_audit(&scene, &test_config()).is_err());
+}
+
 // ============================================================================
 // Camera & Projection Tests
 // ============================================================================
@@ -455,6 +488,38 @@ fn test_scalar_mapped_material_with_viridis() {
     assert!(result.is_ok(), "Scalar-mapped material with viridis should render");
 }
 
+#[test]
+fn test_scalar_mapped_contour_lines() {
+    init_logger();
+
+    // Concentric circles coloured by level through the line scalars
+    let n = 33;
+    let values: Vec<f32> = (0..n * n)
+        .map(|k| {
+            let (x, y) = ((k % n) as f32 / 16.0 - 1.0, (k / n) as f32 / 16.0 - 1.0);
+            (x * x + y * y).sqrt()
+        })
+        .collect();
+    let grid = ScalarGrid::new(values, [n, n], [1.0 / 16.0; 2], [-1.0, -1.0]);
+    let lines = grid.contours(&[0.25, 0.5, 0.75], 2.0);
+    assert_eq!(lines.len(), 3);
+
+    let mut scene = Scene::new(
+        Camera::perspective([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], 45.0),
+        Bounds {
+            min: [-1.0, -1.0, -1.0],
+            max: [1.0, 1.0, 1.0],
+        },
+    )
+    .add_material(Material::ScalarMapped(ScalarMappedMaterial::new("levels", "viridis", [0.25, 0.75])));
+    for line in lines {
+        scene = scene.add_polyline(line.polyline.with_material("levels"));
+    }
+
+    let result = render_to_png(&scene, &test_config());
+    assert!(result.is_ok(), "Scalar-mapped contour lines should render");
+}
+
 #[test]
 fn test_clamp_out_of_range_scalars() {
     init_logger();
@@ -692,6 +757,50 @@ fn test_light_present_lambertian_shading() {
     assert!(result.is_ok(), "Lambertian shading should work");
 }
 
+#[test]
+fn test_shading_mode_honoured() {
+    init_logger();
+
+    // Four-sided pyramid without stored normals
+    #[rustfmt::skip]
+    let pyramid = Mesh::new(
+        vec![
+            -0.8, -0.8, 0.0,
+             0.8, -0.8, 0.0,
+             0.8,  0.8, 0.0,
+            -0.8,  0.8, 0.0,
+             0.0,  0.0, 0.6,
+        ],
+        vec![0, 1, 4, 1, 2, 4, 2, 3, 4, 3, 0, 4],
+    );
+
+    let render = |mesh: Mesh| {
+        let scene = Scene::new(
+            Camera::perspective([0.5, -1.0, 3.0], [0.0, 0.0, 0.2], 45.0),
+            Bounds {
+                min: [-1.0, -1.0, -1.0],
+                max: [1.0, 1.0, 1.0],
+            },
+        )
+        .add_mesh(mesh)
+        .with_light(Light::new([0.3, -0.5, 1.0], 1.0));
+        let png = render_to_png(&scene, &test_config()).unwrap();
+        image::load_from_memory(&png).unwrap().to_rgba8()
+    };
+
+    let smooth = render(pyramid.clone().with_shading(Shading::Smooth));
+    let flat = render(pyramid.clone().with_shading(Shading::Flat));
+    assert_ne!(smooth.as_raw(), flat.as_raw(), "Flat and smooth shading should differ");
+
+    // Smooth shading generates the same normals as compute_normals
+    let precomputed = render(pyramid.compute_normals(&NormalOptions::new()).with_shading(Shading::Smooth));
+    assert_eq!(smooth.as_raw(), precomputed.as_raw());
+
+    // Flat shading ignores stored normals
+    let flat_with_normals = render(pyramid.compute_normals(&NormalOptions::new()).with_shading(Shading::Flat));
+    assert_eq!(flat.as_raw(), flat_with_normals.as_raw());
+}
+
 #[test]
 fn test_points_render_unlit() {
     init_logger();
@@ -925,7 +1034,8 @@ fn test_audit_bundle_generation() {
     assert!(png_data.starts_with(&[0x89, 0x50, 0x4E, 0x47]));
 
     // Audit should contain valid data
-    assert!(!audit.metadata.scene_hash.is_empty());
+    assert_eq!(audit.metadata.scene_hash, scene.content_hash());
+    assert_eq!(audit.metadata.schema_version, scene.version);
     assert_eq!(audit.metadata.resolution, [config.width, config.height]);
     assert!(audit.metadata.primitive_counts.total_triangles > 0);
 
@@ -958,6 +1068,44 @@ fn test_invariant_checking() {
     );
 }
 
+#[test]
+fn test_extraction_report_in_audit() {
+    init_logger();
+
+    let n = 16;
+    let volume = sphere_volume([n; 3], 0.6);
+    // Mask out the +x quarter of the grid, cutting a hole in the sphere
+    let mask = (0..n * n * n).map(|i| i % n < 12).collect();
+    let masked = volume.clone().with_mask(mask);
+
+    let audit_for = |volume: &Volume| {
+        let scene = Scene::new(test_camera(), Bounds { min: [-1.0; 3], max: [1.0; 3] })
+            .add_mesh(surface_nets(volume, 0.0));
+        let (_, mut audit) = render_with_audit(&scene, &test_config()).unwrap();
+        audit.record_extraction(&ExtractionReport::for_volume(volume));
+        audit
+    };
+
+    let clean = audit_for(&volume);
+    assert!(clean.invariants.warnings.iter().all(|w| !w.message.contains("extraction")));
+    assert!(
+        clean.invariants.notes.iter().any(|n| n == "Isosurface extraction used all 3375 cells"),
+        "{:?}",
+        clean.invariants.notes
+    );
+
+    let holed = audit_for(&masked);
+    let warning = holed
+        .invariants
+        .warnings
+        .iter()
+        .find(|w| w.message.contains("extraction"))
+        .expect("skipped cells are a warning");
+    assert_eq!(warning.category, InvariantCategory::Geometry);
+    assert_eq!(warning.message, "Isosurface extraction skipped 900 of 3375 cells touching invalid voxels");
+    assert_ne!(holed.invariants.overall, OverallStatus::Pass);
+}
+
 // ============================================================================
 // Colormap Tests
 // ============================================================================